@@ -0,0 +1,81 @@
+use rand_core::{RngCore, OsRng};
+
+use zeroize::Zeroizing;
+
+use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, edwards::EdwardsPoint};
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+
+use monero_serai::{
+  random_scalar,
+  ringct::{EncryptedAmount, RctBase, RctPrunable, RctSignatures},
+  transaction::{Input, Output, Timelock, TransactionPrefix, Transaction},
+  wallet::{extra::ExtraField, ViewPair, Scanner},
+};
+
+// The amount of transactions scanned per iteration, and the amount of outputs in each
+const TRANSACTIONS: usize = 100;
+const OUTPUTS: usize = 16;
+
+fn random_point() -> EdwardsPoint {
+  &random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE
+}
+
+// Create a transaction whose outputs aren't to the scanning wallet, as most scanned outputs aren't,
+// optionally with an additional key per output
+fn transaction(additional: bool) -> Transaction {
+  let mut extra = vec![];
+  ExtraField::PublicKey(random_point()).write(&mut extra).unwrap();
+  if additional {
+    ExtraField::PublicKeys((0 .. OUTPUTS).map(|_| random_point()).collect())
+      .write(&mut extra)
+      .unwrap();
+  }
+
+  Transaction {
+    prefix: TransactionPrefix {
+      version: 2,
+      timelock: Timelock::None,
+      inputs: vec![Input::ToKey { amount: None, key_offsets: vec![], key_image: random_point() }],
+      outputs: (0 .. OUTPUTS)
+        .map(|_| Output {
+          amount: None,
+          key: random_point().compress(),
+          view_tag: Some(OsRng.next_u32().to_le_bytes()[0]),
+        })
+        .collect(),
+      extra,
+    },
+    signatures: vec![],
+    rct_signatures: RctSignatures {
+      base: RctBase {
+        fee: 0,
+        pseudo_outs: vec![],
+        encrypted_amounts: (0 .. OUTPUTS)
+          .map(|_| EncryptedAmount::Compact { amount: [0; 8] })
+          .collect(),
+        commitments: (0 .. OUTPUTS).map(|_| random_point()).collect(),
+      },
+      prunable: RctPrunable::Null,
+    },
+  }
+}
+
+fn scan(c: &mut Criterion) {
+  let pair = ViewPair::new(random_point(), Zeroizing::new(random_scalar(&mut OsRng)));
+  let mut scanner = Scanner::from_view(pair, Some(Default::default()));
+
+  for (name, additional) in [("scan", false), ("scan with additional keys", true)] {
+    let txs = (0 .. TRANSACTIONS).map(|_| transaction(additional)).collect::<Vec<_>>();
+    c.bench_function(name, |b| {
+      b.iter(|| {
+        for tx in &txs {
+          black_box(scanner.scan_transaction(tx));
+        }
+      })
+    });
+  }
+}
+
+criterion_group!(benches, scan);
+criterion_main!(benches);
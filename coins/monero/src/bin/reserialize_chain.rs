@@ -1,9 +1,19 @@
 #[cfg(feature = "binaries")]
 mod binaries {
-  pub(crate) use std::sync::Arc;
+  pub(crate) use core::future::Future;
+  pub(crate) use std::{
+    sync::{
+      Arc,
+      atomic::{AtomicBool, Ordering},
+    },
+    collections::HashSet,
+  };
 
   pub(crate) use curve25519_dalek::{scalar::Scalar, edwards::EdwardsPoint};
 
+  pub(crate) use rand_core::{RngCore, CryptoRng, SeedableRng, OsRng};
+  pub(crate) use rand_chacha::ChaCha20Rng;
+
   pub(crate) use multiexp::BatchVerifier;
 
   pub(crate) use serde::Deserialize;
@@ -21,41 +31,387 @@ mod binaries {
 
   pub(crate) use tokio::task::JoinHandle;
 
-  pub(crate) async fn check_block(rpc: Arc<Rpc<HttpRpc>>, block_i: usize) {
-    let hash = loop {
-      match rpc.get_block_hash(block_i).await {
-        Ok(hash) => break hash,
-        Err(RpcError::ConnectionError(e)) => {
-          println!("get_block_hash ConnectionError: {e}");
-          continue;
-        }
-        Err(e) => panic!("couldn't get block {block_i}'s hash: {e:?}"),
+  /// Whether to emit a JSON line for each verified block to stdout.
+  ///
+  /// When set, human-readable output is written to stderr, leaving stdout solely JSON.
+  pub(crate) static EMIT_JSON: AtomicBool = AtomicBool::new(false);
+
+  /// Print human-readable output, to stderr if emitting JSON and to stdout otherwise.
+  macro_rules! human {
+    ($($arg: tt)*) => {
+      if EMIT_JSON.load(Ordering::Relaxed) {
+        eprintln!($($arg)*);
+      } else {
+        println!($($arg)*);
       }
     };
+  }
+
+  /// The prefix of the error returned when get_outs responds with a non-OK status.
+  pub(crate) const GET_OUTS_STATUS: &str = "get_outs status: ";
+
+  /// The statuses a node responds to get_outs with when refusing to return the outputs.
+  ///
+  /// Restricted nodes refuse to return more than a limited amount of outputs per request, and
+  /// nodes requiring RPC payment refuse to return any once the client's credits are exhausted.
+  pub(crate) const RATE_LIMITED_STATUSES: [&str; 2] =
+    ["Too many outs requested", "PAYMENT REQUIRED"];
+
+  /// Fetch the specified outputs, retrying on connection errors.
+  ///
+  /// If the node refuses to return the outputs, as restricted nodes do when rate limiting, the
+  /// error, with the status the node responded with, is returned.
+  pub(crate) async fn get_outs(
+    rpc: &Rpc<HttpRpc>,
+    amount: u64,
+    indexes: Vec<u64>,
+  ) -> Result<Vec<[EdwardsPoint; 2]>, RpcError> {
+    #[derive(Deserialize, Debug)]
+    struct Out {
+      key: String,
+      mask: String,
+    }
 
-    // TODO: Grab the JSON to also check it was deserialized correctly
     #[derive(Deserialize, Debug)]
-    struct BlockResponse {
-      blob: String,
+    struct Outs {
+      status: String,
+      // Omitted by nodes refusing to return the outputs
+      #[serde(default)]
+      outs: Vec<Out>,
     }
-    let res: BlockResponse = loop {
-      match rpc.json_rpc_call("get_block", Some(json!({ "hash": hex::encode(hash) }))).await {
-        Ok(res) => break res,
+
+    let outs: Outs = loop {
+      match rpc
+        .rpc_call(
+          "get_outs",
+          Some(json!({
+            "get_txid": true,
+            "outputs": indexes.iter().map(|o| json!({
+              "amount": amount,
+              "index": o
+            })).collect::<Vec<_>>()
+          })),
+        )
+        .await
+      {
+        Ok(outs) => break outs,
         Err(RpcError::ConnectionError(e)) => {
-          println!("get_block ConnectionError: {e}");
+          human!("get_outs ConnectionError: {e}");
           continue;
         }
-        Err(e) => panic!("couldn't get block {block_i} via block.hash(): {e:?}"),
+        Err(e) => Err(e)?,
       }
     };
+    if outs.status != "OK" {
+      Err(RpcError::InvalidNode(format!("{GET_OUTS_STATUS}{}", outs.status)))?;
+    }
+
+    let rpc_point = |point: &str| {
+      decompress_point(
+        hex::decode(point)
+          .expect("invalid hex for ring member")
+          .try_into()
+          .expect("invalid point len for ring member"),
+      )
+      .expect("invalid point for ring member")
+    };
 
-    let blob = hex::decode(res.blob).expect("node returned non-hex block");
-    let block = Block::read(&mut blob.as_slice())
-      .unwrap_or_else(|e| panic!("couldn't deserialize block {block_i}: {e}"));
-    assert_eq!(block.hash(), hash, "hash differs");
-    assert_eq!(block.serialize(), blob, "serialization differs");
+    Ok(
+      outs
+        .outs
+        .iter()
+        .map(|out| {
+          let mask = rpc_point(&out.mask);
+          if amount != 0 {
+            assert_eq!(mask, Commitment::new(Scalar::from(1u8), amount).calculate());
+          }
+          [rpc_point(&out.key), mask]
+        })
+        .collect(),
+    )
+  }
+
+  /// Whether an error from get_outs was due to the node refusing to return the outputs.
+  ///
+  /// Solely errors for one of the `RATE_LIMITED_STATUSES` are considered rate limiting. Any other
+  /// error, including other invalid responses, isn't.
+  pub(crate) fn rate_limited(e: &RpcError) -> bool {
+    let RpcError::InvalidNode(message) = e else { return false };
+    message
+      .strip_prefix(GET_OUTS_STATUS)
+      .is_some_and(|status| RATE_LIMITED_STATUSES.contains(&status))
+  }
+
+  /// The positions of the ring members to sample, at the specified percentage, from a ring of the
+  /// specified length.
+  ///
+  /// At least one ring member is sampled if the rate is non-zero.
+  pub(crate) fn sampled_members(ring_len: usize, rate: u8) -> Vec<usize> {
+    let amount = (ring_len * usize::from(rate.min(100))).div_ceil(100);
+    (0 .. amount).map(|i| (i * ring_len) / amount).collect()
+  }
+
+  /// The result of checking a CLSAG's ring.
+  #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+  pub(crate) enum RingCheck {
+    /// The entire ring was fetched and the CLSAG was verified.
+    Verified,
+    /// The node was rate limiting, so the CLSAG wasn't verified.
+    ///
+    /// Solely the sampled ring members were fetched, having their points decompressed. If the
+    /// node was still rate limiting, no ring members will have been checked.
+    Sampled { sampled: usize },
+  }
+
+  /// Fetch a ring and verify it with the provided function.
+  ///
+  /// If the node is rate limiting, this gracefully degrades to solely decompressing a sample of
+  /// the ring's members.
+  pub(crate) async fn check_ring<F: Future<Output = Result<Vec<[EdwardsPoint; 2]>, RpcError>>>(
+    mut get_outs: impl FnMut(Vec<u64>) -> F,
+    indexes: &[u64],
+    sample_rate: u8,
+    verify: impl FnOnce(&[[EdwardsPoint; 2]]),
+  ) -> RingCheck {
+    let handle = |res| match res {
+      Ok(outs) => Some(outs),
+      Err(e) if rate_limited(&e) => None,
+      Err(e) => panic!("couldn't connect to RPC to get outs: {e:?}"),
+    };
+
+    if let Some(ring) = handle(get_outs(indexes.to_vec()).await) {
+      verify(&ring);
+      return RingCheck::Verified;
+    }
+
+    let sampled = sampled_members(indexes.len(), sample_rate)
+      .into_iter()
+      .map(|i| indexes[i])
+      .collect::<Vec<_>>();
+    if sampled.is_empty() {
+      return RingCheck::Sampled { sampled: 0 };
+    }
+    RingCheck::Sampled { sampled: handle(get_outs(sampled).await).map_or(0, |outs| outs.len()) }
+  }
+
+  /// Parse the percentage of ring members to sample when a node is rate limiting, defaulting to
+  /// 10.
+  pub(crate) fn ring_sample_rate(arg: Option<&str>) -> Result<u8, String> {
+    let Some(arg) = arg else { return Ok(10) };
+    let rate = arg.parse::<u8>().map_err(|e| format!("invalid ring sample rate: {e}"))?;
+    if rate > 100 {
+      Err("ring sample rate is a percentage and must be at most 100".to_string())?;
+    }
+    Ok(rate)
+  }
+
+  /// The ring length mandated by consensus for transactions within the mainnet block of the
+  /// specified height, if one is mandated.
+  ///
+  /// Hard fork 8 fixed the ring length to 11, which hard fork 15 increased to 16. Prior, solely a
+  /// minimum ring length was enforced.
+  pub(crate) fn mandated_ring_len(height: usize) -> Option<usize> {
+    if height >= 2_688_888 {
+      Some(16)
+    } else if height >= 1_685_555 {
+      Some(11)
+    } else {
+      None
+    }
+  }
+
+  /// Check a ring's length is the length mandated for the block's height, if one is mandated.
+  pub(crate) fn check_ring_len(height: usize, ring_len: usize) -> Result<(), String> {
+    match mandated_ring_len(height) {
+      Some(mandated) if mandated != ring_len => {
+        Err(format!("ring length of {ring_len} when {mandated} is mandated at height {height}"))
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// Parse a filter of key images, one hex-encoded key image per line.
+  ///
+  /// Empty lines are ignored.
+  pub(crate) fn key_image_filter(contents: &str) -> Result<HashSet<[u8; 32]>, String> {
+    let mut filter = HashSet::new();
+    for line in contents.lines().map(str::trim).filter(|line| !line.is_empty()) {
+      let key_image = hex::decode(line)
+        .ok()
+        .and_then(|key_image| <[u8; 32]>::try_from(key_image).ok())
+        .ok_or_else(|| format!("invalid key image in filter: {line}"))?;
+      filter.insert(key_image);
+    }
+    Ok(filter)
+  }
+
+  /// Whether a transaction should have its proofs verified under the specified filter.
+  ///
+  /// Without a filter, every transaction is verified. With one, solely transactions spending a key
+  /// image within the filter are.
+  pub(crate) fn selected(filter: Option<&HashSet<[u8; 32]>>, tx: &Transaction) -> bool {
+    let Some(filter) = filter else { return true };
+    tx.prefix.inputs.iter().any(|input| match input {
+      Input::Gen(_) => false,
+      Input::ToKey { key_image, .. } => filter.contains(&key_image.compress().to_bytes()),
+    })
+  }
+
+  /// Why an input couldn't be verified with a CLSAG.
+  #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+  pub(crate) enum ClsagInputError {
+    /// The input was a coinbase generator input, which has no ring.
+    Gen,
+    /// The transaction has more CLSAGs than inputs.
+    Missing,
+  }
+
+  impl core::fmt::Display for ClsagInputError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      match self {
+        ClsagInputError::Gen => write!(f, "input was a generator input"),
+        ClsagInputError::Missing => write!(f, "transaction had more CLSAGs than inputs"),
+      }
+    }
+  }
+
+  /// The amount, ring member indexes, and key image of the specified input, which should be
+  /// verified with the transaction's `i`th CLSAG.
+  pub(crate) fn clsag_input(
+    inputs: &[Input],
+    i: usize,
+  ) -> Result<(Option<u64>, Vec<u64>, EdwardsPoint), ClsagInputError> {
+    match inputs.get(i).ok_or(ClsagInputError::Missing)? {
+      Input::Gen(_) => Err(ClsagInputError::Gen),
+      Input::ToKey { amount, key_offsets, key_image } => {
+        let mut running_sum = 0;
+        let mut actual_indexes = vec![];
+        for offset in key_offsets {
+          running_sum += offset;
+          actual_indexes.push(running_sum);
+        }
+        Ok((*amount, actual_indexes, *key_image))
+      }
+    }
+  }
+
+  /// Parse the seed for the RNG used during verification, if one was specified.
+  pub(crate) fn verification_seed(arg: Option<&str>) -> Result<Option<[u8; 32]>, String> {
+    let Some(arg) = arg else { return Ok(None) };
+    hex::decode(arg)
+      .ok()
+      .and_then(|seed| <[u8; 32]>::try_from(seed).ok())
+      .map(Some)
+      .ok_or_else(|| "verification seed must be 32 hex-encoded bytes".to_string())
+  }
+
+  /// The RNG used when verifying the specified block.
+  ///
+  /// With a seed, this is deterministic, using a distinct stream for each block so blocks may be
+  /// verified in any order. Without one, this is seeded by the OS.
+  pub(crate) fn block_rng(seed: Option<[u8; 32]>, block_i: usize) -> ChaCha20Rng {
+    let Some(seed) = seed else { return ChaCha20Rng::from_rng(OsRng).unwrap() };
+    let mut rng = ChaCha20Rng::from_seed(seed);
+    rng.set_stream(u64::try_from(block_i).unwrap());
+    rng
+  }
+
+  /// Fetch the block at the specified height, returning it and its serialization.
+  ///
+  /// The block is fetched by its hash, so if the node reorganizes between fetching the hash and
+  /// the block, the block may no longer be at this height (or may no longer be available at all).
+  /// The hash is fetched again after the block, and if it changed, the fetch is retried.
+  pub(crate) async fn fetch_block<
+    H: Future<Output = Result<[u8; 32], RpcError>>,
+    B: Future<Output = Result<Vec<u8>, RpcError>>,
+  >(
+    block_i: usize,
+    mut get_hash: impl FnMut() -> H,
+    mut get_blob: impl FnMut([u8; 32]) -> B,
+  ) -> (Block, Vec<u8>) {
+    async fn hash<H: Future<Output = Result<[u8; 32], RpcError>>>(
+      block_i: usize,
+      get_hash: &mut impl FnMut() -> H,
+    ) -> [u8; 32] {
+      loop {
+        match get_hash().await {
+          Ok(hash) => break hash,
+          Err(RpcError::ConnectionError(e)) => {
+            human!("get_block_hash ConnectionError: {e}");
+            continue;
+          }
+          Err(e) => panic!("couldn't get block {block_i}'s hash: {e:?}"),
+        }
+      }
+    }
+
+    loop {
+      let expected = hash(block_i, &mut get_hash).await;
+      let blob = loop {
+        match get_blob(expected).await {
+          Err(RpcError::ConnectionError(e)) => {
+            human!("get_block ConnectionError: {e}");
+            continue;
+          }
+          res => break res,
+        }
+      };
+
+      // If the block at this height changed, the node reorganized while we were fetching it
+      if hash(block_i, &mut get_hash).await != expected {
+        human!("block {block_i} was reorganized while being fetched, retrying");
+        continue;
+      }
+
+      let blob =
+        blob.unwrap_or_else(|e| panic!("couldn't get block {block_i} via block.hash(): {e:?}"));
+      let block = Block::read(&mut blob.as_slice())
+        .unwrap_or_else(|e| panic!("couldn't deserialize block {block_i}: {e}"));
+      assert_eq!(block.hash(), expected, "hash differs");
+      break (block, blob);
+    }
+  }
+
+  pub(crate) async fn check_block<R: Send + RngCore + CryptoRng>(
+    rpc: Arc<Rpc<HttpRpc>>,
+    block_i: usize,
+    ring_sample_rate: u8,
+    ring_len_check: bool,
+    filter: Option<Arc<HashSet<[u8; 32]>>>,
+    mut rng: R,
+  ) {
+    let (block, blob) = fetch_block(
+      block_i,
+      || rpc.get_block_hash(block_i),
+      |hash| {
+        let rpc = &rpc;
+        async move {
+          // TODO: Grab the JSON to also check it was deserialized correctly
+          #[derive(Deserialize, Debug)]
+          struct BlockResponse {
+            blob: String,
+          }
+          let res: BlockResponse =
+            rpc.json_rpc_call("get_block", Some(json!({ "hash": hex::encode(hash) }))).await?;
+          hex::decode(res.blob)
+            .map_err(|_| RpcError::InvalidNode("node returned non-hex block".to_string()))
+        }
+      },
+    )
+    .await;
+    if let Err(mismatch) = block.serialization_matches(&blob) {
+      panic!("block {block_i}: {mismatch}");
+    }
+    // Bound before the block's transactions are consumed, for the JSON line emitted at the end
+    let hash = block.hash();
 
     let txs_len = 1 + block.txs.len();
+    // The amount of CLSAGs which weren't verified due to rate limiting
+    let mut skipped = 0;
+    // The amount of CLSAGs whose rings didn't have the mandated length
+    let mut anomalies = 0;
+    // The amount of CLSAGs which couldn't be verified as their inputs were invalid
+    let mut invalid = 0;
 
     if !block.txs.is_empty() {
       #[derive(Deserialize, Debug)]
@@ -85,7 +441,7 @@ mod binaries {
           {
             Ok(txs) => break txs,
             Err(RpcError::ConnectionError(e)) => {
-              println!("get_transactions ConnectionError: {e}");
+              human!("get_transactions ConnectionError: {e}");
               continue;
             }
             Err(e) => panic!("couldn't call get_transactions: {e:?}"),
@@ -121,6 +477,11 @@ mod binaries {
           continue;
         }
 
+        // Solely verify the proofs of transactions selected by the filter
+        if !selected(filter.as_deref(), &tx) {
+          continue;
+        }
+
         let sig_hash = tx.signature_hash();
         // Verify all proofs we support proving for
         // This is due to having debug_asserts calling verify within their proving, and CLSAG
@@ -133,7 +494,7 @@ mod binaries {
           RctPrunable::MlsagBorromean { .. } => {}
           RctPrunable::MlsagBulletproofs { bulletproofs, .. } => {
             assert!(bulletproofs.batch_verify(
-              &mut rand_core::OsRng,
+              &mut rng,
               &mut batch,
               (),
               &tx.rct_signatures.base.commitments
@@ -141,95 +502,50 @@ mod binaries {
           }
           RctPrunable::Clsag { bulletproofs, clsags, pseudo_outs } => {
             assert!(bulletproofs.batch_verify(
-              &mut rand_core::OsRng,
+              &mut rng,
               &mut batch,
               (),
               &tx.rct_signatures.base.commitments
             ));
 
             for (i, clsag) in clsags.into_iter().enumerate() {
-              let (amount, key_offsets, image) = match &tx.prefix.inputs[i] {
-                Input::Gen(_) => panic!("Input::Gen"),
-                Input::ToKey { amount, key_offsets, key_image } => (amount, key_offsets, key_image),
-              };
-
-              let mut running_sum = 0;
-              let mut actual_indexes = vec![];
-              for offset in key_offsets {
-                running_sum += offset;
-                actual_indexes.push(running_sum);
-              }
-
-              async fn get_outs(
-                rpc: &Rpc<HttpRpc>,
-                amount: u64,
-                indexes: &[u64],
-              ) -> Vec<[EdwardsPoint; 2]> {
-                #[derive(Deserialize, Debug)]
-                struct Out {
-                  key: String,
-                  mask: String,
+              let (amount, actual_indexes, image) = match clsag_input(&tx.prefix.inputs, i) {
+                Ok(input) => input,
+                Err(e) => {
+                  human!(
+                    "couldn't verify the CLSAG for input {i} of {} in block {block_i}: {e}",
+                    hex::encode(tx_hash)
+                  );
+                  invalid += 1;
+                  continue;
                 }
+              };
 
-                #[derive(Deserialize, Debug)]
-                struct Outs {
-                  outs: Vec<Out>,
+              let ring_len = actual_indexes.len();
+              if ring_len_check {
+                if let Err(e) = check_ring_len(block_i, ring_len) {
+                  human!(
+                    "anomaly in input {i} of {} in block {block_i}: {e}",
+                    hex::encode(tx_hash)
+                  );
+                  anomalies += 1;
                 }
-
-                let outs: Outs = loop {
-                  match rpc
-                    .rpc_call(
-                      "get_outs",
-                      Some(json!({
-                        "get_txid": true,
-                        "outputs": indexes.iter().map(|o| json!({
-                          "amount": amount,
-                          "index": o
-                        })).collect::<Vec<_>>()
-                      })),
-                    )
-                    .await
-                  {
-                    Ok(outs) => break outs,
-                    Err(RpcError::ConnectionError(e)) => {
-                      println!("get_outs ConnectionError: {e}");
-                      continue;
-                    }
-                    Err(e) => panic!("couldn't connect to RPC to get outs: {e:?}"),
-                  }
-                };
-
-                let rpc_point = |point: &str| {
-                  decompress_point(
-                    hex::decode(point)
-                      .expect("invalid hex for ring member")
-                      .try_into()
-                      .expect("invalid point len for ring member"),
-                  )
-                  .expect("invalid point for ring member")
-                };
-
-                outs
-                  .outs
-                  .iter()
-                  .map(|out| {
-                    let mask = rpc_point(&out.mask);
-                    if amount != 0 {
-                      assert_eq!(mask, Commitment::new(Scalar::from(1u8), amount).calculate());
-                    }
-                    [rpc_point(&out.key), mask]
-                  })
-                  .collect()
               }
-
-              clsag
-                .verify(
-                  &get_outs(&rpc, amount.unwrap_or(0), &actual_indexes).await,
-                  image,
-                  &pseudo_outs[i],
-                  &sig_hash,
-                )
-                .unwrap();
+              let check = check_ring(
+                |indexes| get_outs(&rpc, amount.unwrap_or(0), indexes),
+                &actual_indexes,
+                ring_sample_rate,
+                |ring| clsag.verify(ring, &image, &pseudo_outs[i], &sig_hash).unwrap(),
+              )
+              .await;
+              if let RingCheck::Sampled { sampled } = check {
+                human!(
+                  "skipped verifying the CLSAG for input {i} of {} in block {block_i} as get_outs \
+                   was rate limited, decompressing {sampled}/{ring_len} sampled ring members",
+                  hex::encode(tx_hash),
+                );
+                skipped += 1;
+              }
             }
           }
         }
@@ -237,7 +553,398 @@ mod binaries {
       assert!(batch.verify_vartime());
     }
 
-    println!("Deserialized, hashed, and reserialized {block_i} with {txs_len} TXs");
+    if skipped != 0 {
+      human!("didn't verify {skipped} CLSAGs in block {block_i} due to rate limiting");
+    }
+    if anomalies != 0 {
+      human!("{anomalies} CLSAGs in block {block_i} didn't have the mandated ring length");
+    }
+    if invalid != 0 {
+      human!("{invalid} CLSAGs in block {block_i} were for invalid inputs");
+    }
+    human!("Deserialized, hashed, and reserialized {block_i} with {txs_len} TXs");
+    if EMIT_JSON.load(Ordering::Relaxed) {
+      println!("{}", block_json(block_i, hash, txs_len));
+    }
+  }
+
+  /// The JSON line emitted for a verified block.
+  ///
+  /// `tx_count` includes the miner transaction.
+  pub(crate) fn block_json(height: usize, hash: [u8; 32], tx_count: usize) -> String {
+    json!({
+      "height": height,
+      "hash": hex::encode(hash),
+      "tx_count": tx_count,
+      "verified": true,
+    })
+    .to_string()
+  }
+
+  /// Return the nodes which respond to `get_height`, printing a warning for each which doesn't.
+  ///
+  /// Returns an error listing every node, and why it failed, if none responded.
+  pub(crate) async fn responsive_nodes(nodes: Vec<String>) -> Result<Vec<String>, String> {
+    if nodes.is_empty() {
+      Err("no nodes were specified".to_string())?;
+    }
+
+    let mut responsive = vec![];
+    let mut failures = vec![];
+    for node in nodes {
+      let res = match HttpRpc::new(node.clone()).await {
+        Ok(rpc) => rpc.get_height().await.map(|_| ()),
+        Err(e) => Err(e),
+      };
+      match res {
+        Ok(()) => responsive.push(node),
+        Err(e) => {
+          human!("warning: node {node} didn't respond to get_height: {e:?}");
+          failures.push(format!("{node} ({e:?})"));
+        }
+      }
+    }
+
+    if responsive.is_empty() {
+      Err(format!("none of the nodes responded to get_height: {}", failures.join(", ")))?;
+    }
+    Ok(responsive)
+  }
+
+  /// Parse how many blocks to work on at once, defaulting to 8.
+  ///
+  /// Parallelism in excess of the amount of nodes solely oversubscribes the same nodes, so a
+  /// warning is printed if it was explicitly specified as such.
+  pub(crate) fn async_parallelism(arg: Option<&str>, nodes: usize) -> Result<usize, String> {
+    let Some(arg) = arg else { return Ok(8) };
+    let parallelism =
+      arg.parse::<usize>().map_err(|e| format!("invalid parallelism argument: {e}"))?;
+    if parallelism == 0 {
+      Err("parallelism must be at least 1".to_string())?;
+    }
+    if parallelism > nodes {
+      human!(
+        "warning: parallelism of {parallelism} exceeds the amount of nodes ({nodes}), \
+         oversubscribing them"
+      );
+    }
+    Ok(parallelism)
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_image_filter() {
+      let filter = key_image_filter(&format!("{}\n\n  {}\n", "01".repeat(32), "02".repeat(32)));
+      assert_eq!(filter, Ok(HashSet::from([[1; 32], [2; 32]])));
+      assert!(key_image_filter("01").is_err());
+      assert!(key_image_filter(&"zz".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn test_selected() {
+      use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+      use monero_serai::{
+        transaction::{Timelock, TransactionPrefix},
+        ringct::{RctBase, RctSignatures},
+      };
+
+      let tx = |key_image| Transaction {
+        prefix: TransactionPrefix {
+          version: 2,
+          timelock: Timelock::None,
+          inputs: vec![Input::ToKey { amount: None, key_offsets: vec![1], key_image }],
+          outputs: vec![],
+          extra: vec![],
+        },
+        signatures: vec![],
+        rct_signatures: RctSignatures {
+          base: RctBase {
+            fee: 0,
+            pseudo_outs: vec![],
+            encrypted_amounts: vec![],
+            commitments: vec![],
+          },
+          prunable: RctPrunable::Null,
+        },
+      };
+      let matching = tx(ED25519_BASEPOINT_POINT);
+      let other = tx(ED25519_BASEPOINT_POINT + ED25519_BASEPOINT_POINT);
+
+      // Without a filter, every transaction is verified
+      assert!(selected(None, &matching));
+      assert!(selected(None, &other));
+
+      // With a filter, solely the matching transaction is
+      let filter = HashSet::from([ED25519_BASEPOINT_POINT.compress().to_bytes()]);
+      assert!(selected(Some(&filter), &matching));
+      assert!(!selected(Some(&filter), &other));
+    }
+
+    #[test]
+    fn test_clsag_input() {
+      use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+
+      // The key offsets are converted to absolute indexes
+      let to_key = [Input::ToKey {
+        amount: None,
+        key_offsets: vec![3, 1, 2],
+        key_image: ED25519_BASEPOINT_POINT,
+      }];
+      assert_eq!(clsag_input(&to_key, 0), Ok((None, vec![3, 4, 6], ED25519_BASEPOINT_POINT)));
+      assert_eq!(clsag_input(&to_key, 1), Err(ClsagInputError::Missing));
+
+      // A generator input is a recoverable error, not a panic
+      assert_eq!(clsag_input(&[Input::Gen(1)], 0), Err(ClsagInputError::Gen));
+    }
+
+    #[test]
+    fn test_verification_seed() {
+      assert_eq!(verification_seed(None), Ok(None));
+      assert_eq!(verification_seed(Some(&"01".repeat(32))), Ok(Some([1; 32])));
+      assert!(verification_seed(Some("01")).is_err());
+      assert!(verification_seed(Some(&"zz".repeat(32))).is_err());
+    }
+
+    #[test]
+    fn test_block_rng() {
+      use monero_serai::ringct::bulletproofs::Bulletproofs;
+
+      let outputs = [Commitment::new(Scalar::ONE, 1), Commitment::new(Scalar::ONE, 2)];
+      let bp = Bulletproofs::prove(&mut OsRng, &outputs, true).unwrap();
+      let commitments = outputs.iter().map(Commitment::calculate).collect::<Vec<_>>();
+
+      // Verify the proof, returning the result and the RNG's next output
+      let verify = |mut rng: ChaCha20Rng| {
+        let mut batch = BatchVerifier::new(1);
+        let verified = bp.batch_verify(&mut rng, &mut batch, (), &commitments);
+        (verified && batch.verify_vartime(), rng.next_u64())
+      };
+
+      // Verifying with the same seed behaves identically
+      let seed = Some([0xaa; 32]);
+      let first = verify(block_rng(seed, 5));
+      assert!(first.0);
+      assert_eq!(verify(block_rng(seed, 5)), first);
+
+      // Each block has a distinct stream, as does each seed
+      assert!(block_rng(seed, 5).next_u64() != block_rng(seed, 6).next_u64());
+      assert!(block_rng(seed, 5).next_u64() != block_rng(Some([0xbb; 32]), 5).next_u64());
+
+      // Without a seed, the RNG isn't deterministic
+      assert!(verify(block_rng(None, 5)).0);
+      assert!(block_rng(None, 5).next_u64() != block_rng(None, 5).next_u64());
+    }
+
+    #[test]
+    fn test_block_json() {
+      let line = block_json(3_000_000, [0xab; 32], 5);
+      // Each block is emitted as a single line
+      assert!(!line.contains('\n'));
+      assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&line).unwrap(),
+        json!({ "height": 3_000_000, "hash": "ab".repeat(32), "tx_count": 5, "verified": true }),
+      );
+    }
+
+    #[test]
+    fn test_async_parallelism() {
+      assert_eq!(async_parallelism(None, 2), Ok(8));
+      assert_eq!(async_parallelism(Some("1"), 2), Ok(1));
+      // Exceeding the amount of nodes is allowed, solely warned about
+      assert_eq!(async_parallelism(Some("16"), 2), Ok(16));
+      assert!(async_parallelism(Some("0"), 2).is_err());
+      assert!(async_parallelism(Some("-1"), 2).is_err());
+      assert!(async_parallelism(Some("eight"), 2).is_err());
+    }
+
+    #[test]
+    fn test_ring_sample_rate() {
+      assert_eq!(ring_sample_rate(None), Ok(10));
+      assert_eq!(ring_sample_rate(Some("0")), Ok(0));
+      assert_eq!(ring_sample_rate(Some("100")), Ok(100));
+      assert!(ring_sample_rate(Some("101")).is_err());
+      assert!(ring_sample_rate(Some("ten")).is_err());
+
+      assert_eq!(sampled_members(16, 0), Vec::<usize>::new());
+      assert_eq!(sampled_members(16, 1), vec![0]);
+      assert_eq!(sampled_members(16, 25), vec![0, 4, 8, 12]);
+      assert_eq!(sampled_members(16, 100), (0 .. 16).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_responsive_nodes() {
+      assert_eq!(responsive_nodes(vec![]).await, Err("no nodes were specified".to_string()));
+
+      // Bind to ports the OS considers free, then stop listening, so nothing is listening on them
+      let nodes = (0 .. 2)
+        .map(|_| {
+          let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+          format!("http://{}", listener.local_addr().unwrap())
+        })
+        .collect::<Vec<_>>();
+      let err = responsive_nodes(nodes.clone()).await.unwrap_err();
+      assert!(err.starts_with("none of the nodes responded to get_height: "));
+      for node in nodes {
+        assert!(err.contains(&node), "{node} wasn't listed as failing");
+      }
+    }
+
+    #[test]
+    fn test_check_ring_len() {
+      // Prior to hard fork 8, any ring length above the minimum was allowed
+      assert_eq!(mandated_ring_len(1_685_554), None);
+      assert!(check_ring_len(1_685_554, 5).is_ok());
+      assert!(check_ring_len(1_685_554, 11).is_ok());
+
+      assert_eq!(mandated_ring_len(1_685_555), Some(11));
+      assert!(check_ring_len(1_685_555, 11).is_ok());
+      assert!(check_ring_len(2_688_887, 11).is_ok());
+      assert!(check_ring_len(1_685_555, 7).is_err());
+      assert!(check_ring_len(2_688_887, 16).is_err());
+
+      assert_eq!(mandated_ring_len(2_688_888), Some(16));
+      assert!(check_ring_len(2_688_888, 16).is_ok());
+      assert_eq!(
+        check_ring_len(3_000_000, 11),
+        Err("ring length of 11 when 16 is mandated at height 3000000".to_string())
+      );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_block_reorg() {
+      use std::sync::Mutex;
+
+      // Mainnet's genesis block, and a competing block differing solely by its nonce
+      let genesis = hex::decode(concat!(
+        "010000000000000000000000000000000000000000000000000000000000000000000010270000",
+        "013c01ff0001ffffffffffff03029b2e4c0281c0b02e7c53291a94d1d0cbff",
+        "8883f8024f5142ee494ffbbd0880",
+        "7121017767aafcde9be00dcfd098715ebcf7f410daebc582fda69d24a28e9d0bc890d1",
+        "00"
+      ))
+      .unwrap();
+      let genesis = Block::read(&mut genesis.as_slice()).unwrap();
+      let mut competing = genesis.clone();
+      competing.header.nonce += 1;
+
+      // The node returns the genesis block's hash once, then reorganizes to the competing block
+      let hashes = Mutex::new(vec![competing.hash(), genesis.hash()]);
+      let get_hash = || {
+        let mut hashes = hashes.lock().unwrap();
+        let hash = if hashes.len() > 1 { hashes.pop().unwrap() } else { hashes[0] };
+        async move { Ok(hash) }
+      };
+
+      // If the node still has the reorganized block, it's discarded for the block at this height
+      let mut requested = vec![];
+      let (block, blob) = fetch_block(0, get_hash, |hash| {
+        requested.push(hash);
+        let block = [&genesis, &competing].into_iter().find(|block| block.hash() == hash).cloned();
+        async move { Ok(block.unwrap().serialize()) }
+      })
+      .await;
+      assert_eq!(block, competing);
+      assert_eq!(blob, competing.serialize());
+      assert_eq!(requested, vec![genesis.hash(), competing.hash()]);
+
+      // If the node no longer has the reorganized block, the fetch is still retried
+      *hashes.lock().unwrap() = vec![competing.hash(), genesis.hash()];
+      let mut requested = vec![];
+      let (block, _) = fetch_block(0, get_hash, |hash| {
+        requested.push(hash);
+        let block = (hash == competing.hash()).then(|| competing.serialize());
+        async move { block.ok_or_else(|| RpcError::InvalidNode("block not found".to_string())) }
+      })
+      .await;
+      assert_eq!(block, competing);
+      assert_eq!(requested, vec![genesis.hash(), competing.hash()]);
+    }
+
+    #[test]
+    fn test_rate_limited() {
+      for status in RATE_LIMITED_STATUSES {
+        assert!(rate_limited(&RpcError::InvalidNode(format!("{GET_OUTS_STATUS}{status}"))));
+      }
+      // Other statuses, and other invalid responses, aren't rate limiting
+      assert!(!rate_limited(&RpcError::InvalidNode(format!("{GET_OUTS_STATUS}Failed"))));
+      assert!(!rate_limited(&RpcError::InvalidNode("response wasn't json: ".to_string())));
+      assert!(!rate_limited(&RpcError::ConnectionError("refused".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_check_ring_rate_limited() {
+      use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+
+      let indexes = (0 .. 16).collect::<Vec<u64>>();
+      let outs =
+        |indexes: &[u64]| indexes.iter().map(|_| [ED25519_BASEPOINT_POINT; 2]).collect::<Vec<_>>();
+      let rate_limited =
+        || Err(RpcError::InvalidNode(format!("{GET_OUTS_STATUS}{}", RATE_LIMITED_STATUSES[0])));
+
+      // When the entire ring is returned, the CLSAG is verified
+      let mut verified = false;
+      let check = check_ring(
+        |indexes| async move { Ok(outs(&indexes)) },
+        &indexes,
+        25,
+        |ring| {
+          assert_eq!(ring.len(), 16);
+          verified = true;
+        },
+      )
+      .await;
+      assert_eq!(check, RingCheck::Verified);
+      assert!(verified);
+
+      // When the entire ring is rate limited, a sample is checked and the CLSAG is reported skipped
+      let mut requested = vec![];
+      let check = check_ring(
+        |indexes| {
+          requested.push(indexes.clone());
+          async move {
+            if indexes.len() == 16 {
+              rate_limited()
+            } else {
+              Ok(outs(&indexes))
+            }
+          }
+        },
+        &indexes,
+        25,
+        |_| panic!("verified a CLSAG without its ring"),
+      )
+      .await;
+      assert_eq!(check, RingCheck::Sampled { sampled: 4 });
+      assert_eq!(requested, vec![indexes.clone(), vec![0, 4, 8, 12]]);
+
+      // When even the sample is rate limited, no ring members are reported as checked
+      let check = check_ring(
+        |_| async { rate_limited() },
+        &indexes,
+        25,
+        |_| panic!("verified a CLSAG without its ring"),
+      )
+      .await;
+      assert_eq!(check, RingCheck::Sampled { sampled: 0 });
+
+      // A sample rate of 0 solely attempts the entire ring
+      let mut requests = 0;
+      let check = check_ring(
+        |_| {
+          requests += 1;
+          async { rate_limited() }
+        },
+        &indexes,
+        0,
+        |_| panic!("verified a CLSAG without its ring"),
+      )
+      .await;
+      assert_eq!(check, RingCheck::Sampled { sampled: 0 });
+      assert_eq!(requests, 1);
+    }
   }
 }
 
@@ -246,15 +953,17 @@ mod binaries {
 async fn main() {
   use binaries::*;
 
-  let args = std::env::args().collect::<Vec<String>>();
+  let mut args = std::env::args().collect::<Vec<String>>();
+
+  // Emit a JSON line for each verified block if --emit-json was specified
+  if let Some(i) = args.iter().position(|arg| arg == "--emit-json") {
+    args.remove(i);
+    EMIT_JSON.store(true, Ordering::Relaxed);
+  }
 
   // Read start block as the first arg
   let mut block_i = args[1].parse::<usize>().expect("invalid start block");
 
-  // How many blocks to work on at once
-  let async_parallelism: usize =
-    args.get(2).unwrap_or(&"8".to_string()).parse::<usize>().expect("invalid parallelism argument");
-
   // Read further args as RPC URLs
   let default_nodes = vec![
     "http://xmr-node.cakewallet.com:18081".to_string(),
@@ -270,6 +979,32 @@ async fn main() {
     }
   }
   let nodes = if specified_nodes.is_empty() { default_nodes } else { specified_nodes };
+  let nodes = responsive_nodes(nodes).await.unwrap_or_else(|e| {
+    eprintln!("{e}");
+    std::process::exit(1);
+  });
+
+  // How many blocks to work on at once
+  let async_parallelism = async_parallelism(args.get(2).map(String::as_str), nodes.len())
+    .unwrap_or_else(|e| panic!("{e}"));
+
+  // The percentage of ring members to sample when a node is rate limiting
+  let ring_sample_rate = ring_sample_rate(std::env::var("RING_SAMPLE_RATE").ok().as_deref())
+    .unwrap_or_else(|e| panic!("{e}"));
+
+  // Whether to flag rings without the length mandated by consensus, solely valid for mainnet
+  let ring_len_check = std::env::var("CHECK_RING_LEN").is_ok();
+
+  // A file of key images, restricting verification to the transactions spending them
+  let filter = std::env::var("KEY_IMAGE_FILTER").ok().map(|path| {
+    let contents = std::fs::read_to_string(&path)
+      .unwrap_or_else(|e| panic!("couldn't read key image filter {path}: {e}"));
+    Arc::new(key_image_filter(&contents).unwrap_or_else(|e| panic!("{e}")))
+  });
+
+  // The seed for the RNG used during verification, making verification reproducible
+  let verification_seed = verification_seed(std::env::var("VERIFICATION_SEED").ok().as_deref())
+    .unwrap_or_else(|e| panic!("{e}"));
 
   let rpc = |url: String| async move {
     HttpRpc::new(url.clone())
@@ -308,7 +1043,14 @@ async fn main() {
         }
       }
 
-      handles.push(tokio::spawn(check_block(rpcs[rpc_i].clone(), block_i)));
+      handles.push(tokio::spawn(check_block(
+        rpcs[rpc_i].clone(),
+        block_i,
+        ring_sample_rate,
+        ring_len_check,
+        filter.clone(),
+        block_rng(verification_seed, block_i),
+      )));
       rpc_i = (rpc_i + 1) % rpcs.len();
       block_i += 1;
     }
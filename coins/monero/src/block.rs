@@ -15,6 +15,31 @@ const CORRECT_BLOCK_HASH_202612: [u8; 32] =
 const EXISTING_BLOCK_HASH_202612: [u8; 32] =
   hex_literal::hex!("bbd604d2ba11ba27935e006ed39c9bfdd99b76bf4a50654bc1e1e61217962698");
 
+/// The amount of bytes of context included on either side of a serialization mismatch.
+const MISMATCH_CONTEXT: usize = 16;
+
+/// A block's serialization didn't match an expected serialization.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[cfg_attr(
+  feature = "std",
+  error(
+    "serialization differs at offset {offset} (expected {}, actual {})",
+    hex::encode(.expected),
+    hex::encode(.actual)
+  )
+)]
+pub struct SerializationMismatch {
+  /// The offset of the first differing byte.
+  ///
+  /// If one serialization is a prefix of the other, this is the length of the shorter one.
+  pub offset: usize,
+  /// The expected serialization's bytes surrounding the offset.
+  pub expected: Vec<u8>,
+  /// The actual serialization's bytes surrounding the offset.
+  pub actual: Vec<u8>,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct BlockHeader {
   pub major_version: u8,
@@ -75,11 +100,30 @@ impl Block {
     Ok(())
   }
 
-  fn tx_merkle_root(&self) -> [u8; 32] {
+  /// The merkle root of the block's transactions, as committed to by the block's hashing blob.
+  ///
+  /// This is Monero's tree hash over the miner transaction's hash followed by the hashes of the
+  /// block's other transactions, in order, letting tooling check a claimed root against the
+  /// block's transactions.
+  pub fn tx_merkle_root(&self) -> [u8; 32] {
     merkle_root(self.miner_tx.hash(), &self.txs)
   }
 
-  /// Serialize the block as required for the proof of work hash.
+  /// Check the block's transactions are the ones with the specified hashes.
+  ///
+  /// `tx_hashes` are the hashes of the block's transactions other than the miner transaction, in
+  /// order. The merkle root over them is compared to the block's own, so the block's linkage to its
+  /// transactions can be verified from their hashes alone, without their prunable data.
+  pub fn verify_header_links(&self, tx_hashes: &[[u8; 32]]) -> bool {
+    merkle_root(self.miner_tx.hash(), tx_hashes) == self.tx_merkle_root()
+  }
+
+  /// Serialize the block as required for the proof of work hash, commonly referred to as the
+  /// block's hashing blob.
+  ///
+  /// This is the header, the merkle root of the block's transactions (including the miner
+  /// transaction), and the amount of transactions. It's what miners hash when searching for a
+  /// nonce and what's hashed when checking a block against its difficulty.
   ///
   /// This is distinct from the serialization required for the block hash. To get the block hash,
   /// use the [`Block::hash`] function.
@@ -113,6 +157,29 @@ impl Block {
     serialized
   }
 
+  /// Check the block's serialization matches the expected serialization.
+  ///
+  /// On mismatch, this returns the offset of the first differing byte with the bytes surrounding
+  /// it, making it usable to audit a block's reserialization against a node's blob.
+  pub fn serialization_matches(&self, expected: &[u8]) -> Result<(), SerializationMismatch> {
+    let actual = self.serialize();
+    if actual == expected {
+      return Ok(());
+    }
+
+    let offset = actual
+      .iter()
+      .zip(expected)
+      .position(|(actual, expected)| actual != expected)
+      .unwrap_or(actual.len().min(expected.len()));
+    let context = |bytes: &[u8]| {
+      let start = offset.saturating_sub(MISMATCH_CONTEXT).min(bytes.len());
+      let end = offset.saturating_add(MISMATCH_CONTEXT).min(bytes.len());
+      bytes[start .. end].to_vec()
+    };
+    Err(SerializationMismatch { offset, expected: context(expected), actual: context(&actual) })
+  }
+
   pub fn read<R: Read>(r: &mut R) -> io::Result<Block> {
     let header = BlockHeader::read(r)?;
 
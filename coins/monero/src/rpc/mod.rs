@@ -20,7 +20,7 @@ use crate::{
   Protocol,
   serialize::*,
   transaction::{Input, Timelock, Transaction},
-  block::Block,
+  block::{BlockHeader, Block},
   wallet::{FeePriority, Fee},
 };
 
@@ -37,8 +37,14 @@ const GRACE_BLOCKS_FOR_FEE_ESTIMATE: u64 = 10;
 #[derive(Deserialize, Debug)]
 pub struct EmptyResponse {}
 #[derive(Deserialize, Debug)]
+struct JsonRpcError {
+  code: i64,
+  message: String,
+}
+#[derive(Deserialize, Debug)]
 pub struct JsonRpcResponse<T> {
-  result: T,
+  result: Option<T>,
+  error: Option<JsonRpcError>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -86,6 +92,9 @@ pub enum RpcError {
   InvalidFee,
   #[cfg_attr(feature = "std", error("invalid priority"))]
   InvalidPriority,
+  /// The node responded to a JSON-RPC call with an error object.
+  #[cfg_attr(feature = "std", error("node returned error {code} ({message})"))]
+  Rpc { code: i64, message: String },
 }
 
 fn rpc_hex(value: &str) -> Result<Vec<u8>, RpcError> {
@@ -132,6 +141,11 @@ pub trait RpcConnection: Clone + Debug {
 #[derive(Clone, Debug)]
 pub struct Rpc<R: RpcConnection>(R);
 impl<R: RpcConnection> Rpc<R> {
+  #[cfg(test)]
+  pub(crate) fn from_connection(connection: R) -> Rpc<R> {
+    Rpc(connection)
+  }
+
   /// Perform a RPC call to the specified route with the provided parameters.
   ///
   /// This is NOT a JSON-RPC call. They use a route of "json_rpc" and are available via
@@ -159,6 +173,9 @@ impl<R: RpcConnection> Rpc<R> {
   }
 
   /// Perform a JSON-RPC call with the specified method with the provided parameters
+  ///
+  /// If the node responds with an error object, `RpcError::Rpc` is returned with its code and
+  /// message.
   pub async fn json_rpc_call<Response: DeserializeOwned + Debug>(
     &self,
     method: &str,
@@ -168,7 +185,13 @@ impl<R: RpcConnection> Rpc<R> {
     if let Some(params) = params {
       req.as_object_mut().unwrap().insert("params".into(), params);
     }
-    Ok(self.rpc_call::<_, JsonRpcResponse<Response>>("json_rpc", Some(req)).await?.result)
+    let res = self.rpc_call::<_, JsonRpcResponse<Response>>("json_rpc", Some(req)).await?;
+    if let Some(JsonRpcError { code, message }) = res.error {
+      Err(RpcError::Rpc { code, message })?;
+    }
+    res.result.ok_or_else(|| {
+      RpcError::InvalidNode("JSON-RPC response had neither a result nor an error".to_string())
+    })
   }
 
   /// Perform a binary call to the specified route with the provided parameters.
@@ -278,6 +301,55 @@ impl<R: RpcConnection> Rpc<R> {
     self.get_transactions(&[tx]).await.map(|mut txs| txs.swap_remove(0))
   }
 
+  /// Get the height of the block which included a transaction, and the transaction's amount of
+  /// confirmations.
+  ///
+  /// A transaction included in the latest block has one confirmation. Returns None if the
+  /// transaction is in the mempool or unknown to the node.
+  pub async fn get_transaction_block(&self, tx: [u8; 32]) -> Result<Option<(u64, u64)>, RpcError> {
+    #[derive(Deserialize, Debug)]
+    struct TransactionResponse {
+      tx_hash: String,
+      in_pool: bool,
+      #[serde(default)]
+      block_height: u64,
+    }
+    #[derive(Deserialize, Debug)]
+    struct TransactionsResponse {
+      #[serde(default)]
+      missed_tx: Vec<String>,
+      #[serde(default)]
+      txs: Vec<TransactionResponse>,
+    }
+
+    let txs: TransactionsResponse =
+      self.rpc_call("get_transactions", Some(json!({ "txs_hashes": [hex::encode(tx)] }))).await?;
+    if !txs.missed_tx.is_empty() {
+      return Ok(None);
+    }
+    let Some(res) = txs.txs.first() else {
+      Err(RpcError::InvalidNode("node neither returned nor missed the transaction".to_string()))?
+    };
+    if hash_hex(&res.tx_hash)? != tx {
+      Err(RpcError::InvalidNode(
+        "replied with transaction wasn't the requested transaction".to_string(),
+      ))?;
+    }
+    if res.in_pool {
+      return Ok(None);
+    }
+
+    // The height is the amount of blocks, making the latest block's number one less than it
+    let height = u64::try_from(self.get_height().await?)
+      .map_err(|_| RpcError::InternalError("node's height exceeded 2**64"))?;
+    if res.block_height >= height {
+      Err(RpcError::InvalidNode(
+        "transaction was included in a block beyond the chain".to_string(),
+      ))?;
+    }
+    Ok(Some((res.block_height, height - res.block_height)))
+  }
+
   /// Get the hash of a block from the node by the block's numbers.
   /// This function does not verify the returned block hash is actually for the number in question.
   pub async fn get_block_hash(&self, number: usize) -> Result<[u8; 32], RpcError> {
@@ -295,6 +367,68 @@ impl<R: RpcConnection> Rpc<R> {
     hash_hex(&header.block_header.hash)
   }
 
+  /// Get the headers of the blocks from the specified number to the specified number (both
+  /// inclusive).
+  ///
+  /// This function checks the headers are for the requested blocks and that each header builds
+  /// on the prior one, yet doesn't verify the hashes the node claims for them.
+  pub async fn get_block_headers_range(
+    &self,
+    start: u64,
+    end: u64,
+  ) -> Result<Vec<BlockHeader>, RpcError> {
+    #[derive(Deserialize, Debug)]
+    struct BlockHeaderResponse {
+      major_version: u8,
+      minor_version: u8,
+      timestamp: u64,
+      prev_hash: String,
+      nonce: u32,
+      height: u64,
+      hash: String,
+    }
+    #[derive(Deserialize, Debug)]
+    struct BlockHeadersRangeResponse {
+      headers: Vec<BlockHeaderResponse>,
+    }
+
+    if end < start {
+      Err(RpcError::InternalError("requested headers for an empty range"))?;
+    }
+
+    let res: BlockHeadersRangeResponse = self
+      .json_rpc_call(
+        "get_block_headers_range",
+        Some(json!({ "start_height": start, "end_height": end })),
+      )
+      .await?;
+    if u64::try_from(res.headers.len()).ok() != Some(end - start + 1) {
+      Err(RpcError::InvalidNode("block headers range had an incorrect length".to_string()))?;
+    }
+
+    let mut headers = Vec::with_capacity(res.headers.len());
+    let mut prior_hash = None;
+    for (number, header) in (start ..).zip(res.headers) {
+      if header.height != number {
+        Err(RpcError::InvalidNode("different block header than requested (number)".to_string()))?;
+      }
+      let previous = hash_hex(&header.prev_hash)?;
+      if prior_hash.is_some_and(|prior_hash| prior_hash != previous) {
+        Err(RpcError::InvalidNode("block header didn't build on the prior header".to_string()))?;
+      }
+      prior_hash = Some(hash_hex(&header.hash)?);
+
+      headers.push(BlockHeader {
+        major_version: header.major_version,
+        minor_version: header.minor_version,
+        timestamp: header.timestamp,
+        previous,
+        nonce: header.nonce,
+      });
+    }
+    Ok(headers)
+  }
+
   /// Get a block from the node by its hash.
   /// This function does not verify the returned block actually has the hash in question.
   pub async fn get_block(&self, hash: [u8; 32]) -> Result<Block, RpcError> {
@@ -512,6 +646,8 @@ impl<R: RpcConnection> Rpc<R> {
 
   /// Get the output distribution, from the specified height to the specified height (both
   /// inclusive).
+  ///
+  /// Each entry is the cumulative amount of RingCT outputs as of that block.
   pub async fn get_output_distribution(
     &self,
     from: usize,
@@ -519,6 +655,8 @@ impl<R: RpcConnection> Rpc<R> {
   ) -> Result<Vec<u64>, RpcError> {
     #[derive(Deserialize, Debug)]
     struct Distribution {
+      start_height: usize,
+      base: u64,
       distribution: Vec<u64>,
     }
 
@@ -540,7 +678,27 @@ impl<R: RpcConnection> Rpc<R> {
       )
       .await?;
 
-    Ok(distributions.distributions.swap_remove(0).distribution)
+    if distributions.distributions.len() != 1 {
+      Err(RpcError::InvalidNode(
+        "distribution response didn't have exactly one distribution".to_string(),
+      ))?;
+    }
+    let Distribution { start_height, base, mut distribution } =
+      distributions.distributions.swap_remove(0);
+
+    // The node won't return the distribution for blocks prior to RingCT's activation, starting
+    // at the first RingCT block instead
+    // As the distribution is cumulative, those blocks have base (zero) outputs
+    if start_height < from {
+      Err(RpcError::InvalidNode("distribution started before the requested height".to_string()))?;
+    }
+    let mut res = vec![base; start_height - from];
+    res.append(&mut distribution);
+
+    if res.len() != (to.saturating_sub(from) + 1) {
+      Err(RpcError::InvalidNode("distribution had an incorrect length".to_string()))?;
+    }
+    Ok(res)
   }
 
   /// Get the specified outputs from the RingCT (zero-amount) pool
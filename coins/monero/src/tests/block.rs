@@ -0,0 +1,125 @@
+use hex_literal::hex;
+
+use crate::{
+  hash,
+  block::{Block, SerializationMismatch},
+};
+
+// Mainnet's genesis block
+const GENESIS: &[u8] = &hex!(
+  "010000000000000000000000000000000000000000000000000000000000000000000010270000"
+  "013c01ff0001ffffffffffff03029b2e4c0281c0b02e7c53291a94d1d0cbff8883f8024f5142ee494ffbbd0880"
+  "7121017767aafcde9be00dcfd098715ebcf7f410daebc582fda69d24a28e9d0bc890d1"
+  "00"
+);
+const GENESIS_HASH: [u8; 32] =
+  hex!("418015bb9ae982a1975da7d79277c2705727a56894ba0fb246adaabb1f4632e3");
+// The header, the merkle root of the transactions (solely the miner transaction's hash), and the
+// amount of transactions
+const GENESIS_HASHING_BLOB: &[u8] = &hex!(
+  "010000000000000000000000000000000000000000000000000000000000000000000010270000"
+  "c88ce9783b4f11190d7b9c17a69c1c52200f9faaee8e98dd07e6811175177139"
+  "01"
+);
+
+#[test]
+fn hashing_blob() {
+  let mut genesis = GENESIS;
+  let block = Block::read(&mut genesis).unwrap();
+  assert_eq!(block.serialize(), GENESIS);
+  assert_eq!(block.hash(), GENESIS_HASH);
+  assert_eq!(block.serialize_hashable(), GENESIS_HASHING_BLOB);
+}
+
+#[test]
+fn tx_merkle_root() {
+  let mut genesis = GENESIS;
+  let mut block = Block::read(&mut genesis).unwrap();
+
+  // The genesis block's merkle root is embedded in its hashing blob, after the header
+  let header_len = block.header.serialize().len();
+  assert_eq!(block.tx_merkle_root(), GENESIS_HASHING_BLOB[header_len .. (header_len + 32)]);
+  // With solely the miner transaction, the merkle root is the miner transaction's hash
+  let miner_tx = block.miner_tx.hash();
+  assert_eq!(block.tx_merkle_root(), miner_tx);
+
+  // With one other transaction, the two hashes are hashed together
+  block.txs = vec![[0xaa; 32]];
+  assert_eq!(block.tx_merkle_root(), hash(&[miner_tx, [0xaa; 32]].concat()));
+
+  // With two other transactions, the right-most hashes are paired off before the rest of the tree
+  block.txs = vec![[0xaa; 32], [0xbb; 32]];
+  assert_eq!(
+    block.tx_merkle_root(),
+    hash(&[miner_tx, hash(&[[0xaa; 32], [0xbb; 32]].concat())].concat())
+  );
+
+  // The merkle root is always the root embedded in the hashing blob
+  for txs in 0 .. 10 {
+    block.txs = (0 .. txs).map(|i| [i; 32]).collect();
+    assert_eq!(block.tx_merkle_root(), block.serialize_hashable()[header_len .. (header_len + 32)]);
+  }
+}
+
+#[test]
+fn serialization_matches() {
+  let mut genesis = GENESIS;
+  let block = Block::read(&mut genesis).unwrap();
+  assert_eq!(block.serialization_matches(GENESIS), Ok(()));
+
+  // Corrupt a byte within the miner transaction
+  let mut corrupted = GENESIS.to_vec();
+  corrupted[50] ^= 1;
+  assert_eq!(
+    block.serialization_matches(&corrupted),
+    Err(SerializationMismatch {
+      offset: 50,
+      expected: corrupted[34 .. 66].to_vec(),
+      actual: GENESIS[34 .. 66].to_vec(),
+    })
+  );
+
+  // The context is truncated at the start of the serialization
+  let mut corrupted = GENESIS.to_vec();
+  corrupted[0] ^= 1;
+  assert_eq!(
+    block.serialization_matches(&corrupted),
+    Err(SerializationMismatch {
+      offset: 0,
+      expected: corrupted[.. 16].to_vec(),
+      actual: GENESIS[.. 16].to_vec(),
+    })
+  );
+
+  // A trailing byte is reported at the end of the actual serialization
+  let extended = [GENESIS, &[0]].concat();
+  assert_eq!(
+    block.serialization_matches(&extended),
+    Err(SerializationMismatch {
+      offset: GENESIS.len(),
+      expected: extended[(GENESIS.len() - 16) ..].to_vec(),
+      actual: GENESIS[(GENESIS.len() - 16) ..].to_vec(),
+    })
+  );
+}
+
+#[test]
+fn verify_header_links() {
+  let mut genesis = GENESIS;
+  let mut block = Block::read(&mut genesis).unwrap();
+
+  // The genesis block solely has the miner transaction
+  assert!(block.verify_header_links(&[]));
+  assert!(!block.verify_header_links(&[[0xaa; 32]]));
+
+  block.txs = vec![[0xaa; 32], [0xbb; 32], [0xcc; 32]];
+  assert!(block.verify_header_links(&[[0xaa; 32], [0xbb; 32], [0xcc; 32]]));
+  // A tampered hash
+  assert!(!block.verify_header_links(&[[0xaa; 32], [0xbd; 32], [0xcc; 32]]));
+  // A reordered list
+  assert!(!block.verify_header_links(&[[0xbb; 32], [0xaa; 32], [0xcc; 32]]));
+  // A missing hash
+  assert!(!block.verify_header_links(&[[0xaa; 32], [0xbb; 32]]));
+  // An additional hash
+  assert!(!block.verify_header_links(&[[0xaa; 32], [0xbb; 32], [0xcc; 32], [0xdd; 32]]));
+}
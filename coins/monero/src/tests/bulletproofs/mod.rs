@@ -1,7 +1,7 @@
 use hex_literal::hex;
-use rand_core::OsRng;
+use rand_core::{RngCore, OsRng};
 
-use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::{scalar::Scalar, edwards::EdwardsPoint};
 use monero_generators::decompress_point;
 use multiexp::BatchVerifier;
 
@@ -60,7 +60,7 @@ fn bulletproofs_vector() {
 }
 
 macro_rules! bulletproofs_tests {
-  ($name: ident, $max: ident, $plus: literal) => {
+  ($name: ident, $max: ident, $multiple: ident, $plus: literal) => {
     #[test]
     fn $name() {
       // Create Bulletproofs for all possible output quantities
@@ -79,6 +79,38 @@ macro_rules! bulletproofs_tests {
       assert!(verifier.verify_vartime());
     }
 
+    #[test]
+    fn $multiple() {
+      let mut bps = vec![];
+      for i in 1 ..= 4 {
+        let commitments = (0 .. i)
+          .map(|_| Commitment::new(random_scalar(&mut OsRng), OsRng.next_u64()))
+          .collect::<Vec<_>>();
+        let bp = Bulletproofs::prove(&mut OsRng, &commitments, $plus).unwrap();
+        bps.push((bp, commitments.iter().map(Commitment::calculate).collect::<Vec<_>>()));
+      }
+
+      fn proofs(
+        bps: &[(Bulletproofs, Vec<EdwardsPoint>)],
+      ) -> Vec<(&Bulletproofs, &[EdwardsPoint])> {
+        bps.iter().map(|(bp, commitments)| (bp, commitments.as_slice())).collect()
+      }
+      assert!(Bulletproofs::verify_multiple(&mut OsRng, &[]));
+      assert!(Bulletproofs::verify_multiple(&mut OsRng, &proofs(&bps)));
+
+      // Replace one of a proof's commitments, making that proof invalid
+      let mut invalid = bps.clone();
+      let other = Commitment::new(random_scalar(&mut OsRng), 1).calculate();
+      invalid[2].1[0] = other;
+      assert!(!invalid[2].0.verify(&mut OsRng, &invalid[2].1));
+      assert!(!Bulletproofs::verify_multiple(&mut OsRng, &proofs(&invalid)));
+
+      // A proof for the wrong amount of commitments also fails the batch
+      let mut invalid = bps;
+      invalid[1].1.pop();
+      assert!(!Bulletproofs::verify_multiple(&mut OsRng, &proofs(&invalid)));
+    }
+
     #[test]
     fn $max() {
       // Check Bulletproofs errors if we try to prove for too many outputs
@@ -91,5 +123,5 @@ macro_rules! bulletproofs_tests {
   };
 }
 
-bulletproofs_tests!(bulletproofs, bulletproofs_max, false);
-bulletproofs_tests!(bulletproofs_plus, bulletproofs_plus_max, true);
+bulletproofs_tests!(bulletproofs, bulletproofs_max, bulletproofs_multiple, false);
+bulletproofs_tests!(bulletproofs_plus, bulletproofs_plus_max, bulletproofs_plus_multiple, true);
@@ -0,0 +1,34 @@
+use std_shims::collections::HashSet;
+
+use rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+use crate::{DEFAULT_LOCK_WINDOW, wallet::select_decoys};
+
+#[test]
+fn select_decoys_from_distribution() {
+  let mut rng = ChaCha20Rng::seed_from_u64(0);
+
+  // 1000 blocks, each with 20 outputs
+  let distribution = (1 ..= 1000).map(|i| i * 20).collect::<Vec<_>>();
+  let high = distribution[distribution.len() - DEFAULT_LOCK_WINDOW];
+
+  for real in [0, 5000, high - 1] {
+    for ring_size in [1, 11, 16] {
+      let ring = select_decoys(&mut rng, &distribution, real, ring_size).unwrap();
+      assert_eq!(ring.len(), ring_size);
+      assert!(ring.contains(&real));
+      assert_eq!(ring.iter().collect::<HashSet<_>>().len(), ring_size);
+      assert!(ring.windows(2).all(|pair| pair[0] < pair[1]));
+      // No locked outputs should've been selected as decoys
+      assert!(ring.iter().all(|o| (*o == real) || (*o < high)));
+    }
+  }
+
+  // A distribution within the lock window has no unlocked outputs
+  assert!(select_decoys(&mut rng, &distribution[.. DEFAULT_LOCK_WINDOW - 1], 0, 11).is_none());
+  // There are too few unlocked outputs for this ring size
+  let small = (1 ..= 20).collect::<Vec<_>>();
+  assert!(select_decoys(&mut rng, &small, 0, 12).is_none());
+  assert!(select_decoys(&mut rng, &small, 5, 11).is_none());
+}
@@ -1,5 +1,5 @@
 use crate::{
-  wallet::{ExtraField, Extra, extra::MAX_TX_EXTRA_PADDING_COUNT},
+  wallet::{PaymentId, ExtraField, Extra, extra::MAX_TX_EXTRA_PADDING_COUNT},
   serialize::write_varint,
 };
 
@@ -156,3 +156,25 @@ fn extra_mysterious_minergate_and_pub_key() {
   );
   test_write_buf(&extra, &buf);
 }
+
+#[test]
+fn extra_nonce_payment_ids() {
+  // A 32-byte cleartext payment ID, as used by legacy integrated addresses
+  let mut buf: Vec<u8> = vec![2, 33, 0];
+  buf.extend([0xab; 32]);
+  let extra = Extra::read::<&[u8]>(&mut buf.as_ref()).unwrap();
+  assert_eq!(extra.payment_id(), Some(PaymentId::Unencrypted([0xab; 32])));
+  test_write_buf(&extra, &buf);
+
+  // An 8-byte encrypted payment ID
+  let mut buf: Vec<u8> = vec![2, 9, 1];
+  buf.extend([0xcd; 8]);
+  let extra = Extra::read::<&[u8]>(&mut buf.as_ref()).unwrap();
+  assert_eq!(extra.payment_id(), Some(PaymentId::Encrypted([0xcd; 8])));
+  test_write_buf(&extra, &buf);
+
+  // A nonce which isn't a payment ID
+  let buf: Vec<u8> = vec![2, 1, 42];
+  let extra = Extra::read::<&[u8]>(&mut buf.as_ref()).unwrap();
+  assert_eq!(extra.payment_id(), None);
+}
@@ -4,3 +4,8 @@ mod bulletproofs;
 mod address;
 mod seed;
 mod extra;
+mod scan;
+mod rpc;
+mod decoys;
+mod transaction;
+mod block;
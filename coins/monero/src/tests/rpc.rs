@@ -0,0 +1,241 @@
+use async_trait::async_trait;
+
+use crate::{
+  block::BlockHeader,
+  rpc::{RpcError, RpcConnection, Rpc},
+};
+
+// A mock node serving a fixed output distribution
+#[derive(Clone, Debug)]
+struct MockRpc {
+  start_height: usize,
+  base: u64,
+  distribution: Vec<u64>,
+}
+
+#[async_trait]
+impl RpcConnection for MockRpc {
+  async fn post(&self, route: &str, body: Vec<u8>) -> Result<Vec<u8>, RpcError> {
+    assert_eq!(route, "json_rpc");
+    let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(request["method"], "get_output_distribution");
+    assert_eq!(request["params"]["amounts"], serde_json::json!([0]));
+    assert_eq!(request["params"]["cumulative"], true);
+    Ok(
+      serde_json::json!({
+        "result": {
+          "distributions": [{
+            "amount": 0,
+            "start_height": self.start_height,
+            "base": self.base,
+            "distribution": self.distribution,
+          }],
+          "status": "OK",
+        }
+      })
+      .to_string()
+      .into_bytes(),
+    )
+  }
+}
+
+#[tokio::test]
+async fn get_output_distribution() {
+  let rpc =
+    Rpc::from_connection(MockRpc { start_height: 5, base: 10, distribution: vec![12, 15, 15, 20] });
+  assert_eq!(rpc.get_output_distribution(5, 8).await.unwrap(), vec![12, 15, 15, 20]);
+
+  // Blocks before the start height should be filled with the base
+  let rpc = Rpc::from_connection(MockRpc { start_height: 3, base: 0, distribution: vec![1, 2, 4] });
+  assert_eq!(rpc.get_output_distribution(0, 5).await.unwrap(), vec![0, 0, 0, 1, 2, 4]);
+
+  // A distribution starting before the requested height is invalid
+  assert!(rpc.get_output_distribution(4, 6).await.is_err());
+
+  // As is one with the wrong amount of entries
+  assert!(rpc.get_output_distribution(3, 6).await.is_err());
+  assert!(rpc.get_output_distribution(3, 4).await.is_err());
+}
+
+// A mock node with a confirmed and a mempool transaction
+#[derive(Clone, Debug)]
+struct MockTransactionsRpc {
+  height: usize,
+  confirmed: ([u8; 32], u64),
+  pooled: [u8; 32],
+}
+
+#[async_trait]
+impl RpcConnection for MockTransactionsRpc {
+  async fn post(&self, route: &str, body: Vec<u8>) -> Result<Vec<u8>, RpcError> {
+    let res = match route {
+      "get_height" => serde_json::json!({ "height": self.height, "status": "OK" }),
+      "get_transactions" => {
+        let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let hash = request["txs_hashes"][0].as_str().unwrap();
+        if hash == hex::encode(self.confirmed.0) {
+          serde_json::json!({
+            "txs": [{ "tx_hash": hash, "in_pool": false, "block_height": self.confirmed.1 }],
+            "status": "OK",
+          })
+        } else if hash == hex::encode(self.pooled) {
+          serde_json::json!({ "txs": [{ "tx_hash": hash, "in_pool": true }], "status": "OK" })
+        } else {
+          serde_json::json!({ "missed_tx": [hash], "status": "OK" })
+        }
+      }
+      _ => panic!("unexpected route {route}"),
+    };
+    Ok(res.to_string().into_bytes())
+  }
+}
+
+#[tokio::test]
+async fn get_transaction_block() {
+  let rpc = Rpc::from_connection(MockTransactionsRpc {
+    height: 100,
+    confirmed: ([1; 32], 90),
+    pooled: [2; 32],
+  });
+  // The latest block is 99, so a transaction in block 90 has 10 confirmations
+  assert_eq!(rpc.get_transaction_block([1; 32]).await.unwrap(), Some((90, 10)));
+  // Mempool transactions aren't in a block
+  assert_eq!(rpc.get_transaction_block([2; 32]).await.unwrap(), None);
+  // Nor are unknown transactions
+  assert_eq!(rpc.get_transaction_block([3; 32]).await.unwrap(), None);
+
+  // A transaction in the latest block has one confirmation
+  let rpc = Rpc::from_connection(MockTransactionsRpc {
+    height: 91,
+    confirmed: ([1; 32], 90),
+    pooled: [2; 32],
+  });
+  assert_eq!(rpc.get_transaction_block([1; 32]).await.unwrap(), Some((90, 1)));
+
+  // A transaction in a block beyond the chain is invalid
+  let rpc = Rpc::from_connection(MockTransactionsRpc {
+    height: 90,
+    confirmed: ([1; 32], 90),
+    pooled: [2; 32],
+  });
+  assert!(rpc.get_transaction_block([1; 32]).await.is_err());
+}
+
+// A mock node serving headers for a chain where block n has hash [n; 32]
+#[derive(Clone, Debug)]
+struct MockHeadersRpc {
+  // Omit the last header from responses
+  truncate: bool,
+  // Have the specified block not build on the prior block
+  fork: Option<u8>,
+}
+
+#[async_trait]
+impl RpcConnection for MockHeadersRpc {
+  async fn post(&self, route: &str, body: Vec<u8>) -> Result<Vec<u8>, RpcError> {
+    assert_eq!(route, "json_rpc");
+    let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(request["method"], "get_block_headers_range");
+    let start = u8::try_from(request["params"]["start_height"].as_u64().unwrap()).unwrap();
+    let mut end = u8::try_from(request["params"]["end_height"].as_u64().unwrap()).unwrap();
+    if self.truncate {
+      end -= 1;
+    }
+    let headers = (start ..= end)
+      .map(|n| {
+        let prev = if self.fork == Some(n) { 0xff } else { n.wrapping_sub(1) };
+        serde_json::json!({
+          "major_version": 16,
+          "minor_version": 16,
+          "timestamp": 1_000_000 + u64::from(n),
+          "prev_hash": hex::encode([prev; 32]),
+          "nonce": u32::from(n) * 2,
+          "height": n,
+          "hash": hex::encode([n; 32]),
+        })
+      })
+      .collect::<Vec<_>>();
+    Ok(
+      serde_json::json!({ "result": { "headers": headers, "status": "OK" } })
+        .to_string()
+        .into_bytes(),
+    )
+  }
+}
+
+#[tokio::test]
+async fn get_block_headers_range() {
+  let rpc = Rpc::from_connection(MockHeadersRpc { truncate: false, fork: None });
+  let headers = rpc.get_block_headers_range(5, 9).await.unwrap();
+  assert_eq!(headers.len(), 5);
+  for (n, header) in (5 ..).zip(headers) {
+    assert_eq!(
+      header,
+      BlockHeader {
+        major_version: 16,
+        minor_version: 16,
+        timestamp: 1_000_000 + u64::from(n),
+        previous: [n - 1; 32],
+        nonce: u32::from(n) * 2,
+      }
+    );
+  }
+  // A single header may be requested
+  assert_eq!(rpc.get_block_headers_range(3, 3).await.unwrap().len(), 1);
+  // An empty range may not
+  assert!(rpc.get_block_headers_range(3, 2).await.is_err());
+
+  // A node which doesn't return every requested header is invalid
+  let rpc = Rpc::from_connection(MockHeadersRpc { truncate: true, fork: None });
+  assert!(rpc.get_block_headers_range(5, 9).await.is_err());
+
+  // As is one returning headers which don't form a chain
+  let rpc = Rpc::from_connection(MockHeadersRpc { truncate: false, fork: Some(7) });
+  assert!(rpc.get_block_headers_range(5, 9).await.is_err());
+  // Though the first header isn't checked against a prior header
+  assert_eq!(rpc.get_block_headers_range(7, 9).await.unwrap().len(), 3);
+}
+
+// A mock node which responds to every JSON-RPC call with the specified response
+#[derive(Clone, Debug)]
+struct MockJsonRpc(serde_json::Value);
+
+#[async_trait]
+impl RpcConnection for MockJsonRpc {
+  async fn post(&self, route: &str, _: Vec<u8>) -> Result<Vec<u8>, RpcError> {
+    assert_eq!(route, "json_rpc");
+    Ok(self.0.to_string().into_bytes())
+  }
+}
+
+#[tokio::test]
+async fn json_rpc_error() {
+  let rpc = Rpc::from_connection(MockJsonRpc(serde_json::json!({
+    "id": "0",
+    "jsonrpc": "2.0",
+    "error": { "code": -32601, "message": "Method not found" },
+  })));
+  assert_eq!(
+    rpc.json_rpc_call::<serde_json::Value>("unknown_method", None).await,
+    Err(RpcError::Rpc { code: -32601, message: "Method not found".to_string() })
+  );
+  // The error should be propagated through the typed methods
+  assert_eq!(
+    rpc.get_block_hash(1).await,
+    Err(RpcError::Rpc { code: -32601, message: "Method not found".to_string() })
+  );
+
+  // A result is still returned as before
+  let rpc = Rpc::from_connection(MockJsonRpc(serde_json::json!({ "result": { "count": 5 } })));
+  assert_eq!(
+    rpc.json_rpc_call::<serde_json::Value>("get_block_count", None).await,
+    Ok(serde_json::json!({ "count": 5 }))
+  );
+
+  // A response with neither a result nor an error is invalid
+  let rpc = Rpc::from_connection(MockJsonRpc(serde_json::json!({ "id": "0" })));
+  assert!(matches!(
+    rpc.json_rpc_call::<serde_json::Value>("get_block_count", None).await,
+    Err(RpcError::InvalidNode(_))
+  ));
+}
@@ -0,0 +1,1188 @@
+use core::ops::Deref;
+use std::{
+  sync::{Arc, Mutex},
+  collections::HashSet,
+};
+
+use rand_core::OsRng;
+
+use zeroize::Zeroizing;
+
+use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, scalar::Scalar, edwards::EdwardsPoint};
+
+use async_trait::async_trait;
+
+#[cfg(feature = "http-rpc")]
+use futures_util::StreamExt;
+#[cfg(feature = "http-rpc")]
+use tokio::time::{Duration, Instant, timeout};
+
+use crate::{
+  Commitment, random_scalar,
+  transaction::{Input, Output, Timelock, TransactionPrefix, Transaction},
+  block::{BlockHeader, Block},
+  ringct::{
+    EncryptedAmount, RctBase, RctPrunable, RctSignatures, hash_to_point, generate_key_image,
+  },
+  rpc::{RpcError, RpcConnection, Rpc},
+  wallet::{
+    MAX_SCANNED_KEYS, PaymentId, ExtraField, Extra, ViewPair, Scanner, ReceivedOutput,
+    SpendableOutput, Timelocked, Derivation, uniqueness, shared_key, derive_output_key,
+    commitment_mask, amount_encryption, amount_decryption,
+  },
+};
+
+fn view_pair() -> ViewPair {
+  ViewPair::new(
+    &random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE,
+    Zeroizing::new(random_scalar(&mut OsRng)),
+  )
+}
+
+// Create a miner transaction paying the specified amount to the specified spend/view keys
+fn miner_transaction(
+  number: usize,
+  spend: EdwardsPoint,
+  view: EdwardsPoint,
+  amount: u64,
+) -> Transaction {
+  let r = random_scalar(&mut OsRng);
+  let (view_tag, shared_key, _) = shared_key(None, r * view, 0);
+  Transaction {
+    prefix: TransactionPrefix {
+      version: 2,
+      timelock: Timelock::Block(number + 60),
+      inputs: vec![Input::Gen(number.try_into().unwrap())],
+      outputs: vec![Output {
+        amount: Some(amount),
+        key: ((&shared_key * ED25519_BASEPOINT_TABLE) + spend).compress(),
+        view_tag: Some(view_tag),
+      }],
+      extra: Extra::new(&r * ED25519_BASEPOINT_TABLE, vec![]).serialize(),
+    },
+    signatures: vec![],
+    rct_signatures: RctSignatures {
+      base: RctBase { fee: 0, pseudo_outs: vec![], encrypted_amounts: vec![], commitments: vec![] },
+      prunable: RctPrunable::Null,
+    },
+  }
+}
+
+// Create a RingCT transaction paying the specified amounts to the specified spend/view keys
+fn transaction(payments: &[(EdwardsPoint, EdwardsPoint, u64)]) -> Transaction {
+  transaction_with_payment_id(payments, None)
+}
+
+// Create a RingCT transaction with a payment ID, encrypting it if it's the encrypted variant
+fn transaction_with_payment_id(
+  payments: &[(EdwardsPoint, EdwardsPoint, u64)],
+  payment_id: Option<PaymentId>,
+) -> Transaction {
+  let payments = payments
+    .iter()
+    .map(|(spend, view, amount)| (*spend, *view, *amount, Derivation::Traditional))
+    .collect::<Vec<_>>();
+  transaction_with_derivations(&payments, payment_id)
+}
+
+// Create a RingCT transaction, deriving each output's shared key with the specified derivation
+fn transaction_with_derivations(
+  payments: &[(EdwardsPoint, EdwardsPoint, u64, Derivation)],
+  payment_id: Option<PaymentId>,
+) -> Transaction {
+  let inputs = vec![Input::ToKey {
+    amount: None,
+    key_offsets: vec![],
+    key_image: &random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE,
+  }];
+
+  let r = random_scalar(&mut OsRng);
+  let mut payment_id_xor = [0; 8];
+  let mut outputs = vec![];
+  let mut encrypted_amounts = vec![];
+  let mut commitments = vec![];
+  for (o, (spend, view, amount, derivation)) in payments.iter().enumerate() {
+    let unique = match derivation {
+      Derivation::Traditional => None,
+      Derivation::Unique => Some(uniqueness(&inputs)),
+    };
+    let (view_tag, shared_key, xor) = shared_key(unique, r * view, o);
+    payment_id_xor = xor;
+    outputs.push(Output {
+      amount: None,
+      key: ((&shared_key * ED25519_BASEPOINT_TABLE) + spend).compress(),
+      view_tag: Some(view_tag),
+    });
+    encrypted_amounts
+      .push(EncryptedAmount::Compact { amount: amount_encryption(*amount, shared_key) });
+    commitments.push(Commitment::new(commitment_mask(shared_key), *amount).calculate());
+  }
+
+  let mut extra = Extra::new(&r * ED25519_BASEPOINT_TABLE, vec![]);
+  if let Some(payment_id) = payment_id {
+    let mut id_vec = vec![];
+    (payment_id ^ payment_id_xor).write(&mut id_vec).unwrap();
+    extra.push(ExtraField::Nonce(id_vec));
+  }
+
+  Transaction {
+    prefix: TransactionPrefix {
+      version: 2,
+      timelock: Timelock::None,
+      inputs,
+      outputs,
+      extra: extra.serialize(),
+    },
+    signatures: vec![],
+    rct_signatures: RctSignatures {
+      base: RctBase { fee: 0, pseudo_outs: vec![], encrypted_amounts, commitments },
+      prunable: RctPrunable::Null,
+    },
+  }
+}
+
+// Create a RingCT transaction with the specified amount of additional keys, deriving each output's
+// shared key with the specified additional key, or the primary key if None
+fn transaction_with_additional_keys(
+  payments: &[(EdwardsPoint, EdwardsPoint, u64, Option<usize>)],
+  additional: usize,
+) -> Transaction {
+  let r = random_scalar(&mut OsRng);
+  let additional = (0 .. additional).map(|_| random_scalar(&mut OsRng)).collect::<Vec<_>>();
+
+  let mut outputs = vec![];
+  let mut encrypted_amounts = vec![];
+  let mut commitments = vec![];
+  for (o, (spend, view, amount, key)) in payments.iter().enumerate() {
+    let r = key.map_or(r, |key| additional[key]);
+    let (view_tag, shared_key, _) = shared_key(None, r * view, o);
+    outputs.push(Output {
+      amount: None,
+      key: ((&shared_key * ED25519_BASEPOINT_TABLE) + spend).compress(),
+      view_tag: Some(view_tag),
+    });
+    encrypted_amounts
+      .push(EncryptedAmount::Compact { amount: amount_encryption(*amount, shared_key) });
+    commitments.push(Commitment::new(commitment_mask(shared_key), *amount).calculate());
+  }
+
+  Transaction {
+    prefix: TransactionPrefix {
+      version: 2,
+      timelock: Timelock::None,
+      inputs: vec![Input::ToKey {
+        amount: None,
+        key_offsets: vec![],
+        key_image: &random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE,
+      }],
+      outputs,
+      extra: Extra::new(
+        &r * ED25519_BASEPOINT_TABLE,
+        additional.iter().map(|r| r * ED25519_BASEPOINT_TABLE).collect(),
+      )
+      .serialize(),
+    },
+    signatures: vec![],
+    rct_signatures: RctSignatures {
+      base: RctBase { fee: 0, pseudo_outs: vec![], encrypted_amounts, commitments },
+      prunable: RctPrunable::Null,
+    },
+  }
+}
+
+fn block(miner_tx: Transaction) -> Block {
+  Block {
+    header: BlockHeader {
+      major_version: 16,
+      minor_version: 16,
+      timestamp: 0,
+      previous: [0; 32],
+      nonce: 0,
+    },
+    miner_tx,
+    txs: vec![],
+  }
+}
+
+// A mock node serving a list of blocks, each of which solely has a miner transaction
+#[derive(Clone, Debug)]
+struct MockRpc(Arc<Mutex<Vec<Block>>>);
+
+// A chain served by a MockRpc, which can be extended or reorganized
+#[derive(Clone, Debug)]
+struct MockChain(Arc<Mutex<Vec<Block>>>);
+impl MockChain {
+  fn new(blocks: Vec<Block>) -> MockChain {
+    MockChain(Arc::new(Mutex::new(blocks)))
+  }
+
+  fn rpc(&self) -> Rpc<MockRpc> {
+    Rpc::from_connection(MockRpc(self.0.clone()))
+  }
+
+  fn push(&self, block: Block) {
+    self.0.lock().unwrap().push(block);
+  }
+
+  // Reorganize the chain, replacing every block from the specified number onwards
+  fn reorg(&self, number: usize, blocks: Vec<Block>) {
+    let mut chain = self.0.lock().unwrap();
+    chain.truncate(number);
+    chain.extend(blocks);
+  }
+}
+
+#[async_trait]
+impl RpcConnection for MockRpc {
+  async fn post(&self, route: &str, body: Vec<u8>) -> Result<Vec<u8>, RpcError> {
+    let blocks = self.0.lock().unwrap();
+    Ok(match route {
+      "get_height" => serde_json::json!({ "height": blocks.len() }).to_string().into_bytes(),
+      "json_rpc" => {
+        let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let number = usize::try_from(request["params"]["height"].as_u64().unwrap()).unwrap();
+        let block = blocks.get(number).ok_or(RpcError::InternalError("unknown block"))?;
+        match request["method"].as_str().unwrap() {
+          "get_block" => {
+            serde_json::json!({ "result": { "blob": hex::encode(block.serialize()) } })
+          }
+          "get_block_header_by_height" => {
+            let hash = hex::encode(block.hash());
+            serde_json::json!({ "result": { "block_header": { "hash": hash } } })
+          }
+          _ => panic!("unsupported method"),
+        }
+        .to_string()
+        .into_bytes()
+      }
+      "get_o_indexes.bin" => {
+        // Every block has one output, so its index is its number
+        let number = blocks
+          .iter()
+          .position(|block| block.miner_tx.hash() == body[(body.len() - 32) ..])
+          .ok_or(RpcError::InternalError("unknown transaction"))?;
+        let mut res = b"\x01\x11\x01\x01\x01\x01\x02\x01\x01".to_vec();
+        res.push(2 << 2);
+        res.push(6);
+        res.extend(b"status");
+        res.push(10);
+        res.push(2 << 2);
+        res.extend(b"OK");
+        res.push(9);
+        res.extend(b"o_indexes");
+        res.push(5 | 0x80);
+        res.push(1 << 2);
+        res.extend(u64::try_from(number).unwrap().to_le_bytes());
+        res
+      }
+      _ => Err(RpcError::InternalError("unsupported route"))?,
+    })
+  }
+}
+
+#[tokio::test]
+async fn scan_from() {
+  let pair = view_pair();
+  let (spend, view) = (pair.spend(), pair.view());
+  let other = view_pair();
+
+  let blocks = Arc::new(Mutex::new(vec![
+    block(miner_transaction(0, spend, view, 1)),
+    block(miner_transaction(1, spend, view, 5)),
+    block(miner_transaction(2, other.spend(), other.view(), 3)),
+  ]));
+  let rpc = Rpc::from_connection(MockRpc(blocks.clone()));
+
+  // Start after the first block, so its output shouldn't be found
+  let mut scanner = Scanner::from_view(pair, Some(Default::default())).scan_from(&rpc, 1);
+
+  let (number, outputs) = scanner.next_block().await.unwrap().unwrap();
+  assert_eq!(number, 1);
+  assert_eq!(outputs.len(), 1);
+  let outputs = outputs[0].ignore_timelock();
+  assert_eq!(outputs.len(), 1);
+  assert_eq!(outputs[0].commitment().amount, 5);
+  assert_eq!(outputs[0].global_index, 1);
+
+  let (number, outputs) = scanner.next_block().await.unwrap().unwrap();
+  assert_eq!(number, 2);
+  assert!(outputs.is_empty());
+
+  // We've reached the tip
+  assert!(scanner.next_block().await.unwrap().is_none());
+  assert_eq!(scanner.next_block_number(), 3);
+
+  // Once a new block is added, polling again should find it
+  blocks.lock().unwrap().push(block(miner_transaction(3, spend, view, 7)));
+  let (number, outputs) = scanner.next_block().await.unwrap().unwrap();
+  assert_eq!(number, 3);
+  assert_eq!(outputs[0].ignore_timelock()[0].commitment().amount, 7);
+  assert!(scanner.next_block().await.unwrap().is_none());
+}
+
+#[cfg(feature = "http-rpc")]
+#[tokio::test(start_paused = true)]
+async fn scan_from_stream() {
+  let spend = &random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE;
+  let view = Zeroizing::new(random_scalar(&mut OsRng));
+  let other = view_pair();
+
+  let chain = MockChain::new(vec![
+    block(miner_transaction(0, spend, &*view * ED25519_BASEPOINT_TABLE, 1)),
+    block(miner_transaction(1, spend, &*view * ED25519_BASEPOINT_TABLE, 5)),
+    block(miner_transaction(2, other.spend(), other.view(), 3)),
+  ]);
+  let rpc = chain.rpc();
+
+  // Start after the first block, so its output shouldn't be found
+  let stream = crate::wallet::scan_from(&rpc, view.clone(), spend, 1);
+  futures_util::pin_mut!(stream);
+
+  let (number, outputs) = stream.next().await.unwrap();
+  assert_eq!(number, 1);
+  assert_eq!(outputs.len(), 1);
+  assert_eq!(outputs[0].commitment().amount, 5);
+  assert_eq!(outputs[0].global_index, 1);
+
+  assert_eq!(stream.next().await.unwrap(), (2, vec![]));
+
+  // We've reached the tip, so the stream should wait for a new block
+  assert!(timeout(Duration::from_secs(60 * 60), stream.next()).await.is_err());
+
+  // Once a new block is added, the stream should find it within the maximum backoff
+  chain.push(block(miner_transaction(3, spend, &*view * ED25519_BASEPOINT_TABLE, 7)));
+  let start = Instant::now();
+  let (number, outputs) = stream.next().await.unwrap();
+  assert!(start.elapsed() <= Duration::from_secs(60));
+  assert_eq!(number, 3);
+  assert_eq!(outputs[0].commitment().amount, 7);
+  assert_eq!(outputs[0].global_index, 3);
+}
+
+#[tokio::test]
+async fn scan_from_with_min_confirmations() {
+  let pair = view_pair();
+  let (spend, view) = (pair.spend(), pair.view());
+
+  let blocks = Arc::new(Mutex::new(vec![
+    block(miner_transaction(0, spend, view, 1)),
+    block(miner_transaction(1, spend, view, 5)),
+  ]));
+  let rpc = Rpc::from_connection(MockRpc(blocks.clone()));
+
+  let mut scanner =
+    Scanner::from_view(pair, Some(Default::default())).scan_from(&rpc, 0).with_min_confirmations(3);
+
+  // Neither block has three confirmations yet
+  assert!(scanner.next_block().await.unwrap().is_none());
+  assert_eq!(scanner.next_block_number(), 0);
+
+  // Adding a block gives the first block three confirmations
+  blocks.lock().unwrap().push(block(miner_transaction(2, spend, view, 7)));
+  let (number, outputs) = scanner.next_block().await.unwrap().unwrap();
+  assert_eq!(number, 0);
+  assert_eq!(outputs[0].ignore_timelock()[0].commitment().amount, 1);
+  // The second block's output is still withheld
+  assert!(scanner.next_block().await.unwrap().is_none());
+
+  // Adding two more blocks gives the second and third blocks three confirmations
+  blocks.lock().unwrap().push(block(miner_transaction(3, spend, view, 9)));
+  blocks.lock().unwrap().push(block(miner_transaction(4, spend, view, 11)));
+  let (number, outputs) = scanner.next_block().await.unwrap().unwrap();
+  assert_eq!(number, 1);
+  assert_eq!(outputs[0].ignore_timelock()[0].commitment().amount, 5);
+  let (number, outputs) = scanner.next_block().await.unwrap().unwrap();
+  assert_eq!(number, 2);
+  assert_eq!(outputs[0].ignore_timelock()[0].commitment().amount, 7);
+  assert!(scanner.next_block().await.unwrap().is_none());
+  assert_eq!(scanner.next_block_number(), 3);
+}
+
+#[tokio::test]
+async fn scan_from_reorg() {
+  let pair = view_pair();
+  let (spend, view) = (pair.spend(), pair.view());
+
+  let chain = MockChain::new(vec![
+    block(miner_transaction(0, spend, view, 1)),
+    block(miner_transaction(1, spend, view, 5)),
+    block(miner_transaction(2, spend, view, 7)),
+  ]);
+  let rpc = chain.rpc();
+
+  let mut scanner = Scanner::from_view(pair, Some(Default::default())).scan_from(&rpc, 0);
+  for _ in 0 .. 3 {
+    scanner.next_block().await.unwrap().unwrap();
+  }
+  assert!(scanner.next_block().await.unwrap().is_none());
+  assert!(scanner.check_reorg().await.unwrap().is_none());
+
+  // Reorganize out the last two blocks, replacing them with a single block
+  chain.reorg(1, vec![block(miner_transaction(1, spend, view, 9))]);
+
+  // The outputs from the reorganized out blocks should be retracted
+  let (fork, retracted) = scanner.check_reorg().await.unwrap().unwrap();
+  assert_eq!(fork, 1);
+  assert_eq!(
+    retracted
+      .iter()
+      .flat_map(Timelocked::ignore_timelock)
+      .map(|output| output.commitment().amount)
+      .collect::<Vec<_>>(),
+    vec![5, 7]
+  );
+  assert_eq!(scanner.next_block_number(), 1);
+  assert!(scanner.check_reorg().await.unwrap().is_none());
+
+  // And the new chain should be scanned
+  let (number, outputs) = scanner.next_block().await.unwrap().unwrap();
+  assert_eq!(number, 1);
+  assert_eq!(outputs[0].ignore_timelock()[0].commitment().amount, 9);
+  assert!(scanner.next_block().await.unwrap().is_none());
+
+  // Truncating the chain should also be detected
+  chain.reorg(1, vec![]);
+  let (fork, retracted) = scanner.check_reorg().await.unwrap().unwrap();
+  assert_eq!(fork, 1);
+  assert_eq!(retracted[0].ignore_timelock()[0].commitment().amount, 9);
+
+  // Blocks which aren't retained can't be rolled back
+  chain.push(block(miner_transaction(1, spend, view, 11)));
+  chain.push(block(miner_transaction(2, spend, view, 13)));
+  let mut scanner = scanner.with_reorg_depth(1);
+  for _ in 0 .. 2 {
+    scanner.next_block().await.unwrap().unwrap();
+  }
+  let retracted = scanner.rollback(0);
+  assert_eq!(scanner.next_block_number(), 0);
+  assert_eq!(retracted.len(), 1);
+  assert_eq!(retracted[0].ignore_timelock()[0].commitment().amount, 13);
+}
+
+#[test]
+fn derived_output_key() {
+  let pair = view_pair();
+  let spend = pair.spend();
+  let scanner = Scanner::from_view(pair.clone(), Some(Default::default()));
+
+  // Build a transaction as a sender would, solely with the sender's view of the keys
+  let r = random_scalar(&mut OsRng);
+  let (view_tag, shared_key, _) = shared_key(None, r * pair.view(), 0);
+  let key = derive_output_key(shared_key, spend);
+  assert_eq!(key, (&shared_key * ED25519_BASEPOINT_TABLE) + spend);
+
+  let tx = Transaction {
+    prefix: TransactionPrefix {
+      version: 2,
+      timelock: Timelock::None,
+      inputs: vec![Input::ToKey {
+        amount: None,
+        key_offsets: vec![],
+        key_image: &random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE,
+      }],
+      outputs: vec![Output { amount: None, key: key.compress(), view_tag: Some(view_tag) }],
+      extra: Extra::new(&r * ED25519_BASEPOINT_TABLE, vec![]).serialize(),
+    },
+    signatures: vec![],
+    rct_signatures: RctSignatures {
+      base: RctBase {
+        fee: 0,
+        pseudo_outs: vec![],
+        encrypted_amounts: vec![EncryptedAmount::Compact {
+          amount: amount_encryption(5, shared_key),
+        }],
+        commitments: vec![Commitment::new(commitment_mask(shared_key), 5).calculate()],
+      },
+      prunable: RctPrunable::Null,
+    },
+  };
+
+  let outputs = scanner.clone().scan_transaction(&tx).ignore_timelock();
+  assert_eq!(outputs.len(), 1);
+  assert_eq!(outputs[0].key(), key);
+  assert_eq!(outputs[0].key_offset(), shared_key);
+  assert_eq!(outputs[0].commitment().amount, 5);
+
+  // A key derived for another spend key isn't detected
+  let mut tx = tx;
+  tx.prefix.outputs[0].key = derive_output_key(shared_key, view_pair().spend()).compress();
+  assert!(scanner.clone().scan_transaction(&tx).ignore_timelock().is_empty());
+}
+
+#[test]
+fn rct_data_length_mismatch() {
+  let pair = view_pair();
+  let (spend, view) = (pair.spend(), pair.view());
+  let mut scanner = Scanner::from_view(pair, Some(Default::default()));
+
+  let tx = transaction(&[(spend, view, 1), (spend, view, 2)]);
+  assert_eq!(scanner.scan_transaction(&tx).ignore_timelock().len(), 2);
+
+  // Missing an encrypted amount
+  let mut malformed = transaction(&[(spend, view, 1), (spend, view, 2)]);
+  malformed.rct_signatures.base.encrypted_amounts.pop();
+  assert!(scanner.scan_transaction(&malformed).ignore_timelock().is_empty());
+
+  // Missing a commitment
+  let mut malformed = transaction(&[(spend, view, 1), (spend, view, 2)]);
+  malformed.rct_signatures.base.commitments.pop();
+  assert!(scanner.scan_transaction(&malformed).ignore_timelock().is_empty());
+
+  // An extra commitment
+  let mut malformed = transaction(&[(spend, view, 1), (spend, view, 2)]);
+  malformed.rct_signatures.base.commitments.push(Commitment::new(Scalar::ONE, 3).calculate());
+  assert!(scanner.scan_transaction(&malformed).ignore_timelock().is_empty());
+
+  // No outputs at all
+  let mut empty = transaction(&[(spend, view, 1)]);
+  empty.prefix.outputs.clear();
+  assert!(scanner.scan_transaction(&empty).ignore_timelock().is_empty());
+}
+
+#[test]
+fn payment_ids() {
+  let pair = view_pair();
+  let (spend, view) = (pair.spend(), pair.view());
+  let mut scanner = Scanner::from_view(pair, Some(Default::default()));
+
+  let scan = |scanner: &mut Scanner, payment_id| {
+    let tx = transaction_with_payment_id(&[(spend, view, 1)], payment_id);
+    let outputs = scanner.scan_transaction(&tx).ignore_timelock();
+    assert_eq!(outputs.len(), 1);
+    outputs[0].metadata.payment_id
+  };
+
+  assert_eq!(scan(&mut scanner, None), None);
+
+  // The legacy 32-byte payment ID is in cleartext and should be surfaced as-is
+  let long = PaymentId::Unencrypted([0xab; 32]);
+  assert_eq!(scan(&mut scanner, Some(long)), Some(long));
+
+  // The 8-byte payment ID should be decrypted
+  let short = PaymentId::Encrypted([0xcd; 8]);
+  assert_eq!(scan(&mut scanner, Some(short)), Some(short));
+}
+
+#[test]
+fn amount_overflow() {
+  let pair = view_pair();
+  let (spend, view) = (pair.spend(), pair.view());
+  let mut scanner = Scanner::from_view(pair, Some(Default::default()));
+
+  let tx = transaction(&[(spend, view, u64::MAX)]);
+  assert_eq!(scanner.scan_transaction(&tx).ignore_timelock()[0].commitment().amount, u64::MAX);
+
+  // The sum of these amounts exceeds a u64
+  let tx = transaction(&[(spend, view, u64::MAX), (spend, view, 1)]);
+  assert!(scanner.scan_transaction(&tx).ignore_timelock().is_empty());
+}
+
+#[test]
+fn scan_above() {
+  let pair = view_pair();
+  let (spend, view) = (pair.spend(), pair.view());
+  // Use a fresh scanner for each scan, as the burning bug protection ignores repeated outputs
+  let scanner = Scanner::from_view(pair, Some(Default::default()));
+
+  let tx = transaction(&[(spend, view, 1), (spend, view, 100), (spend, view, 4), (spend, view, 5)]);
+  let amounts = |outputs: Vec<ReceivedOutput>| {
+    outputs.iter().map(|output| output.commitment().amount).collect::<Vec<_>>()
+  };
+  assert_eq!(
+    amounts(scanner.clone().scan_transaction_above(&tx, 5).ignore_timelock()),
+    vec![100, 5]
+  );
+  assert_eq!(
+    amounts(scanner.clone().scan_transaction_above(&tx, 0).ignore_timelock()),
+    vec![1, 100, 4, 5]
+  );
+
+  // The amount must not be trusted before its commitment is verified
+  let mut malformed = tx.clone();
+  malformed.rct_signatures.base.commitments[1] = Commitment::new(Scalar::ONE, 100).calculate();
+  assert_eq!(
+    amounts(scanner.clone().scan_transaction_above(&malformed, 5).ignore_timelock()),
+    vec![5]
+  );
+}
+
+#[test]
+fn scan_bounds_keys() {
+  let pair = view_pair();
+  let (spend, view) = (pair.spend(), pair.view());
+  let scanner = Scanner::from_view(pair, Some(Default::default()));
+
+  let tx = miner_transaction(0, spend, view, 5);
+  let extra = Extra::read::<&[u8]>(&mut tx.prefix.extra.as_ref()).unwrap();
+  let r = extra.keys().unwrap().0[0];
+  let with_additional = |additional: usize| {
+    let mut tx = tx.clone();
+    tx.prefix.extra = Extra::new(
+      r,
+      (0 .. additional).map(|_| &random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE).collect(),
+    )
+    .serialize();
+    tx
+  };
+
+  // Non-positional additional keys are scanned with, up to the bound
+  let tx = with_additional(MAX_SCANNED_KEYS - 1);
+  assert_eq!(scanner.clone().scan_transaction(&tx).ignore_timelock().len(), 1);
+
+  // An abusive amount of additional keys causes the transaction to be ignored
+  let tx = with_additional(MAX_SCANNED_KEYS);
+  assert!(scanner.clone().scan_transaction(&tx).ignore_timelock().is_empty());
+  let tx = with_additional(10_000);
+  assert!(scanner.clone().scan_transaction(&tx).ignore_timelock().is_empty());
+}
+
+#[test]
+fn amount_decryption_matches_scan() {
+  let spend = &random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE;
+  let view = Zeroizing::new(random_scalar(&mut OsRng));
+  let pair = ViewPair::new(spend, view.clone());
+  let view_point = pair.view();
+  let mut scanner = Scanner::from_view(pair, Some(Default::default()));
+
+  let tx = transaction(&[(spend, view_point, 3), (spend, view_point, 9)]);
+  let outputs = scanner.scan_transaction(&tx).ignore_timelock();
+  assert_eq!(outputs.len(), 2);
+
+  let extra = Extra::read::<&[u8]>(&mut tx.prefix.extra.as_ref()).unwrap();
+  let r = extra.keys().unwrap().0[0];
+  for (o, output) in outputs.iter().enumerate() {
+    let (_, shared_key, _) = shared_key(None, view.deref() * r, o);
+    let (mask, amount) =
+      amount_decryption(&tx.rct_signatures.base.encrypted_amounts[o], shared_key);
+    assert_eq!(mask, commitment_mask(shared_key));
+    assert!(Commitment::new(mask, amount) == output.commitment());
+    assert_eq!(Commitment::new(mask, amount).calculate(), tx.rct_signatures.base.commitments[o]);
+  }
+
+  // The wrong shared key should produce a commitment which doesn't open
+  let (_, wrong, _) = shared_key(None, view.deref() * r, 2);
+  let (mask, amount) = amount_decryption(&tx.rct_signatures.base.encrypted_amounts[0], wrong);
+  assert!(Commitment::new(mask, amount).calculate() != tx.rct_signatures.base.commitments[0]);
+}
+
+#[test]
+fn scan_v1() {
+  let pair = view_pair();
+  let (spend, view) = (pair.spend(), pair.view());
+  let scanner = Scanner::from_view(pair, Some(Default::default()));
+
+  // A pre-RingCT transaction, with cleartext amounts
+  let r = random_scalar(&mut OsRng);
+  let mut outputs = vec![];
+  for (o, amount) in [3, 5].into_iter().enumerate() {
+    let (_, shared_key, _) = shared_key(None, r * view, o);
+    outputs.push(Output {
+      amount: Some(amount),
+      key: ((&shared_key * ED25519_BASEPOINT_TABLE) + spend).compress(),
+      view_tag: None,
+    });
+  }
+  let tx = Transaction {
+    prefix: TransactionPrefix {
+      version: 1,
+      timelock: Timelock::None,
+      inputs: vec![Input::ToKey {
+        amount: Some(10),
+        key_offsets: vec![],
+        key_image: &random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE,
+      }],
+      outputs,
+      extra: Extra::new(&r * ED25519_BASEPOINT_TABLE, vec![]).serialize(),
+    },
+    signatures: vec![],
+    rct_signatures: RctSignatures {
+      base: RctBase { fee: 2, pseudo_outs: vec![], encrypted_amounts: vec![], commitments: vec![] },
+      prunable: RctPrunable::Null,
+    },
+  };
+
+  // v1 outputs aren't spendable, so they aren't found by default
+  assert!(scanner.clone().scan_transaction(&tx).ignore_timelock().is_empty());
+
+  let outputs = scanner.clone().scan_legacy_transaction(&tx).ignore_timelock();
+  assert_eq!(outputs.len(), 2);
+  for (output, amount) in outputs.iter().zip([3, 5]) {
+    assert!(output.commitment() == Commitment::new(Scalar::ONE, amount));
+  }
+
+  // RCT transactions are still found when scanning for legacy transactions
+  let tx = transaction(&[(spend, view, 7)]);
+  assert_eq!(
+    scanner.clone().scan_legacy_transaction(&tx).ignore_timelock()[0].commitment().amount,
+    7
+  );
+}
+
+#[test]
+fn rct_miner_transaction() {
+  let pair = view_pair();
+  let (spend, view) = (pair.spend(), pair.view());
+  let scanner = Scanner::from_view(pair, Some(Default::default()));
+
+  // RingCT miner transactions have cleartext amounts, committed to with a mask of 1
+  let mut tx = miner_transaction(0, spend, view, 5);
+  let outputs = scanner.clone().scan_transaction(&tx).ignore_timelock();
+  assert_eq!(outputs.len(), 1);
+  assert!(outputs[0].commitment() == Commitment::new(Scalar::ONE, 5));
+
+  // An explicit commitment matching the implicit one is accepted
+  tx.rct_signatures.base.commitments = vec![Commitment::new(Scalar::ONE, 5).calculate()];
+  let outputs = scanner.clone().scan_transaction(&tx).ignore_timelock();
+  assert_eq!(outputs.len(), 1);
+  assert!(outputs[0].commitment() == Commitment::new(Scalar::ONE, 5));
+
+  // An explicit commitment to another amount isn't
+  tx.rct_signatures.base.commitments = vec![Commitment::new(Scalar::ONE, 6).calculate()];
+  assert!(scanner.clone().scan_transaction(&tx).ignore_timelock().is_empty());
+
+  // Nor is a cleartext amount in a RingCT transaction which isn't a miner transaction
+  tx.rct_signatures.base.commitments = vec![];
+  tx.prefix.inputs = vec![Input::ToKey {
+    amount: None,
+    key_offsets: vec![],
+    key_image: &random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE,
+  }];
+  assert!(scanner.clone().scan_transaction(&tx).ignore_timelock().is_empty());
+}
+
+#[test]
+fn derivations() {
+  let pair = view_pair();
+  let (spend, view) = (pair.spend(), pair.view());
+
+  let tx = transaction_with_derivations(
+    &[(spend, view, 1, Derivation::Traditional), (spend, view, 2, Derivation::Unique)],
+    None,
+  );
+
+  // A scanner tracking the burning bug solely finds outputs with the traditional derivation
+  let mut scanner = Scanner::from_view(pair.clone(), Some(Default::default()));
+  let outputs = scanner.scan_transaction(&tx).ignore_timelock();
+  assert_eq!(outputs.len(), 1);
+  assert_eq!(outputs[0].commitment().amount, 1);
+  assert_eq!(outputs[0].derivation(), Some(Derivation::Traditional));
+
+  // A guaranteed scanner solely finds outputs with the unique derivation
+  let mut scanner = Scanner::from_view(pair, None);
+  let guaranteed = scanner.scan_transaction(&tx).ignore_timelock();
+  assert_eq!(guaranteed.len(), 1);
+  assert_eq!(guaranteed[0].commitment().amount, 2);
+  assert_eq!(guaranteed[0].derivation(), Some(Derivation::Unique));
+
+  // The derivation is preserved through serialization
+  for output in outputs.iter().chain(&guaranteed) {
+    let read = ReceivedOutput::read::<&[u8]>(&mut output.serialize().as_ref()).unwrap();
+    assert_eq!(&read, output);
+  }
+
+  // Metadata serialized before the derivation was recorded, which lacks the leading marker and
+  // derivation, is still readable, with an unknown derivation
+  for output in outputs.iter().chain(&guaranteed) {
+    let mut legacy = output.absolute.serialize();
+    legacy.extend(output.data.serialize());
+    legacy.extend(&output.metadata.serialize()[2 ..]);
+    let read = ReceivedOutput::read::<&[u8]>(&mut legacy.as_ref()).unwrap();
+    assert_eq!(read.derivation(), None);
+    assert_eq!(read.metadata.subaddress, output.metadata.subaddress);
+    assert_eq!(read.commitment(), output.commitment());
+
+    // Which is written back as it was read
+    assert_eq!(read.serialize(), legacy);
+  }
+}
+
+#[test]
+fn multisig_key_offset() {
+  // Two parties, each holding an additive share of the spend key
+  let shares = [random_scalar(&mut OsRng), random_scalar(&mut OsRng)];
+  let pair = ViewPair::new(
+    &(shares[0] + shares[1]) * ED25519_BASEPOINT_TABLE,
+    Zeroizing::new(random_scalar(&mut OsRng)),
+  );
+
+  let tx = transaction(&[(pair.spend(), pair.view(), 1), (pair.spend(), pair.view(), 2)]);
+  let mut scanner = Scanner::from_view(pair, Some(Default::default()));
+  let outputs = scanner.scan_transaction(&tx).ignore_timelock();
+  assert_eq!(outputs.len(), 2);
+  assert!(outputs[0].key_offset() != outputs[1].key_offset());
+
+  for output in &outputs {
+    // The first party offsets its share by the output's key offset, the second doesn't
+    let offset_shares = [shares[0] + output.key_offset(), shares[1]];
+    assert_eq!(
+      (&offset_shares[0] * ED25519_BASEPOINT_TABLE) + (&offset_shares[1] * ED25519_BASEPOINT_TABLE),
+      output.key()
+    );
+    assert_eq!(&(offset_shares[0] + offset_shares[1]) * ED25519_BASEPOINT_TABLE, output.key());
+  }
+}
+
+#[test]
+fn scan_cache() {
+  let pair = view_pair();
+  let tx = transaction(&[(pair.spend(), pair.view(), 1), (pair.spend(), pair.view(), 2)]);
+  let hash = tx.hash();
+
+  // A Scanner tracking the burning bug won't return outputs whose keys it has already seen, so
+  // re-scanning without a cache returns nothing
+  let mut scanner = Scanner::from_view(pair.clone(), Some(Default::default()));
+  assert_eq!(scanner.scan_transaction(&tx).ignore_timelock().len(), 2);
+  assert!(scanner.scan_transaction(&tx).ignore_timelock().is_empty());
+
+  let mut cache = Scanner::from_view(pair, Some(Default::default())).cached();
+  assert!(!cache.is_cached(&hash));
+  let outputs = cache.scan_transaction(&tx).ignore_timelock();
+  assert_eq!(outputs.len(), 2);
+  assert!(cache.is_cached(&hash));
+
+  // The second scan is served from the cache, returning the same outputs
+  assert_eq!(cache.scan_transaction(&tx).ignore_timelock(), outputs);
+
+  // Once invalidated, the transaction is scanned again, its outputs no longer considered seen
+  assert!(cache.invalidate(&hash));
+  assert!(!cache.is_cached(&hash));
+  assert!(!cache.invalidate(&hash));
+  assert_eq!(cache.scan_transaction(&tx).ignore_timelock(), outputs);
+  assert!(cache.is_cached(&hash));
+}
+
+#[test]
+fn key_image_generator() {
+  let spend = Zeroizing::new(random_scalar(&mut OsRng));
+  let pair = ViewPair::new(
+    spend.deref() * ED25519_BASEPOINT_TABLE,
+    Zeroizing::new(random_scalar(&mut OsRng)),
+  );
+  let mut scanner = Scanner::from_view(pair.clone(), Some(Default::default()));
+
+  let tx = transaction(&[(pair.spend(), pair.view(), 1), (pair.spend(), pair.view(), 2)]);
+  let outputs = scanner
+    .scan_transaction(&tx)
+    .ignore_timelock()
+    .into_iter()
+    .map(|output| SpendableOutput { output, global_index: 0 })
+    .collect::<Vec<_>>();
+  assert_eq!(outputs.len(), 2);
+
+  for output in &outputs {
+    assert_eq!(output.key_image_generator(), hash_to_point(&output.key()));
+
+    // With the private spend key, the generator yields the output's key image
+    let secret = Zeroizing::new(spend.deref() + output.key_offset());
+    assert_eq!(secret.deref() * ED25519_BASEPOINT_TABLE, output.key());
+    assert_eq!(secret.deref() * output.key_image_generator(), generate_key_image(&secret));
+  }
+  assert!(outputs[0].key_image_generator() != outputs[1].key_image_generator());
+}
+
+#[test]
+fn filter_unspent() {
+  let spend = Zeroizing::new(random_scalar(&mut OsRng));
+  let pair = ViewPair::new(
+    spend.deref() * ED25519_BASEPOINT_TABLE,
+    Zeroizing::new(random_scalar(&mut OsRng)),
+  );
+  let mut scanner = Scanner::from_view(pair.clone(), Some(Default::default()));
+
+  let tx = transaction(&[
+    (pair.spend(), pair.view(), 1),
+    (pair.spend(), pair.view(), 2),
+    (pair.spend(), pair.view(), 3),
+  ]);
+  let outputs = scanner
+    .scan_transaction(&tx)
+    .ignore_timelock()
+    .into_iter()
+    .map(|output| SpendableOutput { output, global_index: 0 })
+    .collect::<Vec<_>>();
+  assert_eq!(outputs.len(), 3);
+
+  // Without any spent key images, every output is unspent
+  assert_eq!(crate::wallet::filter_unspent(outputs.clone(), &HashSet::new(), &spend), outputs);
+
+  // The second output was spent, alongside some output which isn't ours
+  let spent = HashSet::from([
+    outputs[1].key_image(&spend).compress(),
+    generate_key_image(&Zeroizing::new(random_scalar(&mut OsRng))).compress(),
+  ]);
+  assert_eq!(
+    crate::wallet::filter_unspent(outputs.clone(), &spent, &spend),
+    vec![outputs[0].clone(), outputs[2].clone()]
+  );
+}
+
+#[tokio::test]
+async fn scan_with_key_images() {
+  let spend = Zeroizing::new(random_scalar(&mut OsRng));
+  let view = Zeroizing::new(random_scalar(&mut OsRng));
+  let pair = ViewPair::new(spend.deref() * ED25519_BASEPOINT_TABLE, view.clone());
+
+  let tx = miner_transaction(0, pair.spend(), pair.view(), 5);
+  let mined = block(tx.clone());
+  let chain = MockChain::new(vec![mined.clone()]);
+  let rpc = chain.rpc();
+
+  let mut scanner = Scanner::from_view(pair, Some(Default::default()));
+  let outputs = scanner.scan_with_key_images(&rpc, &mined, &spend).await.unwrap();
+  assert_eq!(outputs.len(), 1);
+  let outputs = outputs[0].ignore_timelock();
+  assert_eq!(outputs.len(), 1);
+  assert_eq!(outputs[0].output.commitment().amount, 5);
+
+  // Independently derive the output's private key from the transaction key and view key
+  let tx_key = Extra::read::<&[u8]>(&mut tx.prefix.extra.as_ref()).unwrap().keys().unwrap().0[0];
+  let (_, shared, _) = shared_key(None, view.deref() * tx_key, 0);
+  let key = Zeroizing::new(spend.deref() + shared);
+  assert_eq!(key.deref() * ED25519_BASEPOINT_TABLE, outputs[0].output.key());
+  assert_eq!(outputs[0].key_image, generate_key_image(&key));
+  assert_eq!(outputs[0].key_image, outputs[0].output.key_image(&spend));
+}
+
+#[test]
+fn spendable_output_ordering() {
+  let pair = view_pair();
+  let (spend, view) = (pair.spend(), pair.view());
+  let mut scanner = Scanner::from_view(pair, Some(Default::default()));
+
+  let tx_a = transaction(&[(spend, view, 5), (spend, view, 3), (spend, view, 5), (spend, view, 1)]);
+  let tx_b = transaction(&[(spend, view, 3)]);
+  let mut outputs = vec![];
+  for tx in [&tx_a, &tx_b] {
+    for output in scanner.scan_transaction(tx).ignore_timelock() {
+      outputs.push(SpendableOutput { output, global_index: 0 });
+    }
+  }
+  assert_eq!(outputs.len(), 5);
+
+  let id = |output: &SpendableOutput| {
+    (output.commitment().amount, output.output.absolute.tx, output.output.absolute.o)
+  };
+  // Outputs with equal amounts are ordered by their transaction hash, then their index
+  let (first_3, second_3) = if tx_a.hash() < tx_b.hash() {
+    ((tx_a.hash(), 1), (tx_b.hash(), 0))
+  } else {
+    ((tx_b.hash(), 0), (tx_a.hash(), 1))
+  };
+  let expected = vec![
+    (1, tx_a.hash(), 3),
+    (3, first_3.0, first_3.1),
+    (3, second_3.0, second_3.1),
+    (5, tx_a.hash(), 0),
+    (5, tx_a.hash(), 2),
+  ];
+
+  let mut sorted = outputs.clone();
+  sorted.sort();
+  assert_eq!(sorted.iter().map(id).collect::<Vec<_>>(), expected);
+
+  // The ordering doesn't depend on the order the outputs were originally in
+  outputs.reverse();
+  outputs.sort();
+  assert_eq!(outputs, sorted);
+}
+
+#[test]
+fn view_pair_scan() {
+  let pair = view_pair();
+  let other = view_pair();
+  let tx = transaction(&[
+    (pair.spend(), pair.view(), 1),
+    (other.spend(), other.view(), 2),
+    (pair.spend(), pair.view(), 3),
+  ]);
+
+  let outputs = pair.scan_transaction(&tx).ignore_timelock();
+  assert_eq!(outputs.iter().map(|output| output.commitment().amount).collect::<Vec<_>>(), [1, 3]);
+  // This is equivalent to scanning with a fresh Scanner
+  assert_eq!(
+    Scanner::from_view(pair.clone(), Some(Default::default()))
+      .scan_transaction(&tx)
+      .ignore_timelock(),
+    outputs
+  );
+  // As each scan is independent, scanning the transaction again returns the same outputs
+  assert_eq!(pair.scan_transaction(&tx).ignore_timelock(), outputs);
+
+  assert_eq!(other.scan_transaction(&tx).ignore_timelock().len(), 1);
+  assert!(view_pair().scan_transaction(&tx).ignore_timelock().is_empty());
+}
+
+#[tokio::test]
+async fn scan_transactions_aggregate() {
+  let spend = &random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE;
+  let view = Zeroizing::new(random_scalar(&mut OsRng));
+  let pair = ViewPair::new(spend, view.clone());
+  let other = view_pair();
+
+  let chain = MockChain::new(vec![
+    block(miner_transaction(0, spend, pair.view(), 1)),
+    block(miner_transaction(1, spend, pair.view(), 5)),
+    block(miner_transaction(2, other.spend(), other.view(), 3)),
+    block(miner_transaction(3, spend, pair.view(), 7)),
+  ]);
+  let rpc = chain.rpc();
+  let txs = chain.0.lock().unwrap().iter().map(|block| block.miner_tx.clone()).collect::<Vec<_>>();
+
+  // The aggregate should match scanning each transaction individually, in order
+  let outputs = crate::wallet::scan_transactions(&rpc, &txs, view.clone(), spend).await.unwrap();
+  let mut expected = vec![];
+  for tx in &txs {
+    for output in pair.scan_transaction(tx).ignore_timelock() {
+      expected.push(SpendableOutput::from(&rpc, output).await.unwrap());
+    }
+  }
+  assert_eq!(outputs, expected);
+  assert_eq!(
+    outputs.iter().map(|output| output.commitment().amount).collect::<Vec<_>>(),
+    [1, 5, 7]
+  );
+  assert_eq!(outputs.iter().map(|output| output.global_index).collect::<Vec<_>>(), [0, 1, 3]);
+
+  assert!(crate::wallet::scan_transactions(&rpc, &[], view.clone(), spend)
+    .await
+    .unwrap()
+    .is_empty());
+
+  // Outputs with the same key are only returned once
+  let mut duplicated = txs.clone();
+  duplicated.push(txs[0].clone());
+  assert_eq!(
+    crate::wallet::scan_transactions(&rpc, &duplicated, view, spend).await.unwrap(),
+    outputs
+  );
+}
+
+#[test]
+fn additional_keys() {
+  let pair = view_pair();
+  let (spend, view) = (pair.spend(), pair.view());
+  let amounts = |tx: &Transaction| {
+    Scanner::from_view(pair.clone(), Some(Default::default()))
+      .scan_transaction(tx)
+      .ignore_timelock()
+      .iter()
+      .map(|output| output.commitment().amount)
+      .collect::<Vec<_>>()
+  };
+
+  // Solely the primary key
+  let tx = transaction_with_additional_keys(&[(spend, view, 1, None), (spend, view, 2, None)], 0);
+  assert_eq!(amounts(&tx), [1, 2]);
+
+  // As many additional keys as outputs, where each output uses its positional additional key
+  let tx = transaction_with_additional_keys(
+    &[(spend, view, 1, Some(0)), (spend, view, 2, Some(1)), (spend, view, 3, Some(2))],
+    3,
+  );
+  assert_eq!(amounts(&tx), [1, 2, 3]);
+  // Outputs may still use the primary key
+  let tx =
+    transaction_with_additional_keys(&[(spend, view, 1, None), (spend, view, 2, Some(1))], 2);
+  assert_eq!(amounts(&tx), [1, 2]);
+  // Yet an output using another output's additional key isn't scanned
+  let tx =
+    transaction_with_additional_keys(&[(spend, view, 1, Some(1)), (spend, view, 2, Some(0))], 2);
+  assert!(amounts(&tx).is_empty());
+
+  // When the amount of additional keys doesn't match the amount of outputs, every additional key
+  // is tried for every output
+  let tx = transaction_with_additional_keys(
+    &[(spend, view, 1, Some(1)), (spend, view, 2, None), (spend, view, 3, Some(0))],
+    2,
+  );
+  assert_eq!(amounts(&tx), [1, 2, 3]);
+  let tx = transaction_with_additional_keys(&[(spend, view, 1, Some(2))], 4);
+  assert_eq!(amounts(&tx), [1]);
+}
+
+// A straightforward scan for outputs to the standard address of the pair with the specified keys,
+// returning the index and amount of each output found
+//
+// This calculates the ECDH anew for every output, serving as a reference for the Scanner
+fn reference_scan(spend: EdwardsPoint, view: &Scalar, tx: &Transaction) -> Vec<(usize, u64)> {
+  let extra = Extra::read::<&[u8]>(&mut tx.prefix.extra.as_ref()).unwrap();
+  let (tx_keys, additional) = extra.keys().unwrap();
+  let outputs = tx.prefix.outputs.len();
+
+  let mut res = vec![];
+  for (o, output) in tx.prefix.outputs.iter().enumerate() {
+    let mut keys = tx_keys.clone();
+    match &additional {
+      None => {}
+      Some(additional) if additional.len() == outputs => keys.push(additional[o]),
+      Some(additional) => keys.extend(additional),
+    }
+
+    'keys: for key in keys {
+      for derivation in [None, Some(uniqueness(&tx.prefix.inputs))] {
+        let (view_tag, shared_key, _) = shared_key(derivation, view * key, o);
+        if output.view_tag != Some(view_tag) {
+          continue;
+        }
+        if (output.key.decompress().unwrap() - (&shared_key * ED25519_BASEPOINT_TABLE)) != spend {
+          continue;
+        }
+        let (mask, amount) =
+          amount_decryption(&tx.rct_signatures.base.encrypted_amounts[o], shared_key);
+        if Commitment::new(mask, amount).calculate() == tx.rct_signatures.base.commitments[o] {
+          res.push((o, amount));
+        }
+        break 'keys;
+      }
+    }
+  }
+  res
+}
+
+// Create transactions with outputs to the specified pair and to others, using a mix of primary
+// and additional keys
+fn scan_transactions(spend: EdwardsPoint, view: EdwardsPoint, count: usize) -> Vec<Transaction> {
+  (0 .. count)
+    .map(|i| {
+      let other = view_pair();
+      let (other_spend, other_view) = (other.spend(), other.view());
+      let amount = u64::try_from(i).unwrap() + 1;
+      match i % 4 {
+        0 => transaction_with_additional_keys(
+          &[(spend, view, amount, None), (other_spend, other_view, 1, None)],
+          0,
+        ),
+        1 => transaction_with_additional_keys(
+          &[(other_spend, other_view, 1, Some(0)), (spend, view, amount, Some(1))],
+          2,
+        ),
+        2 => transaction_with_additional_keys(
+          &[(other_spend, other_view, 1, None), (spend, view, amount, Some(2))],
+          3,
+        ),
+        _ => transaction_with_additional_keys(
+          &[(other_spend, other_view, 1, None), (other_spend, other_view, 2, None)],
+          0,
+        ),
+      }
+    })
+    .collect()
+}
+
+#[test]
+fn scan_matches_reference() {
+  let view = Zeroizing::new(random_scalar(&mut OsRng));
+  let pair = ViewPair::new(&random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE, view.clone());
+  let mut scanner = Scanner::from_view(pair.clone(), Some(Default::default()));
+
+  for tx in scan_transactions(pair.spend(), pair.view(), 32) {
+    let expected = reference_scan(pair.spend(), &view, &tx);
+    let scanned = scanner
+      .scan_transaction(&tx)
+      .ignore_timelock()
+      .iter()
+      .map(|output| (usize::from(output.absolute.o), output.commitment().amount))
+      .collect::<Vec<_>>();
+    assert_eq!(scanned, expected);
+  }
+}
@@ -0,0 +1,312 @@
+use core::ops::Deref;
+
+use hex_literal::hex;
+
+use zeroize::Zeroizing;
+use rand_core::{RngCore, OsRng};
+
+use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, scalar::Scalar};
+
+use crate::{
+  Commitment, random_scalar, hash,
+  transaction::{
+    Input, Output, Timelock, TransactionPrefix, Transaction, PrunedTransaction, TxSummary,
+  },
+  ringct::{
+    generate_key_image,
+    clsag::{ClsagInput, Clsag},
+    bulletproofs::Bulletproofs,
+    EncryptedAmount, RctType, RctBase, RctPrunable, RctSignatures,
+  },
+  wallet::Decoys,
+};
+
+const RING_LEN: u64 = 16;
+
+// Create a CLSAG/Bulletproofs+ transaction with one input and two outputs
+// The transaction is well-formed, yet its signatures don't sign the transaction itself
+fn transaction() -> Transaction {
+  let mut ring = vec![];
+  for _ in 0 .. RING_LEN {
+    ring.push([
+      &random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE,
+      Commitment::new(random_scalar(&mut OsRng), OsRng.next_u64()).calculate(),
+    ]);
+  }
+  let spend = Zeroizing::new(random_scalar(&mut OsRng));
+  let mask = random_scalar(&mut OsRng);
+  ring[0] = [spend.deref() * ED25519_BASEPOINT_TABLE, Commitment::new(mask, 3).calculate()];
+  let image = generate_key_image(&spend);
+
+  let outputs =
+    vec![Commitment::new(random_scalar(&mut OsRng), 1), Commitment::new(Scalar::ONE, 2)];
+  let sum_outputs = outputs.iter().map(|output| output.mask).sum();
+
+  let (clsag, pseudo_out) = Clsag::sign(
+    &mut OsRng,
+    vec![(
+      spend,
+      image,
+      ClsagInput::new(
+        Commitment::new(mask, 3),
+        Decoys { i: 0, offsets: (1 ..= RING_LEN).collect(), ring },
+      )
+      .unwrap(),
+    )],
+    sum_outputs,
+    [0; 32],
+  )
+  .swap_remove(0);
+
+  Transaction {
+    prefix: TransactionPrefix {
+      version: 2,
+      timelock: Timelock::None,
+      inputs: vec![Input::ToKey {
+        amount: None,
+        key_offsets: (1 ..= RING_LEN).collect(),
+        key_image: image,
+      }],
+      outputs: outputs
+        .iter()
+        .map(|_| Output {
+          amount: None,
+          key: (&random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE).compress(),
+          view_tag: Some(0),
+        })
+        .collect(),
+      extra: vec![],
+    },
+    signatures: vec![],
+    rct_signatures: RctSignatures {
+      base: RctBase {
+        fee: 0,
+        pseudo_outs: vec![],
+        encrypted_amounts: outputs
+          .iter()
+          .map(|_| EncryptedAmount::Compact { amount: OsRng.next_u64().to_le_bytes() })
+          .collect(),
+        commitments: outputs.iter().map(Commitment::calculate).collect(),
+      },
+      prunable: RctPrunable::Clsag {
+        bulletproofs: Bulletproofs::prove(&mut OsRng, &outputs, true).unwrap(),
+        clsags: vec![clsag],
+        pseudo_outs: vec![pseudo_out],
+      },
+    },
+  }
+}
+
+#[test]
+fn pruned_transaction() {
+  let tx = transaction();
+  // Sanity check the transaction serializes
+  assert_eq!(Transaction::read::<&[u8]>(&mut tx.serialize().as_ref()).unwrap(), tx);
+
+  let pruned = tx.pruned();
+  let serialized = pruned.serialize();
+  // The pruned form is a prefix of the full serialization, omitting the prunable data
+  assert!(serialized.len() < tx.serialize().len());
+  assert_eq!(serialized, tx.serialize()[.. serialized.len()]);
+  assert_eq!(PrunedTransaction::read::<&[u8]>(&mut serialized.as_ref()).unwrap(), pruned);
+
+  // The prunable hash is the hash of the prunable data's serialization
+  let prunable_hash = tx.prunable_hash().unwrap();
+  assert_eq!(prunable_hash, hash(&tx.serialize()[serialized.len() ..]));
+  // And combines with the pruned transaction into the full transaction's hash
+  assert_eq!(pruned.hash(prunable_hash), Some(tx.hash()));
+  assert!(pruned.hash([0; 32]) != Some(tx.hash()));
+}
+
+#[test]
+fn pruned_miner_transaction() {
+  let tx = Transaction {
+    prefix: TransactionPrefix {
+      version: 2,
+      timelock: Timelock::Block(60),
+      inputs: vec![Input::Gen(0)],
+      outputs: vec![Output {
+        amount: Some(1),
+        key: (&random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE).compress(),
+        view_tag: Some(0),
+      }],
+      extra: vec![],
+    },
+    signatures: vec![],
+    rct_signatures: RctSignatures {
+      base: RctBase { fee: 0, pseudo_outs: vec![], encrypted_amounts: vec![], commitments: vec![] },
+      prunable: RctPrunable::Null,
+    },
+  };
+
+  // Without prunable data, the prunable hash is defined as 0
+  assert_eq!(tx.prunable_hash(), Some([0; 32]));
+
+  // Miner transactions have nothing to prune
+  let pruned = tx.pruned();
+  assert_eq!(pruned.serialize(), tx.serialize());
+  assert_eq!(PrunedTransaction::read::<&[u8]>(&mut tx.serialize().as_ref()).unwrap(), pruned);
+  assert_eq!(pruned.hash([0; 32]), Some(tx.hash()));
+
+  // v1 transactions don't have a prunable hash
+  let mut v1 = tx;
+  v1.prefix.version = 1;
+  assert_eq!(v1.prunable_hash(), None);
+  assert_eq!(v1.pruned().hash([0; 32]), None);
+}
+
+#[test]
+fn summary() {
+  let mut tx = transaction();
+  tx.rct_signatures.base.fee = 1234;
+  assert_eq!(
+    tx.summary(),
+    TxSummary {
+      version: 2,
+      inputs: 1,
+      outputs: 2,
+      rct_type: RctType::BulletproofsPlus,
+      fee: Some(1234)
+    }
+  );
+
+  let miner_tx = Transaction {
+    prefix: TransactionPrefix {
+      version: 2,
+      timelock: Timelock::Block(60),
+      inputs: vec![Input::Gen(0)],
+      outputs: vec![Output {
+        amount: Some(1),
+        key: (&random_scalar(&mut OsRng) * ED25519_BASEPOINT_TABLE).compress(),
+        view_tag: Some(0),
+      }],
+      extra: vec![],
+    },
+    signatures: vec![],
+    rct_signatures: RctSignatures {
+      base: RctBase { fee: 0, pseudo_outs: vec![], encrypted_amounts: vec![], commitments: vec![] },
+      prunable: RctPrunable::Null,
+    },
+  };
+  // Miner transactions don't pay a fee
+  assert_eq!(
+    miner_tx.summary(),
+    TxSummary { version: 2, inputs: 1, outputs: 1, rct_type: RctType::Null, fee: None }
+  );
+}
+
+#[test]
+fn rct_accessors() {
+  let tx = transaction();
+  for o in 0 .. tx.prefix.outputs.len() {
+    assert_eq!(tx.rct_commitment(o), Some(tx.rct_signatures.base.commitments[o]));
+    assert_eq!(tx.rct_ecdh(o), Some(&tx.rct_signatures.base.encrypted_amounts[o]));
+  }
+  assert!(tx.rct_commitment(tx.prefix.outputs.len()).is_none());
+  assert!(tx.rct_ecdh(tx.prefix.outputs.len()).is_none());
+
+  // If the RCT data doesn't line up with the outputs, no output's data is returned
+  let mut malformed = tx;
+  malformed.rct_signatures.base.commitments.pop();
+  malformed.rct_signatures.base.encrypted_amounts.push(EncryptedAmount::Compact { amount: [0; 8] });
+  for o in 0 .. malformed.prefix.outputs.len() {
+    assert!(malformed.rct_commitment(o).is_none());
+    assert!(malformed.rct_ecdh(o).is_none());
+  }
+}
+
+#[test]
+fn output_components() {
+  let tx = transaction();
+  for (o, output) in tx.prefix.outputs.iter().enumerate() {
+    let (key, encrypted_amount, commitment) = tx.output_components(o).unwrap();
+    assert_eq!(key.compress(), output.key);
+    assert_eq!(encrypted_amount, &tx.rct_signatures.base.encrypted_amounts[o]);
+    assert_eq!(commitment, tx.rct_signatures.base.commitments[o]);
+  }
+  assert!(tx.output_components(tx.prefix.outputs.len()).is_none());
+
+  // A mismatched amount of encrypted amounts or commitments causes every output to be rejected
+  let mut missing_amount = tx.clone();
+  missing_amount.rct_signatures.base.encrypted_amounts.pop();
+  let mut missing_commitment = tx.clone();
+  missing_commitment.rct_signatures.base.commitments.pop();
+  let mut extra_commitment = tx.clone();
+  extra_commitment.rct_signatures.base.commitments.push(&Scalar::ONE * ED25519_BASEPOINT_TABLE);
+  for tx in [missing_amount, missing_commitment, extra_commitment] {
+    for o in 0 .. tx.prefix.outputs.len() {
+      assert!(tx.output_components(o).is_none());
+    }
+  }
+
+  // Outputs with cleartext amounts don't have components
+  let mut cleartext = tx;
+  cleartext.prefix.outputs[0].amount = Some(1);
+  assert!(cleartext.output_components(0).is_none());
+  assert!(cleartext.output_components(1).is_some());
+}
+
+#[test]
+fn signature_hash() {
+  // A CLSAG/Bulletproofs+ transaction, as produced by `transaction()`
+  let tx = Transaction::read::<&[u8]>(
+    &mut hex!(
+      "0200010200100102030405060708090a0b0c0d0e0f108f704b521d019d1bb36bd4bebe80876812aaefea1c5717c2"
+      "dd25c80dfba4ae59020003f871f2dbbc554eb498c5d9d0cc10d3e0045023a1a085d5a00cad83af51ee6fe5000003"
+      "de48f5163e092ede1391fd65ba76cd2b449c79f875523980a2165fc708b1883400000600a5ceae65362855cf26bd"
+      "2e5d6d9e45ebb5a0ef73d56ef98d78e952b7e1b99b33e6aff308fec48898d833934b1efac4a7762484630a061717"
+      "8d0e33f32e0e113ea846869d464b0b6ff13b2997049cda7d0129a8a2c3262d0bc0a990fb4283349ed3357ea91883"
+      "40c160d27ff8601fb38e41f9b3eb149e302858ec7da80d4379b06059fae46b1b95117000cb0919ac5d8d3331f0f5"
+      "896f50e6e10d3e6ec20dadadcb0acfdd4e01ceca78022e1718a820bd9e7f92b7cef09bef20f92eb761caa69dd41a"
+      "7b3536900ef106d5c2f4e949271d0a78d2b8ba4f432a8a4c6c8b2f16f525f79bf00d612f40868a904e4cf5b21c97"
+      "02de1df98cdf8196735ac4fae6472186e0ad755dbaacd927eca6c8e55d7cc37f0907f3ad5939eb42ea2e8e1ee686"
+      "3fc1d7acd2016cacd6e5094ad93f06d7c307e543332d20d64fd5f83aaa57703774b767a40d47e661d5a3ca3421bd"
+      "1001c0a76097ed5cc1f0035478a1b207762d71140a3a815821ed8677350f794cb7f37c136b587cd5339ca5d82fac"
+      "279c6ea55a3318a9e59f29d1a2b8f8b03b358305c0df86c452abb5557bd76c77b986056d0de090f114e0e045be44"
+      "d4a7c01344b2612beef8f0b4e4793c6d6d3dc080d8c086166dedcf192d0d261ed08f55dfa256fec5aaaffa8f2f0d"
+      "3f8f682f92060f0e3c319488443a43ba65903541b686a578e6d9cc780752b41ab74146f4d04a4fe0a8afaa3c9b64"
+      "69da03c110b2d2939d2d93f1b727cda179929630f75250693109751819d78d00def6043ee0c7af69e069b279a432"
+      "1a4313f35697b0b309700228e7fd549020106605784e3717cf0f3f5ec86e4cf0e25a9658c01e1d5ba04cf1cf9043"
+      "08bf02a9d8bbad81eb3de01956e9545b35f3e7397046c6977d415bda20844a55b274cce02c829b0d1b43ab1dc4a5"
+      "59fe1c82533826fc23b4491f69534f637446fcae611361d6039bbe4e93812e500edfd2b304c0228a59063fb20f7c"
+      "0fc17da47746f1f0a4eaff9e2a7b99cb9fd11be30b0d262492c28b8d343bd780b274a43a278b9081b6a3b1288f53"
+      "3489924d3df8c2820e36afb74fb955c176287f0611799edfe14ceefd26bcb8ec26cc9f5f727009a80f421b45359f"
+      "07d005ae2e2574fc6866c6d18d82b004807ccfc12e9482924c130b508a9b8050720890f5a7187221caf27506565c"
+      "e87d79875898a1f726ddb9fc0486ef3dc7ef4baa8f9fce443941fa0bd6642f5d6093f5712a8dc639fc18b8ee062c"
+      "baaeeca86d70799bcd22d946c5abe68cde44c75d8ee703ad636baf5626830629d7ca2b895da2528ca9905b26f16d"
+      "9d916269ece655d120246e8276bf036308f26a4fb8137358f8f8c5bb68fa6c657c2a990f1e13219150c6314434d3"
+      "205a00be020b70b576812d8efcf5663d142e212e06c04141e24706ae75ff2058283d03ca037fa7bb98773479c5e4"
+      "371493319112e144fe2ee0378c3ca8e08148aee50f3be35f2e4b15c03f81e7355e093217d71ff97e52578d37620b"
+      "3a3398cea11f0a7493aa6fceedd44488024987f40ec3227a368007e11bb8aa32fdce2ab7a5d10215b4c7e2859eca"
+      "1843959478ef51d70f24442bbfd0df737bb1badb14d234fe0709086e9252035a7585735b72a20f2b53992d66250f"
+      "3596fd6fadc36cd3a0ad097d7b25af2f3736494ad4b9b3d542bdced88defaf1381ad42c5845588cfea79084aa019"
+      "fdf4280f4aeb938463b98e42f3dd2206fb5443969f20a2312b2c6f2e04f251c3f8d229d24705066a895b1f910059"
+      "b6b66a2537f9bd25105e87e3633c010de9083c176463699bbab3d58b9cc316bc63ea9d6b9713968278639d0fd3c1"
+      "e447a68e60c7c38581f77bf231ebedf03a2bf1740ce9e738f4e2bc69f6df6801f4"
+    )
+    .as_ref(),
+  )
+  .unwrap();
+  // Independently calculated as
+  // keccak(prefix hash || keccak(RctBase) || keccak(A || A1 || B || r1 || s1 || d1 || L || R))
+  assert_eq!(
+    tx.signature_hash(),
+    hex!("a67bd3dd17a54c8ed5f3697912562dcc66f4ffa093c025c752c3a8fddaf16ea7")
+  );
+
+  // The signature hash commits to the prefix, base, and proofs, yet not the CLSAGs
+  let mut modified = tx.clone();
+  modified.prefix.extra.push(0);
+  assert!(modified.signature_hash() != tx.signature_hash());
+
+  let mut modified = tx.clone();
+  modified.rct_signatures.base.fee += 1;
+  assert!(modified.signature_hash() != tx.signature_hash());
+
+  let mut modified = tx.clone();
+  let RctPrunable::Clsag { clsags, .. } = &mut modified.rct_signatures.prunable else {
+    panic!("transaction didn't have CLSAGs")
+  };
+  clsags[0].c1 += Scalar::ONE;
+  assert_eq!(modified.signature_hash(), tx.signature_hash());
+}
@@ -8,11 +8,15 @@ use zeroize::Zeroize;
 
 use curve25519_dalek::edwards::{EdwardsPoint, CompressedEdwardsY};
 
+use monero_generators::decompress_point;
+
 use crate::{
   Protocol, hash,
   serialize::*,
   ring_signatures::RingSignature,
-  ringct::{bulletproofs::Bulletproofs, RctType, RctBase, RctPrunable, RctSignatures},
+  ringct::{
+    bulletproofs::Bulletproofs, EncryptedAmount, RctType, RctBase, RctPrunable, RctSignatures,
+  },
 };
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -244,6 +248,72 @@ impl TransactionPrefix {
   }
 }
 
+// Calculate the fee of a v1 transaction, validating its amounts in the process
+fn v1_fee(prefix: &TransactionPrefix) -> io::Result<u64> {
+  if matches!(prefix.inputs.first(), None | Some(Input::Gen(..))) {
+    return Ok(0);
+  }
+
+  let in_amount = prefix
+    .inputs
+    .iter()
+    .map(|input| match input {
+      Input::Gen(..) => Err(io::Error::other("Input::Gen present in non-coinbase v1 TX"))?,
+      // v1 TXs can burn v2 outputs
+      // dcff3fe4f914d6b6bd4a5b800cc4cca8f2fdd1bd73352f0700d463d36812f328 is one such TX
+      // It includes a pre-RCT signature for a RCT output, yet if you interpret the RCT
+      // output as being worth 0, it passes a sum check (guaranteed since no outputs are RCT)
+      Input::ToKey { amount, .. } => Ok(amount.unwrap_or(0)),
+    })
+    .collect::<io::Result<Vec<_>>>()?
+    .into_iter()
+    .sum::<u64>();
+
+  let mut out = 0;
+  for output in &prefix.outputs {
+    if output.amount.is_none() {
+      Err(io::Error::other("v1 transaction had a 0-amount output"))?;
+    }
+    out += output.amount.unwrap();
+  }
+
+  if in_amount < out {
+    Err(io::Error::other("transaction spent more than it had as inputs"))?;
+  }
+  Ok(in_amount - out)
+}
+
+// Hash a v2 transaction from its prefix, RingCT base, and the hash of its prunable data
+fn v2_hash(
+  prefix: &TransactionPrefix,
+  base: &RctBase,
+  rct_type: RctType,
+  prunable_hash: [u8; 32],
+) -> [u8; 32] {
+  let mut hashes = Vec::with_capacity(96);
+
+  hashes.extend(prefix.hash());
+
+  let mut buf = Vec::with_capacity(2048);
+  base.write(&mut buf, rct_type).unwrap();
+  hashes.extend(hash(&buf));
+
+  hashes.extend(prunable_hash);
+
+  hash(&hashes)
+}
+
+/// A summary of a transaction's shape, for indexing and filtering transactions.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TxSummary {
+  pub version: u64,
+  pub inputs: usize,
+  pub outputs: usize,
+  pub rct_type: RctType,
+  /// The fee paid by this transaction. None for miner transactions, which don't pay a fee.
+  pub fee: Option<u64>,
+}
+
 /// Monero transaction. For version 1, rct_signatures still contains an accurate fee value.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Transaction {
@@ -302,35 +372,7 @@ impl Transaction {
         })
         .collect::<Result<_, _>>()?;
 
-      if !matches!(prefix.inputs[0], Input::Gen(..)) {
-        let in_amount = prefix
-          .inputs
-          .iter()
-          .map(|input| match input {
-            Input::Gen(..) => Err(io::Error::other("Input::Gen present in non-coinbase v1 TX"))?,
-            // v1 TXs can burn v2 outputs
-            // dcff3fe4f914d6b6bd4a5b800cc4cca8f2fdd1bd73352f0700d463d36812f328 is one such TX
-            // It includes a pre-RCT signature for a RCT output, yet if you interpret the RCT
-            // output as being worth 0, it passes a sum check (guaranteed since no outputs are RCT)
-            Input::ToKey { amount, .. } => Ok(amount.unwrap_or(0)),
-          })
-          .collect::<io::Result<Vec<_>>>()?
-          .into_iter()
-          .sum::<u64>();
-
-        let mut out = 0;
-        for output in &prefix.outputs {
-          if output.amount.is_none() {
-            Err(io::Error::other("v1 transaction had a 0-amount output"))?;
-          }
-          out += output.amount.unwrap();
-        }
-
-        if in_amount < out {
-          Err(io::Error::other("transaction spent more than it had as inputs"))?;
-        }
-        rct_signatures.base.fee = in_amount - out;
-      }
+      rct_signatures.base.fee = v1_fee(&prefix)?;
     } else if prefix.version == 2 {
       rct_signatures = RctSignatures::read(
         prefix.inputs.first().map_or(0, |input| match input {
@@ -349,32 +391,103 @@ impl Transaction {
   }
 
   pub fn hash(&self) -> [u8; 32] {
-    let mut buf = Vec::with_capacity(2048);
+    match self.prunable_hash() {
+      None => hash(&self.serialize()),
+      Some(prunable_hash) => v2_hash(
+        &self.prefix,
+        &self.rct_signatures.base,
+        self.rct_signatures.rct_type(),
+        prunable_hash,
+      ),
+    }
+  }
+
+  /// The hash of this transaction's prunable data, as used within its hash.
+  ///
+  /// Returns None for v1 transactions, whose hashes don't have a prunable component.
+  pub fn prunable_hash(&self) -> Option<[u8; 32]> {
     if self.prefix.version == 1 {
-      self.write(&mut buf).unwrap();
-      hash(&buf)
-    } else {
-      let mut hashes = Vec::with_capacity(96);
+      return None;
+    }
+    Some(match self.rct_signatures.prunable {
+      RctPrunable::Null => [0; 32],
+      _ => hash(&self.rct_signatures.prunable.serialize(self.rct_signatures.rct_type())),
+    })
+  }
 
-      hashes.extend(self.prefix.hash());
+  /// The RingCT commitment for the output with the specified index.
+  ///
+  /// Returns None if the index is out of bounds or the amount of commitments doesn't match the
+  /// amount of outputs.
+  pub fn rct_commitment(&self, o: usize) -> Option<EdwardsPoint> {
+    let commitments = &self.rct_signatures.base.commitments;
+    if commitments.len() != self.prefix.outputs.len() {
+      return None;
+    }
+    commitments.get(o).copied()
+  }
 
-      self.rct_signatures.base.write(&mut buf, self.rct_signatures.rct_type()).unwrap();
-      hashes.extend(hash(&buf));
-      buf.clear();
+  /// The RingCT ECDH info, the encrypted amount, for the output with the specified index.
+  ///
+  /// Returns None if the index is out of bounds or the amount of encrypted amounts doesn't match
+  /// the amount of outputs.
+  pub fn rct_ecdh(&self, o: usize) -> Option<&EncryptedAmount> {
+    let encrypted_amounts = &self.rct_signatures.base.encrypted_amounts;
+    if encrypted_amounts.len() != self.prefix.outputs.len() {
+      return None;
+    }
+    encrypted_amounts.get(o)
+  }
 
-      hashes.extend(&match self.rct_signatures.prunable {
-        RctPrunable::Null => [0; 32],
-        _ => {
-          self.rct_signatures.prunable.write(&mut buf, self.rct_signatures.rct_type()).unwrap();
-          hash(&buf)
-        }
-      });
+  /// The key, encrypted amount, and commitment for the output with the specified index.
+  ///
+  /// Returns None if the index is out of bounds, the output's key isn't a valid point, the output
+  /// has a cleartext amount, or the amount of encrypted amounts or commitments doesn't match the
+  /// amount of outputs.
+  pub fn output_components(
+    &self,
+    o: usize,
+  ) -> Option<(EdwardsPoint, &EncryptedAmount, EdwardsPoint)> {
+    let encrypted_amount = self.rct_ecdh(o)?;
+    let commitment = self.rct_commitment(o)?;
+
+    let output = &self.prefix.outputs[o];
+    if output.amount.is_some() {
+      return None;
+    }
+    Some((decompress_point(output.key.to_bytes())?, encrypted_amount, commitment))
+  }
 
-      hash(&hashes)
+  /// A summary of this transaction's version, amount of inputs and outputs, RCT type, and fee.
+  pub fn summary(&self) -> TxSummary {
+    TxSummary {
+      version: self.prefix.version,
+      inputs: self.prefix.inputs.len(),
+      outputs: self.prefix.outputs.len(),
+      rct_type: self.rct_signatures.rct_type(),
+      fee: if matches!(self.prefix.inputs.first(), Some(Input::Gen(_))) {
+        None
+      } else {
+        Some(self.rct_signatures.base.fee)
+      },
+    }
+  }
+
+  /// This transaction without its prunable data.
+  pub fn pruned(&self) -> PrunedTransaction {
+    PrunedTransaction {
+      prefix: self.prefix.clone(),
+      rct_type: self.rct_signatures.rct_type(),
+      rct_base: self.rct_signatures.base.clone(),
     }
   }
 
   /// Calculate the hash of this transaction as needed for signing it.
+  ///
+  /// For v1 transactions, this is the prefix's hash. For RingCT transactions, this is
+  /// `keccak(prefix hash || keccak(RctBase) || keccak(range proofs))`, where the range proofs are
+  /// their fields concatenated without lengths (Monero's `get_pre_mlsag_hash`). The ring
+  /// signatures themselves aren't committed to.
   pub fn signature_hash(&self) -> [u8; 32] {
     if self.prefix.version == 1 {
       return self.prefix.hash();
@@ -430,3 +543,63 @@ impl Transaction {
     }
   }
 }
+
+/// A Monero transaction without its prunable data, as served by pruned nodes.
+///
+/// For version 1, this is solely the prefix, with rct_base still containing an accurate fee
+/// value. For version 2, this is the prefix and the RingCT base.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PrunedTransaction {
+  pub prefix: TransactionPrefix,
+  pub rct_type: RctType,
+  pub rct_base: RctBase,
+}
+
+impl PrunedTransaction {
+  pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    self.prefix.write(w)?;
+    if self.prefix.version == 1 {
+      Ok(())
+    } else if self.prefix.version == 2 {
+      self.rct_base.write(w, self.rct_type)
+    } else {
+      panic!("Serializing a transaction with an unknown version");
+    }
+  }
+
+  pub fn serialize(&self) -> Vec<u8> {
+    let mut res = Vec::with_capacity(512);
+    self.write(&mut res).unwrap();
+    res
+  }
+
+  pub fn read<R: Read>(r: &mut R) -> io::Result<PrunedTransaction> {
+    let prefix = TransactionPrefix::read(r)?;
+    let (rct_base, rct_type) = if prefix.version == 1 {
+      (
+        RctBase {
+          fee: v1_fee(&prefix)?,
+          encrypted_amounts: vec![],
+          pseudo_outs: vec![],
+          commitments: vec![],
+        },
+        RctType::Null,
+      )
+    } else if prefix.version == 2 {
+      RctBase::read(prefix.inputs.len(), prefix.outputs.len(), r)?
+    } else {
+      Err(io::Error::other("Tried to deserialize unknown version"))?
+    };
+    Ok(PrunedTransaction { prefix, rct_type, rct_base })
+  }
+
+  /// Calculate the hash of the full transaction, given the hash of its prunable data.
+  ///
+  /// Returns None for v1 transactions, whose hashes require their signatures.
+  pub fn hash(&self, prunable_hash: [u8; 32]) -> Option<[u8; 32]> {
+    if self.prefix.version == 1 {
+      return None;
+    }
+    Some(v2_hash(&self.prefix, &self.rct_base, self.rct_type, prunable_hash))
+  }
+}
@@ -28,6 +28,8 @@ const RECENT_WINDOW: usize = 15;
 const BLOCKS_PER_YEAR: usize = 365 * 24 * 60 * 60 / BLOCK_TIME;
 #[allow(clippy::cast_precision_loss)]
 const TIP_APPLICATION: f64 = (DEFAULT_LOCK_WINDOW * BLOCK_TIME) as f64;
+// The amount of samples select_decoys will take, per decoy, before giving up
+const MAX_SAMPLES_PER_DECOY: usize = 1000;
 
 // TODO: Resolve safety of this in case a reorg occurs/the network changes
 // TODO: Update this when scanning a block, as possible
@@ -39,6 +41,46 @@ fn DISTRIBUTION() -> &'static Mutex<Vec<u64>> {
   DISTRIBUTION_CELL.get_or_init(|| Mutex::new(Vec::with_capacity(3000000)))
 }
 
+// Sample an output index from the gamma distribution, returning None if the sample was unusable
+fn sample<R: RngCore + CryptoRng>(
+  rng: &mut R,
+  distribution: &[u64],
+  high: u64,
+  per_second: f64,
+) -> Option<u64> {
+  // Use a gamma distribution
+  let mut age = Gamma::<f64>::new(19.28, 1.0 / 1.61).unwrap().sample(rng).exp();
+  #[allow(clippy::cast_precision_loss)]
+  if age > TIP_APPLICATION {
+    age -= TIP_APPLICATION;
+  } else {
+    // f64 does not have try_from available, which is why these are written with `as`
+    age = (rng.next_u64() % u64::try_from(RECENT_WINDOW * BLOCK_TIME).unwrap()) as f64;
+  }
+
+  #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+  let o = (age * per_second) as u64;
+  if o >= high {
+    None?;
+  }
+  let i = distribution.partition_point(|s| *s < (high - 1 - o));
+  let prev = i.saturating_sub(1);
+  let n = distribution[i] - distribution[prev];
+  if n == 0 {
+    None?;
+  }
+  Some(distribution[prev] + (rng.next_u64() % n))
+}
+
+// The average amount of outputs created per second over the last year
+#[allow(clippy::cast_precision_loss)]
+fn outputs_per_second(distribution: &[u64]) -> f64 {
+  let blocks = distribution.len().min(BLOCKS_PER_YEAR);
+  let initial = distribution[distribution.len().saturating_sub(blocks + 1)];
+  let outputs = distribution[distribution.len() - 1].saturating_sub(initial);
+  (outputs as f64) / ((blocks * BLOCK_TIME) as f64)
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn select_n<'a, R: RngCore + CryptoRng, RPC: RpcConnection>(
   rng: &mut R,
@@ -77,29 +119,11 @@ async fn select_n<'a, R: RngCore + CryptoRng, RPC: RpcConnection>(
         }
       }
 
-      // Use a gamma distribution
-      let mut age = Gamma::<f64>::new(19.28, 1.0 / 1.61).unwrap().sample(rng).exp();
-      #[allow(clippy::cast_precision_loss)]
-      if age > TIP_APPLICATION {
-        age -= TIP_APPLICATION;
-      } else {
-        // f64 does not have try_from available, which is why these are written with `as`
-        age = (rng.next_u64() % u64::try_from(RECENT_WINDOW * BLOCK_TIME).unwrap()) as f64;
-      }
-
-      #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-      let o = (age * per_second) as u64;
-      if o < high {
-        let i = distribution.partition_point(|s| *s < (high - 1 - o));
-        let prev = i.saturating_sub(1);
-        let n = distribution[i] - distribution[prev];
-        if n != 0 {
-          let o = distribution[prev] + (rng.next_u64() % n);
-          if !used.contains(&o) {
-            // It will either actually be used, or is unusable and this prevents trying it again
-            used.insert(o);
-            candidates.push(o);
-          }
+      if let Some(o) = sample(rng, distribution, high, per_second) {
+        if !used.contains(&o) {
+          // It will either actually be used, or is unusable and this prevents trying it again
+          used.insert(o);
+          candidates.push(o);
         }
       }
     }
@@ -150,7 +174,7 @@ fn offset(ring: &[u64]) -> Vec<u64> {
   res
 }
 
-async fn select_decoys<R: RngCore + CryptoRng, RPC: RpcConnection>(
+async fn select_decoys_with_rpc<R: RngCore + CryptoRng, RPC: RpcConnection>(
   rng: &mut R,
   rpc: &Rpc<RPC>,
   ring_len: usize,
@@ -192,13 +216,7 @@ async fn select_decoys<R: RngCore + CryptoRng, RPC: RpcConnection>(
     Err(RpcError::InternalError("not enough decoy candidates"))?;
   }
 
-  #[allow(clippy::cast_precision_loss)]
-  let per_second = {
-    let blocks = distribution.len().min(BLOCKS_PER_YEAR);
-    let initial = distribution[distribution.len().saturating_sub(blocks + 1)];
-    let outputs = distribution[distribution.len() - 1].saturating_sub(initial);
-    (outputs as f64) / ((blocks * BLOCK_TIME) as f64)
-  };
+  let per_second = outputs_per_second(&distribution);
 
   let mut used = HashSet::<u64>::new();
   for o in &outputs {
@@ -298,6 +316,58 @@ async fn select_decoys<R: RngCore + CryptoRng, RPC: RpcConnection>(
   Ok(res)
 }
 
+/// Select decoys for the output with the specified global index, using the same distribution as
+/// Monero.
+///
+/// `distribution` is the cumulative RingCT output distribution, as returned by
+/// `Rpc::get_output_distribution`, up to the block the transaction will be created at. Outputs
+/// created within the last `DEFAULT_LOCK_WINDOW` blocks are considered locked and won't be
+/// selected. Unlike `Decoys::select`, this doesn't check if any other outputs are locked with the
+/// node.
+///
+/// Returns the sorted, distinct global indexes of the ring, including the real output, or None if
+/// there aren't enough unlocked outputs to select decoys from. As sampling may repeatedly yield
+/// unusable or already selected outputs, this also returns None if a bounded amount of samples
+/// didn't produce enough decoys.
+pub fn select_decoys<R: RngCore + CryptoRng>(
+  rng: &mut R,
+  distribution: &[u64],
+  real_index: u64,
+  ring_size: usize,
+) -> Option<Vec<u64>> {
+  if (ring_size == 0) || (distribution.len() < DEFAULT_LOCK_WINDOW) {
+    None?;
+  }
+
+  let high = distribution[distribution.len() - DEFAULT_LOCK_WINDOW];
+  // Outputs from the first block are never sampled
+  let candidates = distribution[0] .. high;
+  let available =
+    candidates.end.saturating_sub(candidates.start) - u64::from(candidates.contains(&real_index));
+  if available < u64::try_from(ring_size - 1).unwrap() {
+    None?;
+  }
+
+  let per_second = outputs_per_second(distribution);
+  let mut ring = HashSet::with_capacity(ring_size);
+  ring.insert(real_index);
+  let mut samples = 0;
+  while ring.len() != ring_size {
+    if samples == (ring_size * MAX_SAMPLES_PER_DECOY) {
+      None?;
+    }
+    samples += 1;
+
+    if let Some(o) = sample(rng, distribution, high, per_second) {
+      ring.insert(o);
+    }
+  }
+
+  let mut ring = ring.into_iter().collect::<Vec<_>>();
+  ring.sort_unstable();
+  Some(ring)
+}
+
 /// Decoy data, containing the actual member as well (at index `i`).
 #[derive(Clone, PartialEq, Eq, Debug, Zeroize, ZeroizeOnDrop)]
 pub struct Decoys {
@@ -333,7 +403,7 @@ impl Decoys {
     height: usize,
     inputs: &[SpendableOutput],
   ) -> Result<Vec<Decoys>, RpcError> {
-    select_decoys(rng, rpc, ring_len, height, inputs, false).await
+    select_decoys_with_rpc(rng, rpc, ring_len, height, inputs, false).await
   }
 
   /// If no reorg has occurred and an honest RPC, any caller who passes the same height to this
@@ -351,6 +421,6 @@ impl Decoys {
     height: usize,
     inputs: &[SpendableOutput],
   ) -> Result<Vec<Decoys>, RpcError> {
-    select_decoys(rng, rpc, ring_len, height, inputs, true).await
+    select_decoys_with_rpc(rng, rpc, ring_len, height, inputs, true).await
   }
 }
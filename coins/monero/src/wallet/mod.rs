@@ -24,10 +24,15 @@ pub mod address;
 use address::{Network, AddressType, SubaddressIndex, AddressSpec, AddressMeta, MoneroAddress};
 
 mod scan;
-pub use scan::{ReceivedOutput, SpendableOutput, Timelocked};
+pub use scan::{
+  MAX_SCANNED_KEYS, Derivation, ReceivedOutput, SpendableOutput, KeyImagedOutput, Timelocked,
+  BlockScanner, DEFAULT_REORG_DEPTH, ScanCache, filter_unspent, scan_transactions,
+};
+#[cfg(feature = "http-rpc")]
+pub use scan::scan_from;
 
 pub mod decoys;
-pub use decoys::Decoys;
+pub use decoys::{Decoys, select_decoys};
 
 mod send;
 pub use send::{FeePriority, Fee, TransactionError, Change, SignableTransaction, Eventuality};
@@ -88,7 +93,21 @@ pub(crate) fn shared_key(
   (view_tag, hash_to_scalar(&shared_key), payment_id_xor)
 }
 
-pub(crate) fn commitment_mask(shared_key: Scalar) -> Scalar {
+/// Derive an output's one-time key from its shared key and the recipient's spend key.
+///
+/// This is `Hs(8Ra || o) G + B`, where the shared key is the scalar derived by `shared_key` (and
+/// the scanner). The scanner inverts this, subtracting `Hs(8Ra || o) G` from the output's key to
+/// recover the spend key.
+pub fn derive_output_key(shared_key: Scalar, spend: EdwardsPoint) -> EdwardsPoint {
+  (&shared_key * ED25519_BASEPOINT_TABLE) + spend
+}
+
+/// Derive the mask for an output's commitment from its shared key.
+///
+/// The shared key is the scalar `Hs(8Ra || o)` (optionally prefixed by the transaction's
+/// uniqueness), as derived by the scanner. The returned mask opens the output's commitment
+/// alongside its amount.
+pub fn commitment_mask(shared_key: Scalar) -> Scalar {
   let mut mask = b"commitment_mask".to_vec();
   mask.extend(shared_key.to_bytes());
   hash_to_scalar(&mask)
@@ -101,7 +120,15 @@ pub(crate) fn amount_encryption(amount: u64, key: Scalar) -> [u8; 8] {
 }
 
 // TODO: Move this under EncryptedAmount?
-fn amount_decryption(amount: &EncryptedAmount, key: Scalar) -> (Scalar, u64) {
+/// Decrypt an output's amount using its shared key.
+///
+/// The shared key is the same scalar passed to `commitment_mask`. Returns the commitment's mask
+/// and the amount. These are not authenticated, and the caller MUST verify they open the
+/// output's commitment before trusting them.
+///
+/// Decrypting a pre-RingCT v2 `EncryptedAmount::Original` is only supported with the
+/// `experimental` feature, and will panic otherwise.
+pub fn amount_decryption(amount: &EncryptedAmount, key: Scalar) -> (Scalar, u64) {
   match amount {
     EncryptedAmount::Original { mask, amount } => {
       #[cfg(feature = "experimental")]
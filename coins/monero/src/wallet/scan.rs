@@ -1,24 +1,36 @@
-use core::ops::Deref;
+use core::{ops::Deref, cmp::Ordering};
+#[cfg(feature = "http-rpc")]
+use core::time::Duration;
 use std_shims::{
   vec::Vec,
   string::ToString,
   io::{self, Read, Write},
+  collections::{VecDeque, HashSet, HashMap},
 };
 
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
-use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, scalar::Scalar, edwards::EdwardsPoint};
+use curve25519_dalek::{
+  constants::ED25519_BASEPOINT_TABLE,
+  scalar::Scalar,
+  edwards::{EdwardsPoint, CompressedEdwardsY},
+};
+
+#[cfg(feature = "http-rpc")]
+use futures_util::{Stream, stream};
 
 use monero_generators::decompress_point;
 
 use crate::{
   Commitment,
+  ringct::{hash_to_point, generate_key_image, bulletproofs::MAX_OUTPUTS},
   serialize::{read_byte, read_u32, read_u64, read_bytes, read_scalar, read_point, read_raw_vec},
   transaction::{Input, Timelock, Transaction},
   block::Block,
   rpc::{RpcError, RpcConnection, Rpc},
   wallet::{
-    PaymentId, Extra, address::SubaddressIndex, Scanner, uniqueness, shared_key, amount_decryption,
+    PaymentId, Extra, address::SubaddressIndex, ViewPair, Scanner, uniqueness, shared_key,
+    amount_decryption,
   },
 };
 
@@ -56,7 +68,13 @@ impl AbsoluteId {
 #[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
 pub struct OutputData {
   pub key: EdwardsPoint,
-  /// Absolute difference between the spend key and the key in this output
+  /// Absolute difference between the spend key and the key in this output.
+  ///
+  /// This is the output's entire key derivation component, including any subaddress derivation,
+  /// such that `key == spend + (key_offset * G)`. For a multisig wallet, where the spend key is
+  /// shared, the output's signing key is the shared spend key offset by this scalar. Accordingly,
+  /// participants agree on the output's signing key by having exactly one of them add this to
+  /// their key share.
   pub key_offset: Scalar,
   pub commitment: Commitment,
 }
@@ -95,6 +113,21 @@ impl OutputData {
   }
 }
 
+/// The derivation used to find an output.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Zeroize)]
+pub enum Derivation {
+  /// The traditional derivation, as used by wallet2 and standard addresses.
+  Traditional,
+  /// The derivation binding the output to its transaction's uniqueness, as used by guaranteed
+  /// addresses and guaranteed change.
+  Unique,
+}
+
+// Metadata without a derivation, as serialized before the derivation was recorded, starts with a
+// byte of 0 or 1, flagging if a subaddress is present. Metadata with a derivation starts with this
+// marker, followed by the derivation.
+const METADATA_DERIVATION_MARKER: u8 = 0xff;
+
 /// The metadata for an output.
 #[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
 pub struct Metadata {
@@ -115,6 +148,12 @@ pub struct Metadata {
   pub payment_id: Option<PaymentId>,
   /// Arbitrary data encoded in TX extra.
   pub arbitrary_data: Vec<Vec<u8>>,
+  /// The derivation which found this output.
+  ///
+  /// This is the traditional derivation for outputs found by scanners tracking the burning bug,
+  /// and the unique derivation for outputs found by guaranteed scanners. This is None if the
+  /// derivation is unknown, as it is for metadata serialized before the derivation was recorded.
+  pub derivation: Option<Derivation>,
 }
 
 impl core::fmt::Debug for Metadata {
@@ -124,12 +163,28 @@ impl core::fmt::Debug for Metadata {
       .field("subaddress", &self.subaddress)
       .field("payment_id", &self.payment_id)
       .field("arbitrary_data", &self.arbitrary_data.iter().map(hex::encode).collect::<Vec<_>>())
+      .field("derivation", &self.derivation)
       .finish()
   }
 }
 
 impl Metadata {
+  /// Write the metadata.
+  ///
+  /// Metadata with a derivation is written with a leading marker and the derivation, which
+  /// versions of this library from before the derivation was recorded can't read. Metadata
+  /// without a derivation is written as those versions wrote it.
   pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    if let Some(derivation) = self.derivation {
+      w.write_all(&[
+        METADATA_DERIVATION_MARKER,
+        match derivation {
+          Derivation::Traditional => 0,
+          Derivation::Unique => 1,
+        },
+      ])?;
+    }
+
     if let Some(subaddress) = self.subaddress {
       w.write_all(&[1])?;
       w.write_all(&subaddress.account().to_le_bytes())?;
@@ -154,13 +209,26 @@ impl Metadata {
   }
 
   pub fn serialize(&self) -> Vec<u8> {
-    let mut serialized = Vec::with_capacity(1 + 8 + 1);
+    let mut serialized = Vec::with_capacity(2 + 1 + 8 + 1);
     self.write(&mut serialized).unwrap();
     serialized
   }
 
   pub fn read<R: Read>(r: &mut R) -> io::Result<Metadata> {
-    let subaddress = if read_byte(r)? == 1 {
+    let mut subaddress_flag = read_byte(r)?;
+    let derivation = if subaddress_flag == METADATA_DERIVATION_MARKER {
+      let derivation = match read_byte(r)? {
+        0 => Derivation::Traditional,
+        1 => Derivation::Unique,
+        _ => Err(io::Error::other("invalid derivation in metadata"))?,
+      };
+      subaddress_flag = read_byte(r)?;
+      Some(derivation)
+    } else {
+      None
+    };
+
+    let subaddress = if subaddress_flag == 1 {
       Some(
         SubaddressIndex::new(read_u32(r)?, read_u32(r)?)
           .ok_or_else(|| io::Error::other("invalid subaddress in metadata"))?,
@@ -180,6 +248,7 @@ impl Metadata {
         }
         data
       },
+      derivation,
     })
   }
 }
@@ -209,6 +278,11 @@ impl ReceivedOutput {
     &self.metadata.arbitrary_data
   }
 
+  /// The derivation which found this output, if known.
+  pub fn derivation(&self) -> Option<Derivation> {
+    self.metadata.derivation
+  }
+
   pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
     self.absolute.write(w)?;
     self.data.write(w)?;
@@ -281,6 +355,24 @@ impl SpendableOutput {
     self.output.arbitrary_data()
   }
 
+  /// The derivation which found this output, if known.
+  pub fn derivation(&self) -> Option<Derivation> {
+    self.output.derivation()
+  }
+
+  /// The generator for this output's key image, `hash_to_point(key)`.
+  ///
+  /// This doesn't require the private spend key, yet the key image does. With the private spend
+  /// key `x`, the key image is `(x + key_offset) * key_image_generator`.
+  pub fn key_image_generator(&self) -> EdwardsPoint {
+    hash_to_point(&self.key())
+  }
+
+  /// This output's key image, as calculated with the private spend key.
+  pub fn key_image(&self, spend: &Zeroizing<Scalar>) -> EdwardsPoint {
+    generate_key_image(&Zeroizing::new(spend.deref() + self.key_offset()))
+  }
+
   pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
     self.output.write(w)?;
     w.write_all(&self.global_index.to_le_bytes())
@@ -297,6 +389,52 @@ impl SpendableOutput {
   }
 }
 
+/// Filter outputs to those which are unspent, given the key images which have been spent.
+///
+/// Each output's key image is calculated with the private spend key, and the output is dropped if
+/// its key image is within `spent_images`. The remaining outputs retain their order.
+pub fn filter_unspent(
+  outputs: Vec<SpendableOutput>,
+  spent_images: &HashSet<CompressedEdwardsY>,
+  spend: &Zeroizing<Scalar>,
+) -> Vec<SpendableOutput> {
+  outputs
+    .into_iter()
+    .filter(|output| !spent_images.contains(&output.key_image(spend).compress()))
+    .collect()
+}
+
+/// A spendable output annotated with its key image.
+///
+/// Producing this requires the private spend key, so it's solely available to full wallets, not
+/// view-only wallets.
+#[derive(Clone, PartialEq, Eq, Debug, Zeroize, ZeroizeOnDrop)]
+pub struct KeyImagedOutput {
+  pub output: SpendableOutput,
+  pub key_image: EdwardsPoint,
+}
+
+/// Outputs are ordered by amount, with ties broken by their absolute ID.
+///
+/// As an absolute ID uniquely identifies an output, this ordering is deterministic, letting
+/// wallets deterministically select outputs to spend.
+impl PartialOrd for SpendableOutput {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for SpendableOutput {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self
+      .commitment()
+      .amount
+      .cmp(&other.commitment().amount)
+      .then_with(|| self.output.absolute.tx.cmp(&other.output.absolute.tx))
+      .then_with(|| self.output.absolute.o.cmp(&other.output.absolute.o))
+  }
+}
+
 /// A collection of timelocked outputs, either received or spendable.
 #[derive(Zeroize)]
 pub struct Timelocked<O: Clone + Zeroize>(Timelock, Vec<O>);
@@ -338,14 +476,150 @@ impl<O: Clone + Zeroize> Timelocked<O> {
   }
 }
 
+/// The maximum amount of keys which will be scanned with, per transaction.
+///
+/// Positional additional keys are solely used for their respective output, and are accordingly
+/// bounded by the amount of outputs. Every other key is attempted for every output, so a
+/// transaction with an excessive amount of them is ignored instead of causing unbounded work.
+/// This allows a primary key alongside a full set of non-positional additional keys.
+pub const MAX_SCANNED_KEYS: usize = 1 + MAX_OUTPUTS;
+
+/// The keys to attempt deriving an output's shared key with.
+///
+/// This is generic to the representation of the keys, such as their ECDHs with the view key.
+///
+/// Every primary key is a candidate. Additional keys are positional, one per output, so when there
+/// are as many additional keys as outputs, solely the additional key for this output is a
+/// candidate. If the amount of additional keys doesn't match the amount of outputs, the
+/// transaction is non-standard and the positions can't be relied upon, so every additional key is
+/// a candidate.
+fn candidate_keys<K: Copy>(
+  tx_keys: &[K],
+  additional: Option<&[K]>,
+  outputs: usize,
+  o: usize,
+) -> Vec<K> {
+  let mut keys = tx_keys.to_vec();
+  match additional {
+    None => {}
+    Some(additional) if additional.len() == outputs => keys.push(additional[o]),
+    Some(additional) => keys.extend(additional),
+  }
+  keys
+}
+
+impl ViewPair {
+  /// Scan a transaction to discover the outputs received to this pair's standard address.
+  ///
+  /// This is a convenience for one-off scans. It creates a Scanner without any record of
+  /// previously received outputs, so it doesn't protect against the burning bug across
+  /// transactions. Wallets should maintain a Scanner.
+  pub fn scan_transaction(&self, tx: &Transaction) -> Timelocked<ReceivedOutput> {
+    Scanner::from_view(self.clone(), Some(HashSet::new())).scan_transaction(tx)
+  }
+}
+
+/// Scan several transactions to discover the spendable outputs received to the standard address
+/// of the specified keys.
+///
+/// The outputs are returned in the order of their transactions, then in the order of their
+/// indexes within their transactions. Their timelocks are ignored, and the caller must check
+/// the timelock of the transaction each output is from before spending it.
+///
+/// A single Scanner is used for the entire batch, so an output key received multiple times
+/// within the batch is only returned once, as the burning bug would have it be. The transactions
+/// must be on-chain, as the global indexes of their outputs are fetched from the node.
+pub async fn scan_transactions<RPC: RpcConnection>(
+  rpc: &Rpc<RPC>,
+  txs: &[Transaction],
+  view: Zeroizing<Scalar>,
+  spend: EdwardsPoint,
+) -> Result<Vec<SpendableOutput>, RpcError> {
+  let mut scanner = Scanner::from_view(ViewPair::new(spend, view), Some(HashSet::new()));
+  let mut res = vec![];
+  for tx in txs {
+    let outputs = scanner.scan_transaction(tx).1;
+    if outputs.is_empty() {
+      continue;
+    }
+
+    // Fetch the indexes once per transaction, instead of once per output
+    let indexes = rpc.get_o_indexes(tx.hash()).await?;
+    for output in outputs {
+      let global_index =
+        *indexes.get(usize::from(output.absolute.o)).ok_or(RpcError::InvalidNode(
+          "node returned output indexes didn't include an index for this output".to_string(),
+        ))?;
+      res.push(SpendableOutput { output, global_index });
+    }
+  }
+  Ok(res)
+}
+
 impl Scanner {
   /// Scan a transaction to discover the received outputs.
   pub fn scan_transaction(&mut self, tx: &Transaction) -> Timelocked<ReceivedOutput> {
-    // Only scan RCT TXs since we can only spend RCT outputs
-    if tx.prefix.version != 2 {
+    self.scan_transaction_above(tx, 0)
+  }
+
+  /// Scan a transaction to discover the received outputs, ignoring any outputs with an amount
+  /// below the specified minimum.
+  ///
+  /// Amounts are only compared against the minimum after their commitments have been verified.
+  /// Ignored outputs aren't considered received, and their keys aren't tracked for the burning
+  /// bug.
+  pub fn scan_transaction_above(
+    &mut self,
+    tx: &Transaction,
+    min_amount: u64,
+  ) -> Timelocked<ReceivedOutput> {
+    self.scan_transaction_internal(tx, min_amount, false)
+  }
+
+  /// Scan a transaction to discover the received outputs, including pre-RingCT (v1) transactions.
+  ///
+  /// v1 transactions have cleartext amounts, which are returned as commitments with a mask of 1.
+  /// Outputs from v1 transactions can't be spent by this library and aren't part of the RingCT
+  /// output pool, which is why they're solely found by this method.
+  pub fn scan_legacy_transaction(&mut self, tx: &Transaction) -> Timelocked<ReceivedOutput> {
+    self.scan_transaction_internal(tx, 0, true)
+  }
+
+  fn scan_transaction_internal(
+    &mut self,
+    tx: &Transaction,
+    min_amount: u64,
+    legacy: bool,
+  ) -> Timelocked<ReceivedOutput> {
+    match tx.prefix.version {
+      2 => {}
+      // v1 TXs solely have cleartext amounts
+      1 if legacy => {
+        if tx.prefix.outputs.iter().any(|output| output.amount.is_none()) {
+          return Timelocked(tx.prefix.timelock, vec![]);
+        }
+      }
+      // Otherwise, only scan RCT TXs since we can only spend RCT outputs
+      _ => return Timelocked(tx.prefix.timelock, vec![]),
+    }
+
+    // A transaction without outputs has nothing to scan
+    if tx.prefix.outputs.is_empty() {
       return Timelocked(tx.prefix.timelock, vec![]);
     }
 
+    // If any output's amount is encrypted, every output must have an encrypted amount and a
+    // commitment. If the RCT data doesn't line up with the outputs, the transaction is malformed
+    // and we ignore it entirely instead of scanning whichever outputs happen to have data
+    if tx.prefix.outputs.iter().any(|output| output.amount.is_none()) {
+      let base = &tx.rct_signatures.base;
+      if (base.encrypted_amounts.len() != tx.prefix.outputs.len()) ||
+        (base.commitments.len() != tx.prefix.outputs.len())
+      {
+        return Timelocked(tx.prefix.timelock, vec![]);
+      }
+    }
+
     let Ok(extra) = Extra::read::<&[u8]>(&mut tx.prefix.extra.as_ref()) else {
       return Timelocked(tx.prefix.timelock, vec![]);
     };
@@ -354,8 +628,31 @@ impl Scanner {
       return Timelocked(tx.prefix.timelock, vec![]);
     };
 
+    // Bound the work this transaction can cause
+    let unbounded_keys = tx_keys.len() +
+      additional
+        .as_ref()
+        .filter(|additional| additional.len() != tx.prefix.outputs.len())
+        .map_or(0, Vec::len);
+    if unbounded_keys > MAX_SCANNED_KEYS {
+      return Timelocked(tx.prefix.timelock, vec![]);
+    }
+
     let payment_id = extra.payment_id();
 
+    // Calculate the ECDH with each key once, instead of once per output
+    let view = self.pair.view.deref();
+    let tx_ecdhs = tx_keys.iter().map(|key| view * key).collect::<Vec<_>>();
+    let additional_ecdhs =
+      additional.map(|additional| additional.iter().map(|key| view * key).collect::<Vec<_>>());
+    // The uniqueness is solely dependent on the inputs, so it's also only calculated once
+    let uniqueness = uniqueness(&tx.prefix.inputs);
+    // The transaction's hash, calculated once an output is found
+    let mut tx_hash = None;
+    // RingCT miner transactions have cleartext amounts, unlike every other RingCT transaction
+    let rct_miner_tx =
+      (tx.prefix.version == 2) && matches!(tx.prefix.inputs.first(), Some(Input::Gen(..)));
+
     let mut res = vec![];
     for (o, output) in tx.prefix.outputs.iter().enumerate() {
       // https://github.com/serai-dex/serai/issues/106
@@ -371,30 +668,22 @@ impl Scanner {
       }
       let output_key = output_key.unwrap();
 
-      let additional = additional.as_ref().map(|additional| additional.get(o));
-
-      for key in tx_keys.iter().map(|key| Some(Some(key))).chain(core::iter::once(additional)) {
-        let key = match key {
-          Some(Some(key)) => key,
-          Some(None) => {
-            // This is non-standard. There were additional keys, yet not one for this output
-            // https://github.com/monero-project/monero/
-            //   blob/04a1e2875d6e35e27bb21497988a6c822d319c28/
-            //   src/cryptonote_basic/cryptonote_format_utils.cpp#L1062
-            continue;
-          }
-          None => {
-            break;
-          }
-        };
+      for ecdh in candidate_keys(&tx_ecdhs, additional_ecdhs.as_deref(), tx.prefix.outputs.len(), o)
+      {
+        // Scanners tracking the burning bug solely accept the traditional derivation, as wallet2
+        // does, while guaranteed scanners solely accept the unique derivation, as the traditional
+        // derivation would re-introduce the burning bug
+        let derivation =
+          if self.burning_bug.is_some() { Derivation::Traditional } else { Derivation::Unique };
         let (view_tag, shared_key, payment_id_xor) = shared_key(
-          if self.burning_bug.is_none() { Some(uniqueness(&tx.prefix.inputs)) } else { None },
-          self.pair.view.deref() * key,
+          match derivation {
+            Derivation::Traditional => None,
+            Derivation::Unique => Some(uniqueness),
+          },
+          ecdh,
           o,
         );
 
-        let payment_id = payment_id.map(|id| id ^ payment_id_xor);
-
         if let Some(actual_view_tag) = output.view_tag {
           if actual_view_tag != view_tag {
             continue;
@@ -402,12 +691,14 @@ impl Scanner {
         }
 
         // P - shared == spend
-        let subaddress =
-          self.subaddresses.get(&(output_key - (&shared_key * ED25519_BASEPOINT_TABLE)).compress());
-        if subaddress.is_none() {
+        let Some(subaddress) =
+          self.subaddresses.get(&(output_key - (&shared_key * ED25519_BASEPOINT_TABLE)).compress())
+        else {
           continue;
-        }
-        let subaddress = *subaddress.unwrap();
+        };
+        let subaddress = *subaddress;
+
+        let payment_id = payment_id.map(|id| id ^ payment_id_xor);
 
         // If it has torsion, it'll subtract the non-torsioned shared key to a torsioned key
         // We will not have a torsioned key in our HashMap of keys, so we wouldn't identify it as
@@ -422,34 +713,57 @@ impl Scanner {
         // Since we've found an output to us, get its amount
         let mut commitment = Commitment::zero();
 
-        // Miner transaction
+        // Miner transaction or v1 transaction, either of which has a cleartext amount
         if let Some(amount) = output.amount {
+          if tx.prefix.version != 1 {
+            // The only RingCT transactions with cleartext amounts are miner transactions, whose
+            // outputs are implicitly committed to with a mask of 1
+            if !rct_miner_tx {
+              break;
+            }
+            // If commitments were explicitly included, this output's must match the implicit
+            // commitment
+            if !tx.rct_signatures.base.commitments.is_empty() {
+              let Some(expected_commitment) = tx.rct_commitment(o) else { break };
+              if Commitment::new(Scalar::ONE, amount).calculate() != expected_commitment {
+                break;
+              }
+            }
+          }
           commitment.amount = amount;
         // Regular transaction
         } else {
-          let (mask, amount) = match tx.rct_signatures.base.encrypted_amounts.get(o) {
-            Some(amount) => amount_decryption(amount, shared_key),
-            // This should never happen, yet it may be possible with miner transactions?
-            // Using get just decreases the possibility of a panic and lets us move on in that case
-            None => break,
+          // This should always be Some due to the above length checks, yet this lets us move on
+          // instead of panicking
+          let Some((_, encrypted_amount, expected_commitment)) = tx.output_components(o) else {
+            break;
           };
+          let (mask, amount) = amount_decryption(encrypted_amount, shared_key);
 
           // Rebuild the commitment to verify it
           commitment = Commitment::new(mask, amount);
           // If this is a malicious commitment, move to the next output
           // Any other R value will calculate to a different spend key and are therefore ignorable
-          if Some(&commitment.calculate()) != tx.rct_signatures.base.commitments.get(o) {
+          if commitment.calculate() != expected_commitment {
             break;
           }
         }
 
-        if commitment.amount != 0 {
+        if (commitment.amount != 0) && (commitment.amount >= min_amount) {
           res.push(ReceivedOutput {
-            absolute: AbsoluteId { tx: tx.hash(), o: o.try_into().unwrap() },
+            absolute: AbsoluteId {
+              tx: *tx_hash.get_or_insert_with(|| tx.hash()),
+              o: o.try_into().unwrap(),
+            },
 
             data: OutputData { key: output_key, key_offset, commitment },
 
-            metadata: Metadata { subaddress, payment_id, arbitrary_data: extra.data() },
+            metadata: Metadata {
+              subaddress,
+              payment_id,
+              arbitrary_data: extra.data(),
+              derivation: Some(derivation),
+            },
           });
 
           if let Some(burning_bug) = self.burning_bug.as_mut() {
@@ -462,6 +776,20 @@ impl Scanner {
       }
     }
 
+    // While each amount is range proven to fit within a u64, the sum of the amounts received
+    // within a single transaction isn't. A transaction whose outputs sum to more than u64::MAX
+    // would be spending more than the entirety of the pre-tail emission, and is accordingly
+    // considered malicious. This ensures downstream accounting of a transaction won't overflow
+    if res
+      .iter()
+      .try_fold(0u64, |total, output: &ReceivedOutput| {
+        total.checked_add(output.commitment().amount)
+      })
+      .is_none()
+    {
+      return Timelocked(tx.prefix.timelock, vec![]);
+    }
+
     Timelocked(tx.prefix.timelock, res)
   }
 
@@ -518,4 +846,302 @@ impl Scanner {
     }
     Ok(res)
   }
+
+  /// Scan a block to obtain its spendable outputs, annotated with their key images.
+  ///
+  /// This lets a wallet check if the outputs were spent, such as against the key images of the
+  /// chain's inputs, without deriving their key images in a second pass. The private spend key
+  /// must be the key for this Scanner's view pair, or the key images will be invalid.
+  pub async fn scan_with_key_images<RPC: RpcConnection>(
+    &mut self,
+    rpc: &Rpc<RPC>,
+    block: &Block,
+    spend: &Zeroizing<Scalar>,
+  ) -> Result<Vec<Timelocked<KeyImagedOutput>>, RpcError> {
+    debug_assert_eq!(spend.deref() * ED25519_BASEPOINT_TABLE, self.pair.spend);
+    Ok(
+      self
+        .scan(rpc, block)
+        .await?
+        .into_iter()
+        .map(|mut timelocked| {
+          Timelocked(
+            timelocked.0,
+            timelocked
+              .1
+              .drain(..)
+              .map(|output| KeyImagedOutput { key_image: output.key_image(spend), output })
+              .collect(),
+          )
+        })
+        .collect(),
+    )
+  }
+}
+
+/// A scanner which walks the blockchain from a specified block, scanning each block in order.
+///
+/// Once the tip of the chain has been scanned, `next_block` returns `None`. Calling it again will
+/// check for any new blocks, letting the caller poll the node with whatever backoff suits their
+/// runtime. `scan_from` offers a stream which polls on its own.
+///
+/// The most recently scanned blocks are retained, so if they're reorganized out of the chain, the
+/// outputs yielded for them can be retracted. See `check_reorg` and `rollback`.
+pub struct BlockScanner<'a, RPC: RpcConnection> {
+  rpc: &'a Rpc<RPC>,
+  scanner: Scanner,
+  next: usize,
+  height: usize,
+  min_confirmations: usize,
+  reorg_depth: usize,
+  // The hashes of, and outputs yielded for, the blocks from `next - scanned.len()` to `next`
+  scanned: VecDeque<([u8; 32], Vec<Timelocked<SpendableOutput>>)>,
+}
+
+/// The default amount of recently scanned blocks a `BlockScanner` can roll back.
+pub const DEFAULT_REORG_DEPTH: usize = 100;
+
+impl Scanner {
+  /// Scan the blockchain, block-by-block, starting from the specified block number.
+  pub fn scan_from<RPC: RpcConnection>(
+    self,
+    rpc: &Rpc<RPC>,
+    start: usize,
+  ) -> BlockScanner<'_, RPC> {
+    BlockScanner {
+      rpc,
+      scanner: self,
+      next: start,
+      height: 0,
+      min_confirmations: 1,
+      reorg_depth: DEFAULT_REORG_DEPTH,
+      scanned: VecDeque::new(),
+    }
+  }
+}
+
+impl<RPC: RpcConnection> BlockScanner<'_, RPC> {
+  /// Only scan blocks once they have the specified amount of confirmations.
+  ///
+  /// A block at the tip of the chain has one confirmation, which is the default. Requiring more
+  /// withholds outputs from recent blocks, which could be reorganized out of the chain, until
+  /// enough blocks have been built on top of them.
+  pub fn with_min_confirmations(mut self, min_confirmations: usize) -> Self {
+    self.min_confirmations = min_confirmations.max(1);
+    self
+  }
+
+  /// Retain the specified amount of recently scanned blocks, which is how deep of a reorganization
+  /// can be rolled back.
+  pub fn with_reorg_depth(mut self, reorg_depth: usize) -> Self {
+    self.reorg_depth = reorg_depth;
+    while self.scanned.len() > self.reorg_depth {
+      self.scanned.pop_front();
+    }
+    self
+  }
+
+  // The number of blocks which have the required amount of confirmations, as of the last height
+  // we saw
+  fn confirmed(&self) -> usize {
+    (self.height + 1).saturating_sub(self.min_confirmations)
+  }
+
+  /// The number of the next block to be scanned.
+  pub fn next_block_number(&self) -> usize {
+    self.next
+  }
+
+  /// Return the underlying Scanner, such as to persist the keys it has seen.
+  pub fn into_scanner(self) -> Scanner {
+    self.scanner
+  }
+
+  /// Scan the next block, returning its number and the outputs found within it.
+  ///
+  /// Returns `None` if there is no next block with the required amount of confirmations yet. If an
+  /// error is returned, the block wasn't scanned and will be retried on the next call.
+  pub async fn next_block(
+    &mut self,
+  ) -> Result<Option<(usize, Vec<Timelocked<SpendableOutput>>)>, RpcError> {
+    // Only poll the node for its height once we've caught up to the last height we saw
+    if self.next >= self.confirmed() {
+      self.height = self.rpc.get_height().await?;
+      if self.next >= self.confirmed() {
+        return Ok(None);
+      }
+    }
+
+    let number = self.next;
+    let block = self.rpc.get_block_by_number(number).await?;
+    let outputs = self.scanner.scan(self.rpc, &block).await?;
+    self.next += 1;
+
+    if self.reorg_depth != 0 {
+      if self.scanned.len() == self.reorg_depth {
+        self.scanned.pop_front();
+      }
+      let retained =
+        outputs.iter().map(|outputs| Timelocked(outputs.0, outputs.1.clone())).collect::<Vec<_>>();
+      self.scanned.push_back((block.hash(), retained));
+    }
+
+    Ok(Some((number, outputs)))
+  }
+
+  /// Roll back to the specified block number, so it and every block after it are scanned again.
+  ///
+  /// Returns the outputs previously yielded for the blocks rolled back, which should be retracted
+  /// by the caller. Their keys are no longer considered seen for the burning bug. Only the outputs
+  /// of retained blocks are returned, so rolling back further than the reorg depth will not return
+  /// the outputs of every block rolled back.
+  pub fn rollback(&mut self, number: usize) -> Vec<Timelocked<SpendableOutput>> {
+    let mut retracted = vec![];
+    while self.next > number {
+      self.next -= 1;
+      let Some((_, outputs)) = self.scanned.pop_back() else {
+        self.next = number;
+        break;
+      };
+      if let Some(burning_bug) = self.scanner.burning_bug.as_mut() {
+        for output in outputs.iter().flat_map(|outputs| &outputs.1) {
+          burning_bug.remove(&output.key().compress());
+        }
+      }
+      retracted.push(outputs);
+    }
+    retracted.reverse();
+    let retracted = retracted.into_iter().flatten().collect();
+    // The height we last saw may be for the chain we're rolling back from
+    self.height = 0;
+    retracted
+  }
+
+  /// Check if any retained block was reorganized out of the chain, rolling back to the first such
+  /// block if so.
+  ///
+  /// Returns the number of the first block no longer in the chain and the outputs retracted, as
+  /// `rollback` does, or `None` if no retained block was reorganized out. This should be called
+  /// before `next_block` in order to not yield outputs from a chain which doesn't build upon the
+  /// blocks previously scanned.
+  pub async fn check_reorg(
+    &mut self,
+  ) -> Result<Option<(usize, Vec<Timelocked<SpendableOutput>>)>, RpcError> {
+    let height = self.rpc.get_height().await?;
+    let mut fork = None;
+    // Walk back from the most recently scanned block until we find one still in the chain
+    for (i, (hash, _)) in self.scanned.iter().enumerate().rev() {
+      let number = self.next - self.scanned.len() + i;
+      if (number < height) && (self.rpc.get_block_hash(number).await? == *hash) {
+        break;
+      }
+      fork = Some(number);
+    }
+    Ok(fork.map(|fork| (fork, self.rollback(fork))))
+  }
+}
+
+/// The delay before polling the node again, once `scan_from` has reached the tip of the chain.
+#[cfg(feature = "http-rpc")]
+const SCAN_FROM_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// The maximum delay between polls of the node by `scan_from`.
+#[cfg(feature = "http-rpc")]
+const SCAN_FROM_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Scan the blockchain, starting from the specified block number, to discover the spendable
+/// outputs received to the standard address of the specified keys.
+///
+/// Yields the number of each block alongside the outputs found within it, in order. Once the tip
+/// of the chain has been scanned, the node is polled for new blocks, with an exponential backoff
+/// between polls which resets once a new block is found. Errors from the node are retried with
+/// the same backoff, so the stream never ends.
+///
+/// The outputs' timelocks are ignored, and the caller must check the timelock of the transaction
+/// each output is from before spending it. Reorganizations aren't detected. Wallets which need to
+/// handle them, or to require more confirmations, should use a `BlockScanner`.
+///
+/// Requires tokio.
+#[cfg(feature = "http-rpc")]
+pub fn scan_from<RPC: RpcConnection>(
+  rpc: &Rpc<RPC>,
+  view: Zeroizing<Scalar>,
+  spend: EdwardsPoint,
+  start_height: usize,
+) -> impl '_ + Stream<Item = (u64, Vec<SpendableOutput>)> {
+  let scanner = Scanner::from_view(ViewPair::new(spend, view), Some(HashSet::new()))
+    .scan_from(rpc, start_height)
+    .with_reorg_depth(0);
+  stream::unfold((scanner, SCAN_FROM_INITIAL_BACKOFF), |(mut scanner, mut backoff)| async move {
+    loop {
+      match scanner.next_block().await {
+        Ok(Some((number, outputs))) => {
+          let outputs = outputs.iter().flat_map(Timelocked::ignore_timelock).collect();
+          let number = u64::try_from(number).unwrap();
+          return Some(((number, outputs), (scanner, SCAN_FROM_INITIAL_BACKOFF)));
+        }
+        Ok(None) | Err(_) => {
+          tokio::time::sleep(backoff).await;
+          backoff = (backoff * 2).min(SCAN_FROM_MAX_BACKOFF);
+        }
+      }
+    }
+  })
+}
+
+/// A scanner which caches the result of scanning each transaction, keyed by its hash.
+///
+/// Re-scanning a transaction, such as when re-scanning overlapping blocks after a reorganization
+/// or restart, returns the cached outputs without scanning it again. Since the cache is bound to a
+/// single Scanner, its results are solely for that Scanner's view pair and subaddresses.
+pub struct ScanCache {
+  scanner: Scanner,
+  cache: HashMap<[u8; 32], (Timelock, Vec<ReceivedOutput>)>,
+}
+
+impl Scanner {
+  /// Cache the results of this Scanner, so re-scanning transactions doesn't recompute them.
+  pub fn cached(self) -> ScanCache {
+    ScanCache { scanner: self, cache: HashMap::new() }
+  }
+}
+
+impl ScanCache {
+  /// Return the underlying Scanner, such as to persist the keys it has seen.
+  pub fn into_scanner(self) -> Scanner {
+    self.scanner
+  }
+
+  /// If the transaction with the specified hash has its scan result cached.
+  pub fn is_cached(&self, tx: &[u8; 32]) -> bool {
+    self.cache.contains_key(tx)
+  }
+
+  /// Scan a transaction to discover the received outputs, returning the cached result if this
+  /// transaction was already scanned.
+  pub fn scan_transaction(&mut self, tx: &Transaction) -> Timelocked<ReceivedOutput> {
+    let hash = tx.hash();
+    if let Some((timelock, outputs)) = self.cache.get(&hash) {
+      return Timelocked(*timelock, outputs.clone());
+    }
+
+    let outputs = self.scanner.scan_transaction(tx);
+    self.cache.insert(hash, (outputs.0, outputs.1.clone()));
+    outputs
+  }
+
+  /// Invalidate the cached result for a transaction, such as when it was reorganized out.
+  ///
+  /// The keys of the outputs it had are no longer considered seen for the burning bug, so a
+  /// re-inclusion of this transaction will be scanned as if it was never scanned before.
+  ///
+  /// Returns if the transaction had a cached result.
+  pub fn invalidate(&mut self, tx: &[u8; 32]) -> bool {
+    let Some((_, outputs)) = self.cache.remove(tx) else { return false };
+    if let Some(burning_bug) = self.scanner.burning_bug.as_mut() {
+      for output in outputs {
+        burning_bug.remove(&output.key().compress());
+      }
+    }
+    true
+  }
 }
@@ -39,7 +39,7 @@ use crate::{
   wallet::{
     address::{Network, AddressSpec, MoneroAddress},
     ViewPair, SpendableOutput, Decoys, PaymentId, ExtraField, Extra, key_image_sort, uniqueness,
-    shared_key, commitment_mask, amount_encryption,
+    shared_key, derive_output_key, commitment_mask, amount_encryption,
     extra::{ARBITRARY_DATA_MARKER, MAX_ARBITRARY_DATA_SIZE},
   },
 };
@@ -90,7 +90,7 @@ impl SendOutput {
       SendOutput {
         R,
         view_tag,
-        dest: ((&shared_key * ED25519_BASEPOINT_TABLE) + output.0.spend),
+        dest: derive_output_key(shared_key, output.0.spend),
         commitment: Commitment::new(commitment_mask(shared_key), output.1),
         amount: amount_encryption(output.1, shared_key),
       },
@@ -105,6 +105,7 @@ async fn add_tributary<D: Db, Pro: Processors, P: P2p>(
     spec.start_time(),
     key.clone(),
     spec.validators(),
+    tributary::BLOCK_SIZE_LIMIT,
     p2p,
   )
   .await
@@ -1376,6 +1377,9 @@ async fn main() {
     }
   })
   .await;
-  let p2p = LibP2p::new(serai.clone());
+  let fanout = serai_env::var("P2P_FANOUT")
+    .map(|fanout| fanout.parse().expect("P2P_FANOUT wasn't a valid amount of peers"))
+    .unwrap_or(DEFAULT_FANOUT);
+  let p2p = LibP2p::new(serai.clone(), fanout);
   run(db, key, p2p, processors, serai).await
 }
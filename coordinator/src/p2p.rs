@@ -42,6 +42,41 @@ use crate::{Transaction, Block, Tributary, ActiveTributary, TributaryEvent};
 
 const LIBP2P_TOPIC: &str = "serai-coordinator";
 
+/// The default amount of peers each node forwards gossiped messages to.
+///
+/// This is gossipsub's default, and with it, gossipsub's default mesh degrees are used.
+pub const DEFAULT_FANOUT: usize = 6;
+
+/// The mesh degrees used for a given fanout.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MeshDegrees {
+  /// The amount of peers to forward messages to.
+  pub target: usize,
+  /// The amount of peers below which more will be added to the mesh.
+  pub low: usize,
+  /// The amount of peers above which peers will be pruned from the mesh.
+  pub high: usize,
+  /// The minimum amount of outbound peers kept in the mesh.
+  pub outbound_min: usize,
+}
+
+impl MeshDegrees {
+  /// Derive the mesh degrees for the specified fanout.
+  ///
+  /// These are derived as gossipsub derives its defaults from its default fanout. The fanout must
+  /// be non-zero.
+  pub fn new(fanout: usize) -> Self {
+    assert!(fanout != 0, "fanout of 0 wouldn't forward messages to any peer");
+    MeshDegrees {
+      target: fanout,
+      low: (fanout - 1).max(1),
+      high: fanout * 2,
+      // This must be at most half of the target
+      outbound_min: fanout / 3,
+    }
+  }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, BorshSerialize, BorshDeserialize)]
 pub struct CosignedBlock {
   pub network: NetworkId,
@@ -190,6 +225,55 @@ pub trait P2p: Send + Sync + Clone + fmt::Debug + TributaryP2p {
   }
 }
 
+/// The inbound message channels for every tributary this coordinator is running, keyed by genesis.
+///
+/// Serai runs several tributaries concurrently over a single P2P network. Messages for a tributary
+/// are prefixed with its genesis, which is used to route them to that tributary's handler.
+#[allow(clippy::type_complexity)]
+#[derive(Clone, Debug)]
+pub struct TributaryManager<P: P2p>(
+  Arc<RwLock<HashMap<[u8; 32], mpsc::UnboundedSender<Message<P>>>>>,
+);
+
+impl<P: P2p> Default for TributaryManager<P> {
+  fn default() -> Self {
+    TributaryManager(Arc::new(RwLock::new(HashMap::new())))
+  }
+}
+
+impl<P: P2p> TributaryManager<P> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Register the tributary with the specified genesis, returning the channel its messages will
+  /// be routed to.
+  ///
+  /// If a tributary with this genesis was already registered, it's replaced.
+  pub async fn register(&self, genesis: [u8; 32]) -> mpsc::UnboundedReceiver<Message<P>> {
+    let (send, recv) = mpsc::unbounded_channel();
+    self.0.write().await.insert(genesis, send);
+    recv
+  }
+
+  /// Retire the tributary with the specified genesis, closing its channel.
+  pub async fn retire(&self, genesis: [u8; 32]) {
+    self.0.write().await.remove(&genesis);
+  }
+
+  /// Route a message to the tributary whose genesis it's prefixed with.
+  ///
+  /// Returns false if the message isn't for a tributary, or isn't for a tributary registered with
+  /// this manager, in which case it was dropped.
+  pub async fn route(&self, msg: Message<P>) -> bool {
+    let Some(genesis) = msg.kind.genesis() else { return false };
+    let Some(channel) = self.0.read().await.get(&genesis).cloned() else { return false };
+    // The receiver is only dropped when the handler exits, which it should only do after the
+    // tributary retires, so this is presumably a race with retiring it
+    channel.send(msg).is_ok()
+  }
+}
+
 #[derive(NetworkBehaviour)]
 struct Behavior {
   gossipsub: GsBehavior,
@@ -209,8 +293,12 @@ impl fmt::Debug for LibP2p {
 }
 
 impl LibP2p {
+  /// Create a new LibP2p instance.
+  ///
+  /// `fanout` is the amount of peers each message is forwarded to, trading propagation speed for
+  /// bandwidth. Messages are only forwarded to this subset of peers, not every peer.
   #[allow(clippy::new_without_default)]
-  pub fn new(serai: Arc<Serai>) -> Self {
+  pub fn new(serai: Arc<Serai>, fanout: usize) -> Self {
     // Block size limit + 1 KB of space for signatures/metadata
     const MAX_LIBP2P_MESSAGE_SIZE: usize = tributary::BLOCK_SIZE_LIMIT + 1024;
 
@@ -224,8 +312,19 @@ impl LibP2p {
         let heartbeats_per_block =
           usize::try_from(tributary::tendermint::TARGET_BLOCK_TIME / heartbeat_interval).unwrap();
 
+        let mut config = ConfigBuilder::default();
+        // Solely override gossipsub's mesh degrees if a non-default fanout was configured
+        if fanout != DEFAULT_FANOUT {
+          let degrees = MeshDegrees::new(fanout);
+          config
+            .mesh_n(degrees.target)
+            .mesh_n_low(degrees.low)
+            .mesh_n_high(degrees.high)
+            .mesh_outbound_min(degrees.outbound_min);
+        }
+
         use blake2::{Digest, Blake2s256};
-        let config = ConfigBuilder::default()
+        let config = config
           .heartbeat_interval(Duration::from_millis(heartbeat_interval.into()))
           .history_length(heartbeats_per_block * 2)
           .history_gossip(heartbeats_per_block)
@@ -613,10 +712,10 @@ pub async fn handle_p2p_task<D: Db, P: P2p>(
   cosign_channel: mpsc::UnboundedSender<CosignedBlock>,
   mut tributary_event: broadcast::Receiver<TributaryEvent<D, P>>,
 ) {
-  let channels = Arc::new(RwLock::new(HashMap::<_, mpsc::UnboundedSender<Message<P>>>::new()));
+  let tributaries = TributaryManager::<P>::new();
   tokio::spawn({
     let p2p = p2p.clone();
-    let channels = channels.clone();
+    let tributaries = tributaries.clone();
     let mut set_to_genesis = HashMap::new();
     async move {
       loop {
@@ -625,8 +724,7 @@ pub async fn handle_p2p_task<D: Db, P: P2p>(
             let genesis = tributary.spec.genesis();
             set_to_genesis.insert(tributary.spec.set(), genesis);
 
-            let (send, mut recv) = mpsc::unbounded_channel();
-            channels.write().await.insert(genesis, send);
+            let mut recv = tributaries.register(genesis).await;
 
             // Subscribe to the topic for this tributary
             p2p.subscribe(tributary.spec.set(), genesis).await;
@@ -753,7 +851,7 @@ pub async fn handle_p2p_task<D: Db, P: P2p>(
           TributaryEvent::TributaryRetired(set) => {
             if let Some(genesis) = set_to_genesis.remove(&set) {
               p2p.unsubscribe(set, genesis).await;
-              channels.write().await.remove(&genesis);
+              tributaries.retire(genesis).await;
             }
           }
         }
@@ -765,12 +863,8 @@ pub async fn handle_p2p_task<D: Db, P: P2p>(
     let msg = p2p.receive().await;
     match msg.kind {
       P2pMessageKind::KeepAlive => {}
-      P2pMessageKind::Tributary(genesis) |
-      P2pMessageKind::Heartbeat(genesis) |
-      P2pMessageKind::Block(genesis) => {
-        if let Some(channel) = channels.read().await.get(&genesis) {
-          channel.send(msg).unwrap();
-        }
+      P2pMessageKind::Tributary(_) | P2pMessageKind::Heartbeat(_) | P2pMessageKind::Block(_) => {
+        tributaries.route(msg).await;
       }
       P2pMessageKind::CosignedBlock => {
         let Ok(msg) = CosignedBlock::deserialize_reader(&mut msg.msg.as_slice()) else {
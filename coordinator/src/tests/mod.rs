@@ -19,6 +19,8 @@ use crate::{
 
 pub mod tributary;
 
+mod p2p;
+
 #[derive(Clone)]
 pub struct MemProcessors(pub Arc<RwLock<HashMap<NetworkId, VecDeque<CoordinatorMessage>>>>);
 impl MemProcessors {
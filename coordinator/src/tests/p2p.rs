@@ -0,0 +1,87 @@
+use rand_core::{RngCore, OsRng};
+
+use libp2p::gossipsub::ConfigBuilder;
+
+use crate::{DEFAULT_FANOUT, MeshDegrees, P2pMessageKind, P2p, TributaryManager, tests::LocalP2p};
+
+#[test]
+fn mesh_degrees() {
+  for fanout in 1 .. 32 {
+    let degrees = MeshDegrees::new(fanout);
+    assert_eq!(degrees.target, fanout);
+    assert!(degrees.low >= 1);
+    assert!(degrees.outbound_min <= degrees.low);
+    assert!(degrees.low <= degrees.target);
+    assert!(degrees.target <= degrees.high);
+    assert!((degrees.outbound_min * 2) <= degrees.target);
+  }
+
+  // The default fanout should have gossipsub's default mesh degrees
+  let config = ConfigBuilder::default().build().unwrap();
+  assert_eq!(
+    MeshDegrees::new(DEFAULT_FANOUT),
+    MeshDegrees {
+      target: config.mesh_n(),
+      low: config.mesh_n_low(),
+      high: config.mesh_n_high(),
+      outbound_min: config.mesh_outbound_min(),
+    }
+  );
+}
+
+#[tokio::test]
+async fn tributary_manager_routes_by_genesis() {
+  let mut genesis_a = [0; 32];
+  OsRng.fill_bytes(&mut genesis_a);
+  let mut genesis_b = [0; 32];
+  OsRng.fill_bytes(&mut genesis_b);
+  let mut unknown = [0; 32];
+  OsRng.fill_bytes(&mut unknown);
+
+  let p2p = LocalP2p::new(2);
+  let tributaries = TributaryManager::<LocalP2p>::new();
+  let mut recv_a = tributaries.register(genesis_a).await;
+  let mut recv_b = tributaries.register(genesis_b).await;
+
+  // Send interleaved messages for both tributaries, along with one for an unknown tributary
+  let msgs = [
+    (genesis_a, P2pMessageKind::Tributary(genesis_a)),
+    (genesis_b, P2pMessageKind::Tributary(genesis_b)),
+    (genesis_b, P2pMessageKind::Heartbeat(genesis_b)),
+    (unknown, P2pMessageKind::Tributary(unknown)),
+    (genesis_a, P2pMessageKind::Block(genesis_a)),
+    (genesis_a, P2pMessageKind::Heartbeat(genesis_a)),
+    (genesis_b, P2pMessageKind::Block(genesis_b)),
+  ];
+  for (i, (_, kind)) in msgs.iter().enumerate() {
+    P2p::broadcast(&p2p[0], *kind, vec![u8::try_from(i).unwrap()]).await;
+  }
+
+  // Receive them over the wire and route them
+  for (i, (genesis, kind)) in msgs.iter().enumerate() {
+    let msg = p2p[1].receive().await;
+    assert_eq!(msg.sender, 0);
+    assert_eq!(msg.kind, *kind);
+    assert_eq!(msg.msg, vec![u8::try_from(i).unwrap()]);
+    assert_eq!(tributaries.route(msg).await, *genesis != unknown);
+  }
+
+  // Each tributary should've received solely its own messages, in order
+  for (genesis, recv) in [(genesis_a, &mut recv_a), (genesis_b, &mut recv_b)] {
+    for (i, (msg_genesis, kind)) in msgs.iter().enumerate() {
+      if *msg_genesis != genesis {
+        continue;
+      }
+      let msg = recv.try_recv().unwrap();
+      assert_eq!(msg.kind, *kind);
+      assert_eq!(msg.msg, vec![u8::try_from(i).unwrap()]);
+    }
+    assert!(recv.try_recv().is_err());
+  }
+
+  // Once retired, a tributary's messages should no longer be routed
+  tributaries.retire(genesis_a).await;
+  assert!(recv_a.try_recv().is_err());
+  P2p::broadcast(&p2p[0], P2pMessageKind::Tributary(genesis_a), vec![0xff]).await;
+  assert!(!tributaries.route(p2p[1].receive().await).await);
+}
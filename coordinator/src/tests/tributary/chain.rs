@@ -82,6 +82,7 @@ pub async fn new_tributaries(
         spec.start_time(),
         key.clone(),
         spec.validators(),
+        tributary::BLOCK_SIZE_LIMIT,
         p2p[i].clone(),
       )
       .await
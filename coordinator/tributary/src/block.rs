@@ -14,7 +14,7 @@ use crate::{
     TransactionError, Signed, TransactionKind, Transaction as TransactionTrait, GAIN,
     verify_transaction,
   },
-  BLOCK_SIZE_LIMIT, ReadWrite, merkle, Transaction,
+  ReadWrite, merkle, Transaction,
   tendermint::tx::verify_tendermint_tx,
 };
 
@@ -33,22 +33,33 @@ pub enum BlockError {
   #[error("an unsigned transaction which was already added to the chain was present again")]
   UnsignedAlreadyIncluded,
   /// A provided transaction which was already added to the chain was present again.
-  #[error("an provided transaction which was already added to the chain was present again")]
+  #[error("a provided transaction which was already added to the chain was present again")]
   ProvidedAlreadyIncluded,
   /// Transactions weren't ordered as expected (Provided, followed by Unsigned, followed by Signed).
   #[error("transactions weren't ordered as expected (Provided, Unsigned, Signed)")]
   WrongTransactionOrder,
   /// The block had a provided transaction this validator has yet to be provided.
-  #[error("block had a provided transaction not yet locally provided: {0:?}")]
+  #[error("block had a provided transaction not yet locally provided: {}", hex::encode(.0))]
   NonLocalProvided([u8; 32]),
   /// The provided transaction was distinct from the locally provided transaction.
   #[error("block had a distinct provided transaction")]
   DistinctProvided,
   /// An included transaction was invalid.
-  #[error("included transaction had an error")]
+  #[error("included transaction had an error: {0}")]
   TransactionError(TransactionError),
 }
 
+/// Why a block was rejected when validating it as a proposal.
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum BlockRejection {
+  /// The block couldn't be decoded.
+  #[error("block couldn't be decoded: {0}")]
+  Undecodable(String),
+  /// The block was decoded yet was invalid.
+  #[error("{0}")]
+  Invalid(BlockError),
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct BlockHeader {
   pub parent: [u8; 32],
@@ -111,7 +122,13 @@ impl<T: TransactionTrait> Block<T> {
   /// Create a new block.
   ///
   /// mempool is expected to only have valid, non-conflicting transactions, sorted by nonce.
-  pub(crate) fn new(parent: [u8; 32], provided: Vec<T>, mempool: Vec<Transaction<T>>) -> Self {
+  /// Transactions are dropped from the end of the block until it fits within block_size_limit.
+  pub(crate) fn new(
+    parent: [u8; 32],
+    provided: Vec<T>,
+    mempool: Vec<Transaction<T>>,
+    block_size_limit: usize,
+  ) -> Self {
     let mut txs = vec![];
     for tx in provided {
       txs.push(Transaction::Application(tx))
@@ -151,7 +168,7 @@ impl<T: TransactionTrait> Block<T> {
 
     let mut res =
       Block { header: BlockHeader { parent, transactions: [0; 32] }, transactions: txs };
-    while res.serialize().len() > BLOCK_SIZE_LIMIT {
+    while res.serialize().len() > block_size_limit {
       assert!(res.transactions.pop().is_some());
     }
     let hashes = res.transactions.iter().map(Transaction::hash).collect::<Vec<_>>();
@@ -178,6 +195,7 @@ impl<T: TransactionTrait> Block<T> {
     commit: impl Fn(u64) -> Option<Commit<N::SignatureScheme>>,
     provided_or_unsigned_in_chain: impl Fn([u8; 32]) -> bool,
     allow_non_local_provided: bool,
+    block_size_limit: usize,
   ) -> Result<(), BlockError> {
     #[derive(Clone, Copy, PartialEq, Eq, Debug)]
     enum Order {
@@ -195,7 +213,7 @@ impl<T: TransactionTrait> Block<T> {
       }
     }
 
-    if self.serialize().len() > BLOCK_SIZE_LIMIT {
+    if self.serialize().len() > block_size_limit {
       Err(BlockError::TooLargeBlock)?;
     }
 
@@ -216,6 +234,8 @@ impl<T: TransactionTrait> Block<T> {
             Err(BlockError::ProvidedAlreadyIncluded)?;
           }
 
+          // Provided transactions of a given order must be included in the order they were
+          // provided, so compare against the next transaction we have locally for this order
           if let Some(local) = locally_provided.get_mut(order).and_then(VecDeque::pop_front) {
             // Since this was a provided TX, it must be an application TX
             let Transaction::Application(tx) = tx else {
@@ -1,5 +1,7 @@
 use std::collections::{VecDeque, HashSet};
 
+use thiserror::Error;
+
 use ciphersuite::{group::GroupEncoding, Ciphersuite, Ristretto};
 
 use serai_db::{Get, DbTxn, Db};
@@ -11,8 +13,23 @@ use tendermint::ext::{Network, Commit};
 use crate::{
   ReadWrite, ProvidedError, ProvidedTransactions, BlockError, Block, Mempool, Transaction,
   transaction::{Signed, TransactionKind, TransactionError, Transaction as TransactionTrait},
+  tendermint::TendermintBlock,
 };
 
+/// Why a chain failed verification, with the number of the offending block.
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum ChainError {
+  /// A block within the chain was missing.
+  #[error("block {0} was missing")]
+  MissingBlock(u64),
+  /// A block didn't build off the block before it.
+  #[error("block {0} didn't build off the prior block")]
+  InvalidParent(u64),
+  /// A block's commit was invalid.
+  #[error("block {0} had an invalid commit")]
+  InvalidCommit(u64),
+}
+
 #[derive(Debug)]
 pub(crate) struct Blockchain<D: Db, T: TransactionTrait> {
   db: Option<D>,
@@ -21,6 +38,7 @@ pub(crate) struct Blockchain<D: Db, T: TransactionTrait> {
   block_number: u64,
   tip: [u8; 32],
   participants: HashSet<<Ristretto as Ciphersuite>::G>,
+  block_size_limit: usize,
 
   provided: ProvidedTransactions<D, T>,
   mempool: Mempool<D, T>,
@@ -32,12 +50,15 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
   fn tip_key(genesis: [u8; 32]) -> Vec<u8> {
     D::key(b"tributary_blockchain", b"tip", genesis)
   }
-  fn block_number_key(&self) -> Vec<u8> {
-    D::key(b"tributary_blockchain", b"block_number", self.genesis)
+  fn block_number_key(genesis: [u8; 32]) -> Vec<u8> {
+    D::key(b"tributary_blockchain", b"block_number", genesis)
   }
   fn block_key(genesis: &[u8], hash: &[u8; 32]) -> Vec<u8> {
     D::key(b"tributary_blockchain", b"block", [genesis, hash].concat())
   }
+  fn pruned_below_key(genesis: &[u8]) -> Vec<u8> {
+    D::key(b"tributary_blockchain", b"pruned_below", genesis)
+  }
   fn block_hash_key(genesis: &[u8], block_number: u64) -> Vec<u8> {
     D::key(b"tributary_blockchain", b"block_hash", [genesis, &block_number.to_le_bytes()].concat())
   }
@@ -69,11 +90,13 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
     db: D,
     genesis: [u8; 32],
     participants: &[<Ristretto as Ciphersuite>::G],
+    block_size_limit: usize,
   ) -> Self {
     let mut res = Self {
       db: Some(db.clone()),
       genesis,
       participants: participants.iter().copied().collect(),
+      block_size_limit,
 
       block_number: 0,
       tip: genesis,
@@ -86,11 +109,13 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
 
     if let Some((block_number, tip)) = {
       let db = res.db.as_ref().unwrap();
-      db.get(res.block_number_key()).map(|number| (number, db.get(Self::tip_key(genesis)).unwrap()))
+      db.get(Self::block_number_key(genesis))
+        .map(|number| (number, db.get(Self::tip_key(genesis)).unwrap()))
     } {
       res.block_number = u64::from_le_bytes(block_number.try_into().unwrap());
       res.tip.copy_from_slice(&tip);
     }
+    res.provided.migrate_provided_at(res.block_number);
 
     res
   }
@@ -103,6 +128,10 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
     self.block_number
   }
 
+  pub(crate) fn block_size_limit(&self) -> usize {
+    self.block_size_limit
+  }
+
   pub(crate) fn block_from_db(db: &D, genesis: [u8; 32], block: &[u8; 32]) -> Option<Block<T>> {
     db.get(Self::block_key(&genesis, block))
       .map(|bytes| Block::<T>::read::<&[u8]>(&mut bytes.as_ref()).unwrap())
@@ -132,6 +161,22 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
     db.get(Self::block_after_key(&genesis, block)).map(|bytes| bytes.try_into().unwrap())
   }
 
+  /// Iterate over the blocks after the specified block, in order, until the tip.
+  ///
+  /// This stops at the first block which has been pruned.
+  pub(crate) fn blocks_after<'a>(
+    db: &'a D,
+    genesis: [u8; 32],
+    block: &[u8; 32],
+  ) -> impl Iterator<Item = Block<T>> + 'a {
+    let mut hash = *block;
+    core::iter::from_fn(move || {
+      hash = Self::block_after(db, genesis, &hash)?;
+      // If we have a block after this block, we have that block, unless it was pruned
+      Self::block_from_db(db, genesis, &hash)
+    })
+  }
+
   pub(crate) fn locally_provided_txs_in_block(
     db: &D,
     genesis: &[u8; 32],
@@ -151,6 +196,80 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
     db.get(Self::tip_key(genesis)).map_or(genesis, |bytes| bytes.try_into().unwrap())
   }
 
+  pub(crate) fn block_number_from_db(db: &D, genesis: [u8; 32]) -> u64 {
+    db.get(Self::block_number_key(genesis))
+      .map_or(0, |bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+  }
+
+  pub(crate) fn pruned_below_from_db(db: &D, genesis: [u8; 32]) -> u64 {
+    db.get(Self::pruned_below_key(&genesis))
+      .map_or(1, |bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+  }
+
+  /// Prune the bodies of all blocks below the specified block number.
+  ///
+  /// The hashes and commits of pruned blocks are retained, so the chain remains verifiable, yet
+  /// the blocks themselves will no longer be returned by `block_from_db`. The tip is never
+  /// pruned.
+  pub(crate) fn prune_below(&mut self, block_number: u64) {
+    let genesis = self.genesis;
+    let block_number = block_number.min(self.block_number);
+
+    let db = self.db.as_mut().unwrap();
+    let pruned_below = Self::pruned_below_from_db(db, genesis);
+    if block_number <= pruned_below {
+      return;
+    }
+
+    let mut txn = db.txn();
+    for number in pruned_below .. block_number {
+      let hash: [u8; 32] =
+        txn.get(Self::block_hash_key(&genesis, number)).unwrap().try_into().unwrap();
+      txn.del(Self::block_key(&genesis, &hash));
+    }
+    txn.put(Self::pruned_below_key(&genesis), block_number.to_le_bytes());
+    txn.commit();
+
+    log::info!("pruned tributary {} below block {block_number}", hex::encode(genesis));
+  }
+
+  /// Verify the entire chain, from genesis to the tip.
+  ///
+  /// This checks every block builds off the block before it and was finalized by a valid commit.
+  /// As Tributaries have a static validator set, every commit is verified against the network's
+  /// validators. Pruned blocks are verified by the links recorded when they were added.
+  pub(crate) fn verify_chain<N: Network<Block = TendermintBlock>>(
+    &self,
+    network: &N,
+  ) -> Result<(), ChainError> {
+    let db = self.db.as_ref().unwrap();
+    let pruned_below = Self::pruned_below_from_db(db, self.genesis);
+
+    let mut parent = self.genesis;
+    for number in 1 ..= self.block_number {
+      let hash = self.block_hash(number).ok_or(ChainError::MissingBlock(number))?;
+      if let Some(block) = Self::block_from_db(db, self.genesis, &hash) {
+        if (block.hash() != hash) || (block.parent() != parent) {
+          Err(ChainError::InvalidParent(number))?;
+        }
+      } else if number >= pruned_below {
+        Err(ChainError::MissingBlock(number))?;
+      } else if Self::block_after(db, self.genesis, &parent) != Some(hash) {
+        Err(ChainError::InvalidParent(number))?;
+      }
+
+      let commit = self.commit(&hash).ok_or(ChainError::MissingBlock(number))?;
+      let commit = Commit::<N::SignatureScheme>::decode(&mut commit.as_ref())
+        .map_err(|_| ChainError::InvalidCommit(number))?;
+      if !network.verify_commit(hash, &commit) {
+        Err(ChainError::InvalidCommit(number))?;
+      }
+
+      parent = hash;
+    }
+    Ok(())
+  }
+
   pub(crate) fn add_transaction<N: Network>(
     &mut self,
     internal: bool,
@@ -190,7 +309,13 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
   }
 
   pub(crate) fn provide_transaction(&mut self, tx: T) -> Result<(), ProvidedError> {
-    self.provided.provide(tx)
+    self.provided.provide(tx, self.block_number)
+  }
+
+  /// The locally provided transactions which no block has included despite being provided at
+  /// least `window` blocks ago.
+  pub(crate) fn orphaned_provided(&self, window: u64) -> Vec<T> {
+    self.provided.orphaned(self.block_number, window)
   }
 
   pub(crate) fn next_nonce(
@@ -220,6 +345,7 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
       self.tip,
       self.provided.transactions.values().flatten().cloned().collect(),
       self.mempool.block(),
+      self.block_size_limit,
     );
     // build_block should not return invalid blocks
     self.verify_block::<N>(&block, schema, false).unwrap();
@@ -265,12 +391,26 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
       &commit,
       provided_or_unsigned_in_chain,
       allow_non_local_provided,
+      self.block_size_limit,
     );
     // Drop this TXN's changes as we're solely verifying the block
     drop(txn);
     res
   }
 
+  /// Validate a block as the next block, without mutating the blockchain.
+  ///
+  /// This performs the same checks as when adding a block, allowing provided transactions which
+  /// haven't been locally provided. The block's commit isn't checked. Any state changes made while
+  /// verifying, such as consuming nonces, are discarded.
+  pub(crate) fn validate_block<N: Network>(
+    &self,
+    block: &Block<T>,
+    schema: &N::SignatureScheme,
+  ) -> Result<(), BlockError> {
+    self.verify_block::<N>(block, schema, true)
+  }
+
   /// Add a block.
   pub(crate) fn add_block<N: Network>(
     &mut self,
@@ -298,7 +438,7 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
     txn.put(Self::tip_key(self.genesis), self.tip);
 
     self.block_number += 1;
-    txn.put(self.block_number_key(), self.block_number.to_le_bytes());
+    txn.put(Self::block_number_key(self.genesis), self.block_number.to_le_bytes());
 
     txn.put(Self::block_hash_key(&self.genesis, self.block_number), self.tip);
 
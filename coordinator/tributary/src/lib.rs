@@ -1,5 +1,5 @@
 use core::{marker::PhantomData, fmt::Debug};
-use std::{sync::Arc, io, collections::VecDeque};
+use std::{sync::Arc, io};
 
 use async_trait::async_trait;
 
@@ -39,6 +39,7 @@ pub use block::*;
 
 mod blockchain;
 pub(crate) use blockchain::*;
+pub use blockchain::ChainError;
 
 mod mempool;
 pub(crate) use mempool::*;
@@ -53,7 +54,7 @@ pub mod tests;
 pub const TRANSACTION_SIZE_LIMIT: usize = 3_000_000;
 /// Amount of transactions a single account may have in the mempool.
 pub const ACCOUNT_MEMPOOL_LIMIT: u32 = 50;
-/// Block size limit.
+/// Maximum block size limit.
 // This targets a growth limit of roughly 45 GB a day, under load, in order to prevent a malicious
 // participant from flooding disks and causing out of space errors in order processes.
 pub const BLOCK_SIZE_LIMIT: usize = 3_001_000;
@@ -166,22 +167,32 @@ impl<D: Db, T: TransactionTrait, P: P2p> Drop for Tributary<D, T, P> {
 }
 
 impl<D: Db, T: TransactionTrait, P: P2p> Tributary<D, T, P> {
+  /// Create a new Tributary.
+  ///
+  /// block_size_limit is the maximum size of a serialized block, which must be agreed upon by all
+  /// validators. Returns None if it exceeds BLOCK_SIZE_LIMIT or the validators are invalid.
   pub async fn new(
     db: D,
     genesis: [u8; 32],
     start_time: u64,
     key: Zeroizing<<Ristretto as Ciphersuite>::F>,
     validators: Vec<(<Ristretto as Ciphersuite>::G, u64)>,
+    block_size_limit: usize,
     p2p: P,
   ) -> Option<Self> {
     log::info!("new Tributary with genesis {}", hex::encode(genesis));
 
+    // Blocks larger than BLOCK_SIZE_LIMIT wouldn't fit within P2P messages
+    if block_size_limit > BLOCK_SIZE_LIMIT {
+      None?;
+    }
+
     let validators_vec = validators.iter().map(|validator| validator.0).collect::<Vec<_>>();
 
     let signer = Arc::new(Signer::new(genesis, key));
     let validators = Arc::new(Validators::new(genesis, validators)?);
 
-    let mut blockchain = Blockchain::new(db.clone(), genesis, &validators_vec);
+    let mut blockchain = Blockchain::new(db.clone(), genesis, &validators_vec, block_size_limit);
     let block_number = BlockNumber(blockchain.block_number());
 
     let start_time = if let Some(commit) = blockchain.commit(&blockchain.tip()) {
@@ -194,13 +205,13 @@ impl<D: Db, T: TransactionTrait, P: P2p> Tributary<D, T, P> {
     );
     let blockchain = Arc::new(RwLock::new(blockchain));
 
-    let to_rebroadcast = Arc::new(RwLock::new(VecDeque::new()));
+    let network = TendermintNetwork::new(genesis, signer, validators, blockchain, p2p.clone());
+
     // Actively rebroadcast consensus messages to ensure they aren't prematurely dropped from the
     // P2P layer
     let p2p_meta_task_handle = Arc::new(
       tokio::spawn({
-        let to_rebroadcast = to_rebroadcast.clone();
-        let p2p = p2p.clone();
+        let to_rebroadcast = network.to_rebroadcast.clone();
         async move {
           loop {
             let to_rebroadcast = to_rebroadcast.read().await.clone();
@@ -214,9 +225,6 @@ impl<D: Db, T: TransactionTrait, P: P2p> Tributary<D, T, P> {
       .abort_handle(),
     );
 
-    let network =
-      TendermintNetwork { genesis, signer, validators, blockchain, to_rebroadcast, p2p };
-
     let TendermintHandle { synced_block, synced_block_result, messages, machine } =
       TendermintMachine::new(
         db.clone(),
@@ -255,6 +263,72 @@ impl<D: Db, T: TransactionTrait, P: P2p> Tributary<D, T, P> {
     self.network.blockchain.read().await.tip()
   }
 
+  /// Verify the specified block was finalized by the specified commit.
+  ///
+  /// This checks the commit's signers are distinct validators, their aggregate signature, and that
+  /// they have a supermajority of the weight. As Tributaries have a static validator set, these are
+  /// the validators for every block.
+  pub fn verify_commit(&self, block: [u8; 32], commit: &Commit<Validators>) -> bool {
+    self.network.verify_commit(
+      block,
+      &Commit {
+        end_time: commit.end_time,
+        validators: commit.validators.clone(),
+        signature: commit.signature.clone(),
+      },
+    )
+  }
+
+  /// Validate a block as the next block, without mutating the blockchain.
+  ///
+  /// This performs the same checks as when adding a block, yet doesn't check the block's commit.
+  pub async fn validate_block(&self, block: &Block<T>) -> Result<(), BlockError> {
+    self
+      .network
+      .blockchain
+      .read()
+      .await
+      .validate_block::<TendermintNetwork<D, T, P>>(block, &self.network.signature_scheme())
+  }
+
+  /// Why the latest block rejected when validating proposals was rejected, if any block has been.
+  ///
+  /// This is solely for diagnostics, such as determining why the tributary halted.
+  pub async fn last_rejection(&self) -> Option<BlockRejection> {
+    self.network.last_rejection.read().await.clone()
+  }
+
+  /// The distinct validators who signed a commit, and their combined weight.
+  ///
+  /// Returns None if any signer isn't a validator. This doesn't verify the commit's signature.
+  pub fn commit_signers(
+    &self,
+    commit: &Commit<Validators>,
+  ) -> Option<(Vec<<Ristretto as Ciphersuite>::G>, u64)> {
+    self.network.validators.commit_signers(commit)
+  }
+
+  /// The validators who didn't sign a commit, sorted by their keys.
+  ///
+  /// Signers who aren't validators are ignored. This doesn't verify the commit's signature.
+  pub fn absent_signers(&self, commit: &Commit<Validators>) -> Vec<[u8; 32]> {
+    self.network.validators.absent_signers(commit)
+  }
+
+  /// Verify the entire chain, from genesis to the tip.
+  ///
+  /// This checks every block builds off the block before it and was finalized by a valid commit.
+  pub async fn verify_chain(&self) -> Result<(), ChainError> {
+    self.network.blockchain.read().await.verify_chain(&self.network)
+  }
+
+  /// Prune the bodies of all blocks below the specified block number.
+  ///
+  /// The hashes and commits of pruned blocks are retained.
+  pub async fn prune_below(&self, block_number: u64) {
+    self.network.blockchain.write().await.prune_below(block_number);
+  }
+
   pub fn reader(&self) -> TributaryReader<D, T> {
     TributaryReader(self.db.clone(), self.genesis, PhantomData)
   }
@@ -263,6 +337,23 @@ impl<D: Db, T: TransactionTrait, P: P2p> Tributary<D, T, P> {
     self.network.blockchain.write().await.provide_transaction(tx)
   }
 
+  /// The provided transactions which no block has included despite being provided at least
+  /// `window` blocks ago.
+  ///
+  /// These are solely reported, not removed, as such a transaction is most likely yet to be
+  /// provided by other validators, and a block including it will still need to be verified.
+  pub async fn orphaned_provided(&self, window: u64) -> Vec<T> {
+    let orphaned = self.network.blockchain.read().await.orphaned_provided(window);
+    for tx in &orphaned {
+      log::warn!(
+        "provided transaction {} in tributary {} has yet to be included after {window} blocks",
+        hex::encode(tx.hash()),
+        hex::encode(self.genesis),
+      );
+    }
+    orphaned
+  }
+
   pub async fn next_nonce(
     &self,
     signer: &<Ristretto as Ciphersuite>::G,
@@ -366,7 +457,7 @@ impl<D: Db, T: TransactionTrait, P: P2p> Tributary<D, T, P> {
           return false;
         };
 
-        self.messages.write().await.send(msg).await.unwrap();
+        self.messages.write().await.send(msg).unwrap();
         false
       }
 
@@ -418,6 +509,14 @@ impl<D: Db, T: TransactionTrait> TributaryReader<D, T> {
   pub fn block_after(&self, hash: &[u8; 32]) -> Option<[u8; 32]> {
     Blockchain::<D, T>::block_after(&self.0, self.1, hash)
   }
+  /// Iterate over the blocks after the specified block, such as to serve them to a syncing peer.
+  pub fn blocks_after(&self, hash: &[u8; 32]) -> impl Iterator<Item = Block<T>> + '_ {
+    Blockchain::<D, T>::blocks_after(&self.0, self.1, hash)
+  }
+  /// The number of the first block whose body hasn't been pruned.
+  pub fn pruned_below(&self) -> u64 {
+    Blockchain::<D, T>::pruned_below_from_db(&self.0, self.1)
+  }
   pub fn time_of_block(&self, hash: &[u8; 32]) -> Option<u64> {
     self
       .commit(hash)
@@ -432,4 +531,8 @@ impl<D: Db, T: TransactionTrait> TributaryReader<D, T> {
   pub fn tip(&self) -> [u8; 32] {
     Blockchain::<D, T>::tip_from_db(&self.0, self.1)
   }
+
+  pub fn block_number(&self) -> u64 {
+    Blockchain::<D, T>::block_number_from_db(&self.0, self.1)
+  }
 }
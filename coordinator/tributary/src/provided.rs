@@ -28,12 +28,17 @@ pub struct ProvidedTransactions<D: Db, T: Transaction> {
   genesis: [u8; 32],
 
   pub(crate) transactions: HashMap<&'static str, VecDeque<T>>,
+  // The block number each locally provided, yet not on-chain, transaction was provided at
+  provided_at: HashMap<[u8; 32], u64>,
 }
 
 impl<D: Db, T: Transaction> ProvidedTransactions<D, T> {
   fn transaction_key(&self, hash: &[u8]) -> Vec<u8> {
     D::key(b"tributary_provided", b"transaction", [self.genesis.as_ref(), hash].concat())
   }
+  fn provided_at_key(&self, hash: &[u8]) -> Vec<u8> {
+    D::key(b"tributary_provided", b"provided_at", [self.genesis.as_ref(), hash].concat())
+  }
   fn current_provided_key(&self) -> Vec<u8> {
     D::key(b"tributary_provided", b"current", self.genesis)
   }
@@ -60,7 +65,12 @@ impl<D: Db, T: Transaction> ProvidedTransactions<D, T> {
   }
 
   pub(crate) fn new(db: D, genesis: [u8; 32]) -> Self {
-    let mut res = ProvidedTransactions { db, genesis, transactions: HashMap::new() };
+    let mut res = ProvidedTransactions {
+      db,
+      genesis,
+      transactions: HashMap::new(),
+      provided_at: HashMap::new(),
+    };
 
     let currently_provided = res.db.get(res.current_provided_key()).unwrap_or(vec![]);
     let mut i = 0;
@@ -73,6 +83,12 @@ impl<D: Db, T: Transaction> ProvidedTransactions<D, T> {
       let TransactionKind::Provided(order) = tx.kind() else {
         panic!("provided transaction saved to disk wasn't provided");
       };
+      // Transactions provided before their block numbers were saved won't have one, and are
+      // migrated by `migrate_provided_at`
+      if let Some(provided_at) = res.db.get(res.provided_at_key(&currently_provided[i .. (i + 32)]))
+      {
+        res.provided_at.insert(tx.hash(), u64::from_le_bytes(provided_at.try_into().unwrap()));
+      }
 
       if res.transactions.get(order).is_none() {
         res.transactions.insert(order, VecDeque::new());
@@ -85,8 +101,39 @@ impl<D: Db, T: Transaction> ProvidedTransactions<D, T> {
     res
   }
 
+  /// Save the block number of every provided transaction which doesn't have one, as happens for
+  /// transactions provided before block numbers were saved.
+  ///
+  /// The block number should be the number of the current tip. Such transactions are treated as
+  /// provided as of it, as the block they were actually provided at isn't known.
+  pub(crate) fn migrate_provided_at(&mut self, block_number: u64) {
+    let missing = self
+      .transactions
+      .values()
+      .flatten()
+      .map(T::hash)
+      .filter(|hash| !self.provided_at.contains_key(hash))
+      .collect::<Vec<_>>();
+    if missing.is_empty() {
+      return;
+    }
+
+    let keys = missing.iter().map(|hash| self.provided_at_key(hash)).collect::<Vec<_>>();
+    let mut txn = self.db.txn();
+    for key in keys {
+      txn.put(key, block_number.to_le_bytes());
+    }
+    txn.commit();
+    for hash in missing {
+      self.provided_at.insert(hash, block_number);
+    }
+  }
+
   /// Provide a transaction for inclusion in a block.
-  pub(crate) fn provide(&mut self, tx: T) -> Result<(), ProvidedError> {
+  ///
+  /// The block number is the number of the current tip, used to detect if this transaction is
+  /// orphaned.
+  pub(crate) fn provide(&mut self, tx: T, block_number: u64) -> Result<(), ProvidedError> {
     let TransactionKind::Provided(order) = tx.kind() else { Err(ProvidedError::NotProvided)? };
 
     match verify_transaction(&tx, self.genesis, &mut |_, _| None) {
@@ -110,6 +157,7 @@ impl<D: Db, T: Transaction> ProvidedTransactions<D, T> {
       self.db.get(on_chain_key).map_or(0, |bytes| u32::from_le_bytes(bytes.try_into().unwrap()));
 
     let current_provided_key = self.current_provided_key();
+    let provided_at_key = self.provided_at_key(&tx_hash);
 
     // This would have a race-condition with multiple calls to provide, though this takes &mut self
     // peventing multiple calls at once
@@ -133,8 +181,11 @@ impl<D: Db, T: Transaction> ProvidedTransactions<D, T> {
       let mut currently_provided = txn.get(&current_provided_key).unwrap_or(vec![]);
       currently_provided.extend(tx_hash);
       txn.put(current_provided_key, currently_provided);
+      txn.put(provided_at_key, block_number.to_le_bytes());
       txn.commit();
 
+      self.provided_at.insert(tx_hash, block_number);
+
       if self.transactions.get(order).is_none() {
         self.transactions.insert(order, VecDeque::new());
       }
@@ -154,6 +205,8 @@ impl<D: Db, T: Transaction> ProvidedTransactions<D, T> {
   ) {
     if let Some(next_tx) = self.transactions.get_mut(order).and_then(VecDeque::pop_front) {
       assert_eq!(next_tx.hash(), tx);
+      self.provided_at.remove(&tx);
+      txn.del(self.provided_at_key(&tx));
 
       let current_provided_key = self.current_provided_key();
       let mut currently_provided = txn.get(&current_provided_key).unwrap();
@@ -188,4 +241,17 @@ impl<D: Db, T: Transaction> ProvidedTransactions<D, T> {
     txn.put(on_chain_key, on_chain_quantity.to_le_bytes());
     txn.put(block_order_key, on_chain_quantity.to_le_bytes());
   }
+
+  /// The provided transactions which have yet to be included on-chain despite being provided at
+  /// least `window` blocks ago.
+  pub(crate) fn orphaned(&self, block_number: u64, window: u64) -> Vec<T> {
+    let mut orders = self.transactions.keys().copied().collect::<Vec<_>>();
+    orders.sort_unstable();
+    orders
+      .into_iter()
+      .flat_map(|order| &self.transactions[order])
+      .filter(|tx| self.provided_at[&tx.hash()].saturating_add(window) <= block_number)
+      .cloned()
+      .collect()
+  }
 }
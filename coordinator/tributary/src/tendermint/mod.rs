@@ -1,4 +1,4 @@
-use core::ops::Deref;
+use core::{marker::PhantomData, ops::Deref, fmt};
 use std::{
   sync::Arc,
   collections::{VecDeque, HashMap},
@@ -26,6 +26,8 @@ use schnorr::{
   aggregate::{SchnorrAggregator, SchnorrAggregate},
 };
 
+use dkg::Participant;
+
 use serai_db::Db;
 
 use scale::{Encode, Decode};
@@ -43,7 +45,7 @@ use tokio::sync::RwLock;
 use crate::{
   TENDERMINT_MESSAGE, TRANSACTION_MESSAGE, BLOCK_MESSAGE, ReadWrite,
   transaction::Transaction as TransactionTrait, Transaction, BlockHeader, Block, BlockError,
-  Blockchain, P2p,
+  BlockRejection, Blockchain, P2p,
 };
 
 pub mod tx;
@@ -66,16 +68,173 @@ fn challenge(
   <Ristretto as Ciphersuite>::F::from_bytes_mod_order_wide(&transcript.challenge(b"schnorr").into())
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+// Deterministically sign a message, deriving the nonce from the genesis, key, and message
+pub(crate) fn sign(
+  genesis: [u8; 32],
+  key: &Zeroizing<<Ristretto as Ciphersuite>::F>,
+  msg: &[u8],
+) -> [u8; 64] {
+  let mut nonce = Zeroizing::new(RecommendedTranscript::new(b"Tributary Chain Tendermint Nonce"));
+  nonce.append_message(b"genesis", genesis);
+  nonce.append_message(b"key", Zeroizing::new(key.deref().to_repr()).as_ref());
+  nonce.append_message(b"message", msg);
+  let mut nonce = nonce.challenge(b"nonce");
+
+  let mut nonce_arr = [0; 64];
+  nonce_arr.copy_from_slice(nonce.as_ref());
+
+  let nonce_ref: &mut [u8] = nonce.as_mut();
+  nonce_ref.zeroize();
+  let nonce_ref: &[u8] = nonce.as_ref();
+  assert_eq!(nonce_ref, [0; 64].as_ref());
+
+  let nonce = Zeroizing::new(<Ristretto as Ciphersuite>::F::from_bytes_mod_order_wide(&nonce_arr));
+  nonce_arr.zeroize();
+
+  assert!(!bool::from(nonce.ct_eq(&<Ristretto as Ciphersuite>::F::ZERO)));
+
+  let challenge = challenge(
+    genesis,
+    (Ristretto::generator() * key.deref()).to_bytes(),
+    (Ristretto::generator() * nonce.deref()).to_bytes().as_ref(),
+    msg,
+  );
+
+  let sig = SchnorrSignature::<Ristretto>::sign(key, nonce, challenge).serialize();
+
+  let mut res = [0; 64];
+  res.copy_from_slice(&sig);
+  res
+}
+
+// Verify a signature from the specified key, which isn't checked to be a validator
+fn verify_signature(genesis: [u8; 32], key: [u8; 32], msg: &[u8], sig: &[u8]) -> bool {
+  let Ok(key_point) = Ristretto::read_G::<&[u8]>(&mut key.as_ref()) else {
+    return false;
+  };
+  let Ok(actual_sig) = SchnorrSignature::<Ristretto>::read::<&[u8]>(&mut sig.as_ref()) else {
+    return false;
+  };
+  actual_sig.verify(key_point, challenge(genesis, key, &sig[.. 32], msg))
+}
+
+/// A strategy for aggregating the signatures within a commit, defining the commit's format.
+pub trait CommitAggregation: Clone + PartialEq + Eq + fmt::Debug + Send + Sync + 'static {
+  /// Aggregate the signatures from the specified validators.
+  ///
+  /// This may panic if any signature is malformed.
+  fn aggregate(
+    genesis: [u8; 32],
+    validators: &[[u8; 32]],
+    msg: &[u8],
+    sigs: &[[u8; 64]],
+  ) -> Vec<u8>;
+
+  /// Verify an aggregate signature for the list of signers.
+  ///
+  /// The signers are presumed to have been checked to be validators.
+  fn verify_aggregate(genesis: [u8; 32], signers: &[[u8; 32]], msg: &[u8], sig: &[u8]) -> bool;
+}
+
+/// Half-aggregate the signatures within a commit.
+///
+/// This is the default strategy.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HalfAggregation;
+impl CommitAggregation for HalfAggregation {
+  fn aggregate(
+    genesis: [u8; 32],
+    validators: &[[u8; 32]],
+    msg: &[u8],
+    sigs: &[[u8; 64]],
+  ) -> Vec<u8> {
+    assert_eq!(validators.len(), sigs.len());
+
+    let mut aggregator = SchnorrAggregator::<Ristretto>::new(DST);
+    for (key, sig) in validators.iter().zip(sigs) {
+      let actual_sig = SchnorrSignature::<Ristretto>::read::<&[u8]>(&mut sig.as_ref()).unwrap();
+      let challenge = challenge(genesis, *key, actual_sig.R.to_bytes().as_ref(), msg);
+      aggregator.aggregate(challenge, actual_sig);
+    }
+
+    let aggregate = aggregator.complete().unwrap();
+    aggregate.serialize()
+  }
+
+  fn verify_aggregate(genesis: [u8; 32], signers: &[[u8; 32]], msg: &[u8], sig: &[u8]) -> bool {
+    let Ok(aggregate) = SchnorrAggregate::<Ristretto>::read::<&[u8]>(&mut sig.as_ref()) else {
+      return false;
+    };
+
+    if signers.len() != aggregate.Rs().len() {
+      return false;
+    }
+
+    let mut keys_and_challenges = vec![];
+    for (key, nonce) in signers.iter().zip(aggregate.Rs()) {
+      let Ok(key_point) = Ristretto::read_G::<&[u8]>(&mut key.as_ref()) else {
+        return false;
+      };
+      keys_and_challenges
+        .push((key_point, challenge(genesis, *key, nonce.to_bytes().as_ref(), msg)));
+    }
+
+    aggregate.verify(DST, keys_and_challenges.as_slice())
+  }
+}
+
+/// Concatenate the signatures within a commit, without aggregating them.
+///
+/// Commits are larger than with half-aggregation, yet their signatures can be individually
+/// inspected, which may be preferred when debugging or cross-checking implementations.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ConcatenatedSignatures;
+impl CommitAggregation for ConcatenatedSignatures {
+  fn aggregate(
+    _genesis: [u8; 32],
+    validators: &[[u8; 32]],
+    _msg: &[u8],
+    sigs: &[[u8; 64]],
+  ) -> Vec<u8> {
+    assert_eq!(validators.len(), sigs.len());
+    sigs.concat()
+  }
+
+  fn verify_aggregate(genesis: [u8; 32], signers: &[[u8; 32]], msg: &[u8], sig: &[u8]) -> bool {
+    (sig.len() == (signers.len() * 64)) &&
+      signers
+        .iter()
+        .zip(sig.chunks(64))
+        .all(|(key, sig)| verify_signature(genesis, *key, msg, sig))
+  }
+}
+
+#[derive(Clone, PartialEq, Eq)]
 pub struct Signer {
   genesis: [u8; 32],
   key: Zeroizing<<Ristretto as Ciphersuite>::F>,
 }
 
+// Manually implemented to not leak the key, solely identifying the signer by its public key
+impl fmt::Debug for Signer {
+  fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt
+      .debug_struct("Signer")
+      .field("genesis", &hex::encode(self.genesis))
+      .field("validator", &hex::encode((Ristretto::generator() * self.key.deref()).to_bytes()))
+      .finish_non_exhaustive()
+  }
+}
+
 impl Signer {
   pub(crate) fn new(genesis: [u8; 32], key: Zeroizing<<Ristretto as Ciphersuite>::F>) -> Signer {
     Signer { genesis, key }
   }
+
+  /// The genesis of the Tributary this signer signs for.
+  pub fn genesis(&self) -> [u8; 32] {
+    self.genesis
+  }
 }
 
 #[async_trait]
@@ -90,78 +249,186 @@ impl SignerTrait for Signer {
 
   /// Sign a signature with the current validator's private key.
   async fn sign(&self, msg: &[u8]) -> Self::Signature {
-    let mut nonce = Zeroizing::new(RecommendedTranscript::new(b"Tributary Chain Tendermint Nonce"));
-    nonce.append_message(b"genesis", self.genesis);
-    nonce.append_message(b"key", Zeroizing::new(self.key.deref().to_repr()).as_ref());
-    nonce.append_message(b"message", msg);
-    let mut nonce = nonce.challenge(b"nonce");
-
-    let mut nonce_arr = [0; 64];
-    nonce_arr.copy_from_slice(nonce.as_ref());
-
-    let nonce_ref: &mut [u8] = nonce.as_mut();
-    nonce_ref.zeroize();
-    let nonce_ref: &[u8] = nonce.as_ref();
-    assert_eq!(nonce_ref, [0; 64].as_ref());
-
-    let nonce =
-      Zeroizing::new(<Ristretto as Ciphersuite>::F::from_bytes_mod_order_wide(&nonce_arr));
-    nonce_arr.zeroize();
-
-    assert!(!bool::from(nonce.ct_eq(&<Ristretto as Ciphersuite>::F::ZERO)));
-
-    let challenge = challenge(
-      self.genesis,
-      (Ristretto::generator() * self.key.deref()).to_bytes(),
-      (Ristretto::generator() * nonce.deref()).to_bytes().as_ref(),
-      msg,
-    );
-
-    let sig = SchnorrSignature::<Ristretto>::sign(&self.key, nonce, challenge).serialize();
-
-    let mut res = [0; 64];
-    res.copy_from_slice(&sig);
-    res
+    sign(self.genesis, &self.key, msg)
   }
 }
 
+/// The validators of a Tributary, aggregating the signatures within commits with the specified
+/// strategy.
 #[derive(Clone, PartialEq, Eq, Debug)]
-pub struct Validators {
+pub struct Validators<A: CommitAggregation = HalfAggregation> {
   genesis: [u8; 32],
   total_weight: u64,
   weights: HashMap<[u8; 32], u64>,
+  // The validators in the order they were listed, which the robin is derived from
+  order: Vec<[u8; 32]>,
   robin: Vec<[u8; 32]>,
+  aggregation: PhantomData<A>,
 }
 
 impl Validators {
+  /// Create the validators for a Tributary.
+  ///
+  /// Returns None if any validator has a weight of 0 or is listed multiple times.
   pub(crate) fn new(
     genesis: [u8; 32],
     validators: Vec<(<Ristretto as Ciphersuite>::G, u64)>,
   ) -> Option<Validators> {
+    Validators::with_aggregation(genesis, validators)
+  }
+
+  /// Create the validators for a Tributary from the result of a DKG.
+  ///
+  /// Each key share is the participant, the Ristretto key they'll validate with, and their weight.
+  /// The validators are ordered by their participant index, so the result is independent of the
+  /// order the key shares are listed in.
+  ///
+  /// Returns None if any participant is listed multiple times, any key is invalid, or if `new`
+  /// would.
+  pub fn from_dkg(
+    genesis: [u8; 32],
+    key_shares: &[(Participant, [u8; 32], u64)],
+  ) -> Option<Validators> {
+    let mut key_shares = key_shares.to_vec();
+    key_shares.sort_by_key(|(participant, _, _)| *participant);
+    if key_shares.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+      return None;
+    }
+
+    let mut validators = vec![];
+    for (_, key, weight) in key_shares {
+      validators.push((Ristretto::read_G::<&[u8]>(&mut key.as_ref()).ok()?, weight));
+    }
+    Validators::new(genesis, validators)
+  }
+}
+
+// The round robin for proposer selection, with each validator appearing as many times as their
+// weight, deterministically shuffled by the validators and their weights
+fn round_robin(validators: &[([u8; 32], u64)]) -> Vec<[u8; 32]> {
+  let mut transcript = RecommendedTranscript::new(b"Round Robin Randomization");
+  let mut robin = vec![];
+  for (validator, weight) in validators {
+    transcript.append_message(b"validator", validator);
+    transcript.append_message(b"weight", weight.to_le_bytes());
+    robin.extend(vec![*validator; usize::try_from(*weight).unwrap()]);
+  }
+  robin.shuffle(&mut ChaCha12Rng::from_seed(transcript.rng_seed(b"robin")));
+  robin
+}
+
+impl<A: CommitAggregation> Validators<A> {
+  /// Create the validators for a Tributary, aggregating the signatures within commits with the
+  /// specified strategy.
+  ///
+  /// Returns None if any validator has a weight of 0, is listed multiple times, or if the
+  /// resulting round robin isn't canonical.
+  pub fn with_aggregation(
+    genesis: [u8; 32],
+    validators: Vec<(<Ristretto as Ciphersuite>::G, u64)>,
+  ) -> Option<Self> {
     let mut total_weight = 0;
     let mut weights = HashMap::new();
-
-    let mut transcript = RecommendedTranscript::new(b"Round Robin Randomization");
-    let mut robin = vec![];
+    let mut listed = vec![];
     for (validator, weight) in validators {
       let validator = validator.to_bytes();
       if weight == 0 {
         return None;
       }
       total_weight += weight;
-      weights.insert(validator, weight);
+      if weights.insert(validator, weight).is_some() {
+        return None;
+      }
+      listed.push((validator, weight));
+    }
+
+    let robin = round_robin(&listed);
+    let order = listed.into_iter().map(|(validator, _)| validator).collect();
+    let validators =
+      Validators { genesis, total_weight, weights, order, robin, aggregation: PhantomData };
+    if !validators.is_canonical() {
+      return None;
+    }
+    Some(validators)
+  }
+
+  /// The genesis of the Tributary these are the validators of.
+  pub fn genesis(&self) -> [u8; 32] {
+    self.genesis
+  }
+
+  /// Whether the round robin used for proposer selection is consistent with the weights.
+  ///
+  /// This checks every validator appears in the robin as many times as their weight, with no other
+  /// entries, the total weight is the sum of the weights, and the robin is in the deterministic
+  /// order derived from the validators as listed. If this doesn't hold, nodes may disagree on who
+  /// the proposer is.
+  pub fn is_canonical(&self) -> bool {
+    let Some(listed) = self
+      .order
+      .iter()
+      .map(|validator| Some((*validator, *self.weights.get(validator)?)))
+      .collect::<Option<Vec<_>>>()
+    else {
+      return false;
+    };
 
-      transcript.append_message(b"validator", validator);
-      transcript.append_message(b"weight", weight.to_le_bytes());
-      robin.extend(vec![validator; usize::try_from(weight).unwrap()]);
+    let mut appearances = HashMap::new();
+    for validator in &self.robin {
+      *appearances.entry(*validator).or_insert(0u64) += 1;
     }
-    robin.shuffle(&mut ChaCha12Rng::from_seed(transcript.rng_seed(b"robin")));
+    (self.weights.values().sum::<u64>() == self.total_weight) &&
+      (u64::try_from(self.robin.len()).unwrap() == self.total_weight) &&
+      (appearances == self.weights) &&
+      (self.robin == round_robin(&listed))
+  }
 
-    Some(Validators { genesis, total_weight, weights, robin })
+  #[cfg(test)]
+  pub(crate) fn robin_mut(&mut self) -> &mut Vec<[u8; 32]> {
+    &mut self.robin
+  }
+
+  /// The distinct validators who signed a commit, in the order they're listed, and their combined
+  /// weight.
+  ///
+  /// Returns None if any signer isn't a validator. This doesn't verify the commit's signature.
+  pub fn commit_signers<S: SignatureScheme<ValidatorId = [u8; 32]>>(
+    &self,
+    commit: &Commit<S>,
+  ) -> Option<(Vec<<Ristretto as Ciphersuite>::G>, u64)> {
+    let mut signers = vec![];
+    let mut weight = 0;
+    for validator in &commit.validators {
+      let validator_weight = self.weights.get(validator)?;
+      let signer = Ristretto::read_G::<&[u8]>(&mut validator.as_ref()).ok()?;
+      if signers.contains(&signer) {
+        continue;
+      }
+      signers.push(signer);
+      weight += validator_weight;
+    }
+    Some((signers, weight))
+  }
+
+  /// The validators who didn't sign a commit, sorted by their keys.
+  ///
+  /// Signers who aren't validators are ignored. This doesn't verify the commit's signature.
+  pub fn absent_signers<S: SignatureScheme<ValidatorId = [u8; 32]>>(
+    &self,
+    commit: &Commit<S>,
+  ) -> Vec<[u8; 32]> {
+    let mut absent = self
+      .weights
+      .keys()
+      .filter(|validator| !commit.validators.contains(validator))
+      .copied()
+      .collect::<Vec<_>>();
+    absent.sort_unstable();
+    absent
   }
 }
 
-impl SignatureScheme for Validators {
+impl<A: CommitAggregation> SignatureScheme for Validators<A> {
   type ValidatorId = [u8; 32];
   type Signature = [u8; 64];
   type AggregateSignature = Vec<u8>;
@@ -172,13 +439,7 @@ impl SignatureScheme for Validators {
     if !self.weights.contains_key(&validator) {
       return false;
     }
-    let Ok(validator_point) = Ristretto::read_G::<&[u8]>(&mut validator.as_ref()) else {
-      return false;
-    };
-    let Ok(actual_sig) = SchnorrSignature::<Ristretto>::read::<&[u8]>(&mut sig.as_ref()) else {
-      return false;
-    };
-    actual_sig.verify(validator_point, challenge(self.genesis, validator, &sig[.. 32], msg))
+    verify_signature(self.genesis, validator, msg, sig)
   }
 
   fn aggregate(
@@ -187,17 +448,7 @@ impl SignatureScheme for Validators {
     msg: &[u8],
     sigs: &[Self::Signature],
   ) -> Self::AggregateSignature {
-    assert_eq!(validators.len(), sigs.len());
-
-    let mut aggregator = SchnorrAggregator::<Ristretto>::new(DST);
-    for (key, sig) in validators.iter().zip(sigs) {
-      let actual_sig = SchnorrSignature::<Ristretto>::read::<&[u8]>(&mut sig.as_ref()).unwrap();
-      let challenge = challenge(self.genesis, *key, actual_sig.R.to_bytes().as_ref(), msg);
-      aggregator.aggregate(challenge, actual_sig);
-    }
-
-    let aggregate = aggregator.complete().unwrap();
-    aggregate.serialize()
+    A::aggregate(self.genesis, validators, msg, sigs)
   }
 
   #[must_use]
@@ -207,32 +458,14 @@ impl SignatureScheme for Validators {
     msg: &[u8],
     sig: &Self::AggregateSignature,
   ) -> bool {
-    let Ok(aggregate) = SchnorrAggregate::<Ristretto>::read::<&[u8]>(&mut sig.as_slice()) else {
+    if signers.iter().any(|signer| !self.weights.contains_key(signer)) {
       return false;
-    };
-
-    if signers.len() != aggregate.Rs().len() {
-      return false;
-    }
-
-    let mut challenges = vec![];
-    for (key, nonce) in signers.iter().zip(aggregate.Rs()) {
-      challenges.push(challenge(self.genesis, *key, nonce.to_bytes().as_ref(), msg));
     }
-
-    aggregate.verify(
-      DST,
-      signers
-        .iter()
-        .zip(challenges)
-        .map(|(s, c)| (<Ristretto as Ciphersuite>::read_G(&mut s.as_slice()).unwrap(), c))
-        .collect::<Vec<_>>()
-        .as_slice(),
-    )
+    A::verify_aggregate(self.genesis, signers, msg, sig)
   }
 }
 
-impl Weights for Validators {
+impl<A: CommitAggregation> Weights for Validators<A> {
   type ValidatorId = [u8; 32];
 
   fn total_weight(&self) -> u64 {
@@ -242,15 +475,21 @@ impl Weights for Validators {
     self.weights[&validator]
   }
   fn proposer(&self, block: BlockNumber, round: RoundNumber) -> Self::ValidatorId {
-    let block = usize::try_from(block.0).unwrap();
-    let round = usize::try_from(round.0).unwrap();
-    // If multiple rounds are used, a naive block + round would cause the same index to be chosen
-    // in quick succession.
-    // Accordingly, if we use additional rounds, jump halfway around.
-    // While this is still game-able, it's not explicitly reusing indexes immediately after each
-    // other.
-    self.robin
-      [(block + (if round == 0 { 0 } else { round + (self.robin.len() / 2) })) % self.robin.len()]
+    // Select the proposer by hashing the genesis, block, and round, instead of indexing
+    // arithmetically
+    // With arithmetic indexing, a validator can predict which rounds they'll propose in, and may
+    // attempt to force the protocol into those rounds
+    // While this is still predictable, as it has no secret inputs, it doesn't offer any structure
+    // to exploit and all honest nodes will agree on it
+    let mut transcript = RecommendedTranscript::new(b"Tributary Chain Proposer");
+    transcript.append_message(b"genesis", self.genesis);
+    transcript.append_message(b"block", block.0.to_le_bytes());
+    transcript.append_message(b"round", round.0.to_le_bytes());
+    let index = u64::from_le_bytes(transcript.challenge(b"proposer")[.. 8].try_into().unwrap());
+    // The robin has a length equal to the total weight, a u64, so this is lossless
+    // The bias from reducing the index is negligible as the total weight is much smaller than
+    // 2**64
+    self.robin[usize::try_from(index % self.total_weight).unwrap()]
   }
 }
 
@@ -272,10 +511,47 @@ pub struct TendermintNetwork<D: Db, T: TransactionTrait, P: P2p> {
   pub(crate) blockchain: Arc<RwLock<Blockchain<D, T>>>,
 
   pub(crate) to_rebroadcast: Arc<RwLock<VecDeque<Vec<u8>>>>,
+  // Why the latest block rejected by validate was rejected, for diagnostics
+  pub(crate) last_rejection: Arc<RwLock<Option<BlockRejection>>>,
 
   pub(crate) p2p: P,
 }
 
+impl<D: Db, T: TransactionTrait, P: P2p> TendermintNetwork<D, T, P> {
+  /// Create the network for a Tributary.
+  ///
+  /// Panics if the signer or validators are for a different genesis, as signatures would then
+  /// fail to verify.
+  pub(crate) fn new(
+    genesis: [u8; 32],
+    signer: Arc<Signer>,
+    validators: Arc<Validators>,
+    blockchain: Arc<RwLock<Blockchain<D, T>>>,
+    p2p: P,
+  ) -> Self {
+    assert_eq!(signer.genesis(), genesis, "signer was for a different genesis than the network");
+    assert_eq!(
+      validators.genesis(),
+      genesis,
+      "validators were for a different genesis than the network"
+    );
+    TendermintNetwork {
+      genesis,
+      signer,
+      validators,
+      blockchain,
+      to_rebroadcast: Arc::new(RwLock::new(VecDeque::new())),
+      last_rejection: Arc::new(RwLock::new(None)),
+      p2p,
+    }
+  }
+
+  /// The genesis of the Tributary this is the network for.
+  pub fn genesis(&self) -> [u8; 32] {
+    self.genesis
+  }
+}
+
 pub const BLOCK_PROCESSING_TIME: u32 = 999;
 pub const LATENCY_TIME: u32 = 1667;
 pub const TARGET_BLOCK_TIME: u32 = BLOCK_PROCESSING_TIME + (3 * LATENCY_TIME);
@@ -333,7 +609,14 @@ impl<D: Db, T: TransactionTrait, P: P2p> Network for TendermintNetwork<D, T, P>
   }
 
   async fn slash(&mut self, validator: Self::ValidatorId, slash_event: SlashEvent) {
+    let block_number = match slash_event {
+      SlashEvent::Id(_, block, _) => block,
+      SlashEvent::WithEvidence(_) => self.blockchain.read().await.block_number() + 1,
+    };
     log::error!(
+      genesis = hex::encode(self.genesis).as_str(),
+      block_number = block_number,
+      validator = hex::encode(validator).as_str();
       "validator {} triggered a slash event on tributary {} (with evidence: {})",
       hex::encode(validator),
       hex::encode(self.genesis),
@@ -368,20 +651,47 @@ impl<D: Db, T: TransactionTrait, P: P2p> Network for TendermintNetwork<D, T, P>
   }
 
   async fn validate(&mut self, block: &Self::Block) -> Result<(), TendermintBlockError> {
-    let block =
-      Block::read::<&[u8]>(&mut block.0.as_ref()).map_err(|_| TendermintBlockError::Fatal)?;
-    self
-      .blockchain
-      .read()
-      .await
-      .verify_block::<Self>(&block, &self.signature_scheme(), false)
-      .map_err(|e| match e {
-        BlockError::NonLocalProvided(_) => TendermintBlockError::Temporal,
-        _ => {
-          log::warn!("Tributary Tendermint validate returning BlockError::Fatal due to {e:?}");
-          TendermintBlockError::Fatal
+    let rejection = {
+      let blockchain = self.blockchain.read().await;
+      // Check the size before decoding so oversized blocks are rejected as cheaply as possible
+      if block.0.len() > blockchain.block_size_limit() {
+        BlockRejection::Invalid(BlockError::TooLargeBlock)
+      } else {
+        match Block::read::<&[u8]>(&mut block.0.as_ref()) {
+          Ok(block) => {
+            match blockchain.verify_block::<Self>(&block, &self.signature_scheme(), false) {
+              Ok(()) => return Ok(()),
+              Err(e) => BlockRejection::Invalid(e),
+            }
+          }
+          Err(e) => BlockRejection::Undecodable(e.to_string()),
         }
-      })
+      }
+    };
+
+    let block_number = self.blockchain.read().await.block_number() + 1;
+    let res = match rejection {
+      BlockRejection::Invalid(BlockError::NonLocalProvided(_)) => {
+        log::debug!(
+          genesis = hex::encode(self.genesis).as_str(),
+          block_number = block_number;
+          "Tributary Tendermint validate returning BlockError::Temporal due to {rejection}",
+        );
+        TendermintBlockError::Temporal
+      }
+      _ => {
+        log::warn!(
+          genesis = hex::encode(self.genesis).as_str(),
+          block_number = block_number;
+          "Tributary Tendermint validate returning BlockError::Fatal for a {} byte block due to {}",
+          block.0.len(),
+          rejection,
+        );
+        TendermintBlockError::Fatal
+      }
+    };
+    *self.last_rejection.write().await = Some(rejection);
+    Err(res)
   }
 
   async fn add_block(
@@ -389,12 +699,20 @@ impl<D: Db, T: TransactionTrait, P: P2p> Network for TendermintNetwork<D, T, P>
     serialized_block: Self::Block,
     commit: Commit<Self::SignatureScheme>,
   ) -> Option<Self::Block> {
+    let block_number = self.blockchain.read().await.block_number() + 1;
     let invalid_block = || {
       // There's a fatal flaw in the code, it's behind a hard fork, or the validators turned
       // malicious
       // All justify a halt to then achieve social consensus from
       // TODO: Under multiple validator sets, a small validator set turning malicious knocks
       // off the entire network. That's an unacceptable DoS.
+      log::error!(
+        genesis = hex::encode(self.genesis).as_str(),
+        block_number = block_number;
+        "validators added invalid block {} to tributary {}",
+        block_number,
+        hex::encode(self.genesis),
+      );
       panic!("validators added invalid block to tributary {}", hex::encode(self.genesis));
     };
 
@@ -424,6 +742,9 @@ impl<D: Db, T: TransactionTrait, P: P2p> Network for TendermintNetwork<D, T, P>
         }
         Err(BlockError::NonLocalProvided(hash)) => {
           log::error!(
+            genesis = hex::encode(self.genesis).as_str(),
+            block_number = block_number,
+            transaction = hex::encode(hash).as_str();
             "missing provided transaction {} which other validators on tributary {} had",
             hex::encode(hash),
             hex::encode(self.genesis)
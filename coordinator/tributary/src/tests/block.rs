@@ -11,7 +11,7 @@ use serai_db::MemDb;
 use tendermint::ext::Commit;
 
 use crate::{
-  ReadWrite, BlockError, Block, Transaction,
+  BLOCK_SIZE_LIMIT, ReadWrite, BlockError, Block, Transaction,
   tests::p2p::DummyP2p,
   transaction::{TransactionError, Signed, TransactionKind, Transaction as TransactionTrait},
   tendermint::{TendermintNetwork, Validators},
@@ -82,7 +82,7 @@ fn empty_block() {
     Some(Commit::<Arc<Validators>> { end_time: 0, validators: vec![], signature: vec![] })
   };
   let provided_or_unsigned_in_chain = |_: [u8; 32]| false;
-  Block::<NonceTransaction>::new(LAST, vec![], vec![])
+  Block::<NonceTransaction>::new(LAST, vec![], vec![], BLOCK_SIZE_LIMIT)
     .verify::<N, _>(
       GENESIS,
       LAST,
@@ -92,6 +92,7 @@ fn empty_block() {
       commit,
       provided_or_unsigned_in_chain,
       false,
+      BLOCK_SIZE_LIMIT,
     )
     .unwrap();
 }
@@ -117,7 +118,7 @@ fn duplicate_nonces() {
     let provided_or_unsigned_in_chain = |_: [u8; 32]| false;
 
     let mut last_nonce = 0;
-    let res = Block::new(LAST, vec![], mempool).verify::<N, _>(
+    let res = Block::new(LAST, vec![], mempool, BLOCK_SIZE_LIMIT).verify::<N, _>(
       GENESIS,
       LAST,
       HashMap::new(),
@@ -130,6 +131,7 @@ fn duplicate_nonces() {
       commit,
       provided_or_unsigned_in_chain,
       false,
+      BLOCK_SIZE_LIMIT,
     );
     if i == 1 {
       res.unwrap();
@@ -138,3 +140,39 @@ fn duplicate_nonces() {
     }
   }
 }
+
+#[test]
+fn block_error_display() {
+  assert_eq!(
+    BlockError::NonLocalProvided([0xab; 32]).to_string(),
+    format!("block had a provided transaction not yet locally provided: {}", "ab".repeat(32)),
+  );
+  assert_eq!(
+    BlockError::TransactionError(TransactionError::InvalidNonce).to_string(),
+    "included transaction had an error: invalid nonce",
+  );
+
+  for (error, context) in [
+    (BlockError::TooLargeBlock, "size limit"),
+    (BlockError::InvalidParent, "chain tip"),
+    (BlockError::InvalidTransactions, "transactions hash"),
+    (BlockError::UnsignedAlreadyIncluded, "unsigned transaction"),
+    (BlockError::ProvidedAlreadyIncluded, "provided transaction"),
+    (BlockError::WrongTransactionOrder, "ordered"),
+    (BlockError::DistinctProvided, "distinct provided transaction"),
+  ] {
+    assert!(error.to_string().contains(context));
+  }
+
+  for (error, context) in [
+    (TransactionError::TooLargeTransaction, "too large"),
+    (TransactionError::InvalidSigner, "signer"),
+    (TransactionError::InvalidNonce, "nonce"),
+    (TransactionError::InvalidSignature([0; 32]), "signature"),
+    (TransactionError::InvalidContent, "content"),
+    (TransactionError::TooManyInMempool, "mempool"),
+    (TransactionError::ProvidedAddedToMempool, "provided transaction"),
+  ] {
+    assert!(error.to_string().contains(context));
+  }
+}
@@ -14,11 +14,13 @@ use ciphersuite::{group::ff::Field, Ciphersuite, Ristretto};
 
 use serai_db::{DbTxn, Db, MemDb};
 
+use tendermint::ext::{BlockError as TendermintBlockError, Network};
+
 use crate::{
-  ReadWrite, TransactionKind,
+  BLOCK_SIZE_LIMIT, ReadWrite, TransactionKind,
   transaction::Transaction as TransactionTrait,
-  TransactionError, Transaction, ProvidedError, ProvidedTransactions, merkle, BlockError, Block,
-  Blockchain,
+  TransactionError, Transaction, ProvidedError, ProvidedTransactions, merkle, BlockError,
+  BlockRejection, Block, Blockchain,
   tendermint::{TendermintNetwork, Validators, Signer, TendermintBlock},
   tests::{
     ProvidedTransaction, SignedTransaction, random_provided_transaction, p2p::DummyP2p,
@@ -33,7 +35,7 @@ fn new_blockchain<T: TransactionTrait>(
   participants: &[<Ristretto as Ciphersuite>::G],
 ) -> (MemDb, Blockchain<MemDb, T>) {
   let db = MemDb::new();
-  let blockchain = Blockchain::new(db.clone(), genesis, participants);
+  let blockchain = Blockchain::new(db.clone(), genesis, participants, BLOCK_SIZE_LIMIT);
   assert_eq!(blockchain.tip(), genesis);
   assert_eq!(blockchain.block_number(), 0);
   (db, blockchain)
@@ -58,6 +60,145 @@ fn block_addition() {
   );
 }
 
+#[test]
+fn validate_block() {
+  let genesis = new_genesis();
+  let validators = Arc::new(Validators::new(genesis, vec![]).unwrap());
+  let key = Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng));
+  let tx = crate::tests::signed_transaction(&mut OsRng, genesis, &key, 0);
+  let signer = tx.1.signer;
+  let (db, mut blockchain) = new_blockchain::<SignedTransaction>(genesis, &[signer]);
+
+  let block =
+    Block::new(blockchain.tip(), vec![], vec![Transaction::Application(tx)], BLOCK_SIZE_LIMIT);
+  let unchanged = |blockchain: &Blockchain<MemDb, SignedTransaction>| {
+    assert_eq!(blockchain.tip(), genesis);
+    assert_eq!(blockchain.block_number(), 0);
+    assert_eq!(Blockchain::<MemDb, SignedTransaction>::tip_from_db(&db, genesis), genesis);
+    assert_eq!(Blockchain::<MemDb, SignedTransaction>::block_number_from_db(&db, genesis), 0);
+    assert_eq!(blockchain.next_nonce(&signer, &[]), Some(0));
+    assert!(
+      Blockchain::<MemDb, SignedTransaction>::block_from_db(&db, genesis, &block.hash()).is_none()
+    );
+  };
+
+  // Validating doesn't consume the transaction's nonce, so it can be repeated
+  for _ in 0 .. 2 {
+    blockchain.validate_block::<N>(&block, &validators).unwrap();
+    unchanged(&blockchain);
+  }
+
+  // An invalid block also doesn't mutate the blockchain
+  let invalid = Block::new([0xff; 32], vec![], vec![], BLOCK_SIZE_LIMIT);
+  assert!(blockchain.validate_block::<N>(&invalid, &validators).is_err());
+  unchanged(&blockchain);
+
+  // The validated block can still be added
+  blockchain.add_block::<N>(&block, vec![], &validators).unwrap();
+  assert_eq!(blockchain.tip(), block.hash());
+  assert_eq!(blockchain.next_nonce(&signer, &[]), Some(1));
+}
+
+#[test]
+fn tip_from_db() {
+  let genesis = new_genesis();
+  let validators = Arc::new(Validators::new(genesis, vec![]).unwrap());
+  let (db, mut blockchain) = new_blockchain::<SignedTransaction>(genesis, &[]);
+  assert_eq!(Blockchain::<MemDb, SignedTransaction>::tip_from_db(&db, genesis), genesis);
+  assert_eq!(Blockchain::<MemDb, SignedTransaction>::block_number_from_db(&db, genesis), 0);
+
+  for i in 1 ..= 3 {
+    let block = blockchain.build_block::<N>(&validators);
+    blockchain.add_block::<N>(&block, vec![], &validators).unwrap();
+    assert_eq!(Blockchain::<MemDb, SignedTransaction>::tip_from_db(&db, genesis), block.hash());
+    assert_eq!(Blockchain::<MemDb, SignedTransaction>::block_number_from_db(&db, genesis), i);
+  }
+
+  // A blockchain reloaded from the DB should have the same tip
+  let reloaded = Blockchain::<MemDb, SignedTransaction>::new(db, genesis, &[], BLOCK_SIZE_LIMIT);
+  assert_eq!(reloaded.tip(), blockchain.tip());
+  assert_eq!(reloaded.block_number(), 3);
+}
+
+#[test]
+fn blocks_after() {
+  let genesis = new_genesis();
+  let validators = Arc::new(Validators::new(genesis, vec![]).unwrap());
+  let (db, mut blockchain) = new_blockchain::<SignedTransaction>(genesis, &[]);
+
+  let mut blocks = vec![];
+  for _ in 0 .. 3 {
+    let block = blockchain.build_block::<N>(&validators);
+    blockchain.add_block::<N>(&block, vec![], &validators).unwrap();
+    blocks.push(block);
+  }
+
+  let blocks_after = |hash| {
+    Blockchain::<MemDb, SignedTransaction>::blocks_after(&db, genesis, &hash).collect::<Vec<_>>()
+  };
+  assert_eq!(blocks_after(genesis), blocks);
+  assert_eq!(blocks_after(blocks[0].hash()), blocks[1 ..]);
+  assert!(blocks_after(blocks[2].hash()).is_empty());
+  assert!(blocks_after([0xff; 32]).is_empty());
+}
+
+#[test]
+fn prune_below() {
+  let genesis = new_genesis();
+  let validators = Arc::new(Validators::new(genesis, vec![]).unwrap());
+  let (db, mut blockchain) = new_blockchain::<SignedTransaction>(genesis, &[]);
+
+  let mut blocks = vec![];
+  for i in 0 .. 20 {
+    let block = blockchain.build_block::<N>(&validators);
+    blockchain.add_block::<N>(&block, vec![i], &validators).unwrap();
+    blocks.push(block);
+  }
+
+  let block_from_db =
+    |hash| Blockchain::<MemDb, SignedTransaction>::block_from_db(&db, genesis, &hash);
+  let pruned_below = || Blockchain::<MemDb, SignedTransaction>::pruned_below_from_db(&db, genesis);
+  assert_eq!(pruned_below(), 1);
+
+  // Blocks are numbered from 1, so blocks[i] is block number i + 1
+  blockchain.prune_below(16);
+  assert_eq!(pruned_below(), 16);
+  for (i, block) in blocks.iter().enumerate() {
+    let number = u64::try_from(i).unwrap() + 1;
+    // Hashes and commits are retained for every block
+    assert_eq!(blockchain.block_hash(number), Some(block.hash()));
+    assert_eq!(blockchain.commit(&block.hash()), Some(vec![u8::try_from(i).unwrap()]));
+    if number < 16 {
+      assert!(block_from_db(block.hash()).is_none());
+    } else {
+      assert_eq!(block_from_db(block.hash()).as_ref(), Some(block));
+    }
+  }
+  assert_eq!(blockchain.tip(), blocks[19].hash());
+  assert_eq!(blockchain.block_number(), 20);
+
+  // Recent blocks can still be iterated over to serve a syncing peer
+  assert_eq!(
+    Blockchain::<MemDb, SignedTransaction>::blocks_after(&db, genesis, &blocks[14].hash())
+      .collect::<Vec<_>>(),
+    blocks[15 ..]
+  );
+
+  // Pruning below an already pruned height is a no-op
+  blockchain.prune_below(10);
+  assert_eq!(pruned_below(), 16);
+
+  // The tip is never pruned
+  blockchain.prune_below(u64::MAX);
+  assert_eq!(pruned_below(), 20);
+  assert_eq!(block_from_db(blocks[19].hash()).as_ref(), Some(&blocks[19]));
+
+  // The chain can continue to be extended
+  let block = blockchain.build_block::<N>(&validators);
+  blockchain.add_block::<N>(&block, vec![], &validators).unwrap();
+  assert_eq!(blockchain.block_number(), 21);
+}
+
 #[test]
 fn invalid_block() {
   let genesis = new_genesis();
@@ -87,7 +228,12 @@ fn invalid_block() {
   // Not a participant
   {
     // Manually create the block to bypass build_block's checks
-    let block = Block::new(blockchain.tip(), vec![], vec![Transaction::Application(tx.clone())]);
+    let block = Block::new(
+      blockchain.tip(),
+      vec![],
+      vec![Transaction::Application(tx.clone())],
+      BLOCK_SIZE_LIMIT,
+    );
     assert_eq!(block.header.transactions, merkle(&[tx.hash()]));
     assert!(blockchain.verify_block::<N>(&block, &validators, false).is_err());
   }
@@ -97,7 +243,12 @@ fn invalid_block() {
 
   // Re-run the not a participant block to make sure it now works
   {
-    let block = Block::new(blockchain.tip(), vec![], vec![Transaction::Application(tx.clone())]);
+    let block = Block::new(
+      blockchain.tip(),
+      vec![],
+      vec![Transaction::Application(tx.clone())],
+      BLOCK_SIZE_LIMIT,
+    );
     assert_eq!(block.header.transactions, merkle(&[tx.hash()]));
     blockchain.verify_block::<N>(&block, &validators, false).unwrap();
   }
@@ -121,7 +272,8 @@ fn invalid_block() {
     // Invalid nonce
     let tx = crate::tests::signed_transaction(&mut OsRng, genesis, &key, 5);
     // Manually create the block to bypass build_block's checks
-    let block = Block::new(blockchain.tip(), vec![], vec![Transaction::Application(tx)]);
+    let block =
+      Block::new(blockchain.tip(), vec![], vec![Transaction::Application(tx)], BLOCK_SIZE_LIMIT);
     assert!(blockchain.verify_block::<N>(&block, &validators, false).is_err());
   }
 
@@ -168,7 +320,7 @@ fn signed_transaction() {
       assert_eq!(next_nonce + 1, blockchain.next_nonce(&signer, &[]).unwrap());
     }
     let block = blockchain.build_block::<N>(&validators);
-    assert_eq!(block, Block::new(blockchain.tip(), vec![], mempool.clone()));
+    assert_eq!(block, Block::new(blockchain.tip(), vec![], mempool.clone(), BLOCK_SIZE_LIMIT));
     assert_eq!(blockchain.tip(), tip);
     assert_eq!(block.header.parent, tip);
 
@@ -201,6 +353,79 @@ fn signed_transaction() {
   assert_eq!(blockchain.next_nonce(&signer, &[]), Some(64));
 }
 
+#[tokio::test]
+async fn block_size_limit() {
+  let genesis = new_genesis();
+  let key = Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng));
+  let signer_id = Ristretto::generator() * key.deref();
+  let validators = Arc::new(Validators::new(genesis, vec![(signer_id, 1)]).unwrap());
+
+  let mempool = (0 .. 3)
+    .map(|nonce| {
+      Transaction::Application(crate::tests::signed_transaction(&mut OsRng, genesis, &key, nonce))
+    })
+    .collect::<Vec<_>>();
+  let oversized = Block::new(genesis, vec![], mempool.clone(), BLOCK_SIZE_LIMIT);
+  assert_eq!(oversized.transactions.len(), 3);
+
+  // Set a limit which only allows two of the transactions
+  let limit = oversized.serialize().len() - 1;
+  let blockchain = Blockchain::new(MemDb::new(), genesis, &[signer_id], limit);
+  let blockchain = Arc::new(tokio::sync::RwLock::new(blockchain));
+  for tx in mempool {
+    blockchain.write().await.add_transaction::<N>(true, tx, &validators).unwrap();
+  }
+
+  // We should never propose a block over the limit
+  let block = blockchain.write().await.build_block::<N>(&validators);
+  assert_eq!(block.transactions.len(), 2);
+  assert!(block.serialize().len() <= limit);
+
+  assert_eq!(
+    blockchain.read().await.verify_block::<N>(&oversized, &validators, false),
+    Err(BlockError::TooLargeBlock)
+  );
+
+  let mut network =
+    N::new(genesis, Arc::new(Signer::new(genesis, key)), validators.clone(), blockchain, DummyP2p);
+  network.validate(&TendermintBlock(block.serialize())).await.unwrap();
+  assert_eq!(*network.last_rejection.read().await, None);
+  assert_eq!(
+    network.validate(&TendermintBlock(oversized.serialize())).await,
+    Err(TendermintBlockError::Fatal)
+  );
+  assert_eq!(
+    *network.last_rejection.read().await,
+    Some(BlockRejection::Invalid(BlockError::TooLargeBlock))
+  );
+
+  // A malformed block is rejected as undecodable
+  assert_eq!(
+    network.validate(&TendermintBlock(vec![0xff; 16])).await,
+    Err(TendermintBlockError::Fatal)
+  );
+  assert!(matches!(*network.last_rejection.read().await, Some(BlockRejection::Undecodable(_))));
+
+  // A block which doesn't build off the tip is rejected with the specific reason
+  let mut orphan = block.clone();
+  orphan.header.parent = [0xff; 32];
+  assert_eq!(
+    network.validate(&TendermintBlock(orphan.serialize())).await,
+    Err(TendermintBlockError::Fatal)
+  );
+  assert_eq!(
+    *network.last_rejection.read().await,
+    Some(BlockRejection::Invalid(BlockError::InvalidParent))
+  );
+
+  // A valid block doesn't clear the last rejection
+  network.validate(&TendermintBlock(block.serialize())).await.unwrap();
+  assert_eq!(
+    *network.last_rejection.read().await,
+    Some(BlockRejection::Invalid(BlockError::InvalidParent))
+  );
+}
+
 #[test]
 fn provided_transaction() {
   let genesis = new_genesis();
@@ -212,8 +437,8 @@ fn provided_transaction() {
   // This should be providable
   let mut temp_db = MemDb::new();
   let mut txs = ProvidedTransactions::<_, ProvidedTransaction>::new(temp_db.clone(), genesis);
-  txs.provide(tx.clone()).unwrap();
-  assert_eq!(txs.provide(tx.clone()), Err(ProvidedError::AlreadyProvided));
+  txs.provide(tx.clone(), 0).unwrap();
+  assert_eq!(txs.provide(tx.clone(), 0), Err(ProvidedError::AlreadyProvided));
   assert_eq!(
     ProvidedTransactions::<_, ProvidedTransaction>::new(temp_db.clone(), genesis).transactions,
     HashMap::from([("order1", VecDeque::from([tx.clone()]))]),
@@ -228,7 +453,7 @@ fn provided_transaction() {
   // case we have the block's provided txs in our local as well
   {
     // Non-provided transactions should fail verification because we don't have them locally.
-    let block = Block::new(blockchain.tip(), vec![tx.clone()], vec![]);
+    let block = Block::new(blockchain.tip(), vec![tx.clone()], vec![], BLOCK_SIZE_LIMIT);
     assert!(blockchain.verify_block::<N>(&block, &validators, false).is_err());
 
     // Provided transactions should pass verification
@@ -238,7 +463,7 @@ fn provided_transaction() {
     // add_block should work for verified blocks
     assert!(blockchain.add_block::<N>(&block, vec![], &validators).is_ok());
 
-    let block = Block::new(blockchain.tip(), vec![tx.clone()], vec![]);
+    let block = Block::new(blockchain.tip(), vec![tx.clone()], vec![], BLOCK_SIZE_LIMIT);
 
     // The provided transaction should no longer considered provided but added to chain,
     // causing this error
@@ -257,11 +482,13 @@ fn provided_transaction() {
 
     // add_block DOES NOT fail for unverified provided transactions if told to add them,
     // since now we can have them later.
-    let block1 = Block::new(blockchain.tip(), vec![tx1.clone(), tx3.clone()], vec![]);
+    let block1 =
+      Block::new(blockchain.tip(), vec![tx1.clone(), tx3.clone()], vec![], BLOCK_SIZE_LIMIT);
     assert!(blockchain.add_block::<N>(&block1, vec![], &validators).is_ok());
 
     // in fact, we can have many blocks that have provided txs that we don't have locally.
-    let block2 = Block::new(blockchain.tip(), vec![tx2.clone(), tx4.clone()], vec![]);
+    let block2 =
+      Block::new(blockchain.tip(), vec![tx2.clone(), tx4.clone()], vec![], BLOCK_SIZE_LIMIT);
     assert!(blockchain.add_block::<N>(&block2, vec![], &validators).is_ok());
 
     // make sure we won't return ok for the block before we actually got the txs
@@ -335,6 +562,80 @@ fn provided_transaction() {
   }
 }
 
+#[test]
+fn provided_transaction_order() {
+  let genesis = new_genesis();
+  let validators = Arc::new(Validators::new(genesis, vec![]).unwrap());
+  let (_, mut blockchain) = new_blockchain::<ProvidedTransaction>(genesis, &[]);
+
+  let tx1 = random_provided_transaction(&mut OsRng, "order1");
+  let tx2 = random_provided_transaction(&mut OsRng, "order1");
+  let tx3 = random_provided_transaction(&mut OsRng, "order2");
+  blockchain.provide_transaction(tx1.clone()).unwrap();
+  blockchain.provide_transaction(tx2.clone()).unwrap();
+  blockchain.provide_transaction(tx3.clone()).unwrap();
+
+  // Provided transactions of the same order must be included in the order they were provided
+  let block = Block::new(
+    blockchain.tip(),
+    vec![tx2.clone(), tx1.clone(), tx3.clone()],
+    vec![],
+    BLOCK_SIZE_LIMIT,
+  );
+  assert_eq!(
+    blockchain.verify_block::<N>(&block, &validators, false),
+    Err(BlockError::DistinctProvided)
+  );
+
+  // Distinct orders are independent of each other, so they may be interleaved
+  let block = Block::new(blockchain.tip(), vec![tx1, tx3, tx2], vec![], BLOCK_SIZE_LIMIT);
+  blockchain.verify_block::<N>(&block, &validators, false).unwrap();
+}
+
+#[test]
+fn orphaned_provided_transaction() {
+  let genesis = new_genesis();
+  let validators = Arc::new(Validators::new(genesis, vec![]).unwrap());
+  let (mut db, mut blockchain) = new_blockchain::<ProvidedTransaction>(genesis, &[]);
+
+  let orphan = random_provided_transaction(&mut OsRng, "order1");
+  blockchain.provide_transaction(orphan.clone()).unwrap();
+
+  // Add a block which doesn't reference the provided transaction
+  let block = Block::new(blockchain.tip(), vec![], vec![], BLOCK_SIZE_LIMIT);
+  blockchain.add_block::<N>(&block, vec![], &validators).unwrap();
+  let included = random_provided_transaction(&mut OsRng, "order2");
+  blockchain.provide_transaction(included.clone()).unwrap();
+
+  // Within the window, nothing is orphaned
+  assert!(blockchain.orphaned_provided(2).is_empty());
+
+  // Include the other order's transaction, yet still not the orphan
+  let block = Block::new(blockchain.tip(), vec![included], vec![], BLOCK_SIZE_LIMIT);
+  blockchain.add_block::<N>(&block, vec![], &validators).unwrap();
+  assert_eq!(blockchain.orphaned_provided(2), vec![orphan.clone()]);
+  // Reporting the orphan doesn't remove it
+  assert_eq!(blockchain.orphaned_provided(2), vec![orphan.clone()]);
+
+  // Remove the orphan's block number, as if it was provided before block numbers were saved
+  let mut txn = db.txn();
+  txn.del(MemDb::key(b"tributary_provided", b"provided_at", [genesis, orphan.hash()].concat()));
+  txn.commit();
+
+  // On reload, it should be treated as provided as of the current tip
+  let mut blockchain =
+    Blockchain::<_, ProvidedTransaction>::new(db.clone(), genesis, &[], BLOCK_SIZE_LIMIT);
+  assert_eq!(blockchain.block_number(), 2);
+  assert!(blockchain.orphaned_provided(1).is_empty());
+  assert_eq!(blockchain.orphaned_provided(0), vec![orphan.clone()]);
+
+  // A block including the orphan should still be accepted
+  let block = Block::new(blockchain.tip(), vec![orphan], vec![], BLOCK_SIZE_LIMIT);
+  blockchain.verify_block::<N>(&block, &validators, false).unwrap();
+  blockchain.add_block::<N>(&block, vec![], &validators).unwrap();
+  assert!(blockchain.orphaned_provided(0).is_empty());
+}
+
 #[tokio::test]
 async fn tendermint_evidence_tx() {
   let genesis = new_genesis();
@@ -1,7 +1,38 @@
-use tendermint::ext::Network;
+use std::{
+  sync::{Arc, Mutex},
+  collections::HashMap,
+};
+
+use zeroize::Zeroizing;
+use rand::rngs::OsRng;
+
+use ciphersuite::{
+  group::{
+    ff::{Field, PrimeField},
+    Group, GroupEncoding,
+  },
+  Ciphersuite, Ristretto,
+};
+
+use scale::Encode;
+
+use dkg::Participant;
+
+use serai_db::MemDb;
+
+use tendermint::{
+  SlashReason, SlashEvent, commit_msg,
+  ext::{
+    BlockNumber, RoundNumber, Signer as SignerTrait, SignatureScheme, Commit, Weights, Network,
+  },
+};
 use crate::{
-  P2p, TendermintTx,
-  tendermint::{TARGET_BLOCK_TIME, TendermintNetwork},
+  P2p, TendermintTx, Blockchain, ChainError, BLOCK_SIZE_LIMIT,
+  tendermint::{
+    TARGET_BLOCK_TIME, Signer, Validators, HalfAggregation, ConcatenatedSignatures,
+    CommitAggregation, TendermintNetwork, sign,
+  },
+  tests::{SignedTransaction, new_genesis, p2p::DummyP2p},
 };
 
 #[test]
@@ -26,3 +57,555 @@ fn assert_target_block_time() {
     TARGET_BLOCK_TIME / 1000
   )
 }
+
+type N = TendermintNetwork<MemDb, SignedTransaction, DummyP2p>;
+
+// Create a network for the specified validators, each with a weight of 1, signing with the first
+fn network(genesis: [u8; 32], keys: &[Zeroizing<<Ristretto as Ciphersuite>::F>]) -> N {
+  let validators =
+    keys.iter().map(|key| <Ristretto as Ciphersuite>::generator() * **key).collect::<Vec<_>>();
+  let blockchain = Blockchain::new(MemDb::new(), genesis, &validators, BLOCK_SIZE_LIMIT);
+  TendermintNetwork::new(
+    genesis,
+    Arc::new(Signer::new(genesis, keys[0].clone())),
+    Arc::new(
+      Validators::new(genesis, validators.into_iter().map(|validator| (validator, 1)).collect())
+        .unwrap(),
+    ),
+    Arc::new(tokio::sync::RwLock::new(blockchain)),
+    DummyP2p,
+  )
+}
+
+#[test]
+fn network_genesis() {
+  let genesis = new_genesis();
+  let keys = vec![Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng))];
+  let network = network(genesis, &keys);
+  assert_eq!(network.genesis(), genesis);
+  assert_eq!(network.signer.genesis(), genesis);
+  assert_eq!(network.validators.genesis(), genesis);
+}
+
+// Create a network whose signer and validators may be for a genesis other than the network's
+fn mismatched_network(
+  genesis: [u8; 32],
+  signer_genesis: [u8; 32],
+  validators_genesis: [u8; 32],
+) -> N {
+  let key = Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng));
+  let validator = <Ristretto as Ciphersuite>::generator() * *key;
+  let blockchain = Blockchain::new(MemDb::new(), genesis, &[validator], BLOCK_SIZE_LIMIT);
+  TendermintNetwork::new(
+    genesis,
+    Arc::new(Signer::new(signer_genesis, key)),
+    Arc::new(Validators::new(validators_genesis, vec![(validator, 1)]).unwrap()),
+    Arc::new(tokio::sync::RwLock::new(blockchain)),
+    DummyP2p,
+  )
+}
+
+#[test]
+#[should_panic(expected = "signer was for a different genesis than the network")]
+fn network_signer_genesis_mismatch() {
+  let genesis = new_genesis();
+  mismatched_network(genesis, new_genesis(), genesis);
+}
+
+#[test]
+#[should_panic(expected = "validators were for a different genesis than the network")]
+fn network_validators_genesis_mismatch() {
+  let genesis = new_genesis();
+  mismatched_network(genesis, genesis, new_genesis());
+}
+
+#[test]
+fn validators_from_dkg() {
+  let genesis = new_genesis();
+  let keys =
+    (0 .. 4).map(|_| <Ristretto as Ciphersuite>::G::random(&mut OsRng)).collect::<Vec<_>>();
+  let weights = [1, 2, 1, 3];
+  let key_shares = keys
+    .iter()
+    .zip(weights)
+    .enumerate()
+    .map(|(i, (key, weight))| {
+      (Participant::new(u16::try_from(i + 1).unwrap()).unwrap(), key.to_bytes(), weight)
+    })
+    .collect::<Vec<_>>();
+
+  let validators = Validators::from_dkg(genesis, &key_shares).unwrap();
+  assert!(validators.is_canonical());
+  assert_eq!(validators.total_weight(), 7);
+  for (key, weight) in keys.iter().zip(weights) {
+    assert_eq!(validators.weight(key.to_bytes()), weight);
+  }
+  // The validators should be identical to those created from the keys directly
+  assert_eq!(
+    validators,
+    Validators::new(genesis, keys.iter().copied().zip(weights).collect()).unwrap()
+  );
+
+  // The order the key shares are listed in shouldn't affect the validators, including their
+  // proposers
+  let mut reversed = key_shares.clone();
+  reversed.reverse();
+  let other = Validators::from_dkg(genesis, &reversed).unwrap();
+  assert_eq!(validators, other);
+  for round in 0 .. 10 {
+    assert_eq!(
+      validators.proposer(BlockNumber(1), RoundNumber(round)),
+      other.proposer(BlockNumber(1), RoundNumber(round))
+    );
+  }
+
+  // Duplicated participants, invalid keys, and zero weights should be rejected
+  let mut duplicated = key_shares.clone();
+  duplicated[1].0 = duplicated[0].0;
+  assert!(Validators::from_dkg(genesis, &duplicated).is_none());
+  let mut invalid = key_shares.clone();
+  invalid[0].1 = [0xff; 32];
+  assert!(Validators::from_dkg(genesis, &invalid).is_none());
+  let mut zero = key_shares;
+  zero[0].2 = 0;
+  assert!(Validators::from_dkg(genesis, &zero).is_none());
+}
+
+#[test]
+fn commit_aggregation() {
+  fn test<A: CommitAggregation>(
+    genesis: [u8; 32],
+    keys: &[Zeroizing<<Ristretto as Ciphersuite>::F>],
+  ) -> Vec<u8> {
+    let points =
+      keys.iter().map(|key| <Ristretto as Ciphersuite>::generator() * **key).collect::<Vec<_>>();
+    let validators =
+      Validators::<A>::with_aggregation(genesis, points.iter().map(|point| (*point, 1)).collect())
+        .unwrap();
+
+    let ids = points.iter().map(GroupEncoding::to_bytes).collect::<Vec<_>>();
+    let msg = b"commit";
+    let sigs = keys.iter().map(|key| sign(genesis, key, msg)).collect::<Vec<_>>();
+    let aggregate = validators.aggregate(&ids, msg, &sigs);
+    assert!(validators.verify_aggregate(&ids, msg, &aggregate));
+
+    // The aggregate shouldn't verify for another message or set of signers
+    assert!(!validators.verify_aggregate(&ids, b"other", &aggregate));
+    assert!(!validators.verify_aggregate(&ids[1 ..], msg, &aggregate));
+    let mut reordered = ids.clone();
+    reordered.swap(0, 1);
+    assert!(!validators.verify_aggregate(&reordered, msg, &aggregate));
+
+    aggregate
+  }
+
+  let genesis = new_genesis();
+  let keys = (0 .. 4)
+    .map(|_| Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng)))
+    .collect::<Vec<_>>();
+
+  // The same signers should produce verifying commits with either strategy
+  let half = test::<HalfAggregation>(genesis, &keys);
+  let concatenated = test::<ConcatenatedSignatures>(genesis, &keys);
+  assert_eq!(concatenated.len(), 64 * keys.len());
+  assert_ne!(half, concatenated);
+
+  // Yet a commit's signature is solely valid under the strategy which produced it
+  let ids = keys
+    .iter()
+    .map(|key| (<Ristretto as Ciphersuite>::generator() * **key).to_bytes())
+    .collect::<Vec<_>>();
+  let weighted = || {
+    keys.iter().map(|key| (<Ristretto as Ciphersuite>::generator() * **key, 1)).collect::<Vec<_>>()
+  };
+  let half_validators = Validators::new(genesis, weighted()).unwrap();
+  let concatenated_validators =
+    Validators::<ConcatenatedSignatures>::with_aggregation(genesis, weighted()).unwrap();
+  assert!(!half_validators.verify_aggregate(&ids, b"commit", &concatenated));
+  assert!(!concatenated_validators.verify_aggregate(&ids, b"commit", &half));
+}
+
+#[test]
+fn has_supermajority() {
+  let genesis = new_genesis();
+  let keys =
+    (0 .. 4).map(|_| <Ristretto as Ciphersuite>::G::random(&mut OsRng)).collect::<Vec<_>>();
+  // A total weight of 6 has a threshold of 5
+  let validators =
+    Validators::new(genesis, keys.iter().copied().zip([1, 1, 2, 2]).collect()).unwrap();
+  assert_eq!(validators.threshold(), 5);
+
+  let ids = keys.iter().map(GroupEncoding::to_bytes).collect::<Vec<_>>();
+  assert!(!validators.has_supermajority(&[]));
+  assert!(!validators.has_supermajority(&[ids[2], ids[3]]));
+  assert!(validators.has_supermajority(&[ids[0], ids[2], ids[3]]));
+  assert!(validators.has_supermajority(&ids));
+  // Duplicated signers should only be counted once
+  assert!(!validators.has_supermajority(&[ids[2], ids[2], ids[3]]));
+  assert!(!validators.has_supermajority(&[ids[0], ids[0], ids[1], ids[2]]));
+}
+
+#[test]
+fn proposer() {
+  let genesis = new_genesis();
+  let keys =
+    (0 .. 4).map(|_| <Ristretto as Ciphersuite>::G::random(&mut OsRng)).collect::<Vec<_>>();
+  let new_validators =
+    || Validators::new(genesis, keys.iter().copied().zip([1, 1, 1, 1]).collect()).unwrap();
+  let validators = new_validators();
+
+  // The proposer must be deterministic, including across separately constructed instances
+  let other = new_validators();
+  for block in 0 .. 10 {
+    for round in 0 .. 10 {
+      assert_eq!(
+        validators.proposer(BlockNumber(block), RoundNumber(round)),
+        other.proposer(BlockNumber(block), RoundNumber(round))
+      );
+    }
+  }
+
+  // And should differ across genesis
+  let other_genesis =
+    Validators::new(new_genesis(), keys.iter().copied().zip([1, 1, 1, 1]).collect()).unwrap();
+  assert!((0 .. 100).any(|round| {
+    validators.proposer(BlockNumber(1), RoundNumber(round)) !=
+      other_genesis.proposer(BlockNumber(1), RoundNumber(round))
+  }));
+
+  // The proposers across rounds, and across blocks, should be well-spread
+  const SAMPLES: u32 = 4000;
+  let ids = keys.iter().map(GroupEncoding::to_bytes).collect::<Vec<_>>();
+  let mut by_round = HashMap::new();
+  let mut by_block = HashMap::new();
+  for i in 0 .. SAMPLES {
+    *by_round.entry(validators.proposer(BlockNumber(1), RoundNumber(i))).or_insert(0) += 1;
+    *by_block.entry(validators.proposer(BlockNumber(i.into()), RoundNumber(0))).or_insert(0) += 1;
+  }
+  for counts in [by_round, by_block] {
+    for id in &ids {
+      // Each validator should propose ~1000 times, allowing for a wide deviation
+      let count = counts[id];
+      assert!((800 .. 1200).contains(&count), "poorly spread proposers: {count}");
+    }
+  }
+
+  // A validator with more weight should be selected proportionally more often
+  let weighted =
+    Validators::new(genesis, keys.iter().copied().zip([1, 1, 1, 5]).collect()).unwrap();
+  let heaviest =
+    (0 .. SAMPLES).filter(|i| weighted.proposer(BlockNumber(1), RoundNumber(*i)) == ids[3]).count();
+  // 5/8ths of 4000 is 2500
+  assert!((2300 .. 2700).contains(&heaviest), "proposers weren't weighted: {heaviest}");
+}
+
+#[tokio::test]
+async fn slash_log_context() {
+  // Captures the key-value pairs of every error logged
+  struct KvLogger(Mutex<Vec<Vec<(String, String)>>>);
+  impl log::Log for KvLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+      metadata.level() <= log::Level::Error
+    }
+    fn log(&self, record: &log::Record) {
+      struct Collector(Vec<(String, String)>);
+      impl<'kvs> log::kv::VisitSource<'kvs> for Collector {
+        fn visit_pair(
+          &mut self,
+          key: log::kv::Key<'kvs>,
+          value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+          self.0.push((key.to_string(), value.to_string()));
+          Ok(())
+        }
+      }
+
+      if self.enabled(record.metadata()) {
+        let mut collector = Collector(vec![]);
+        record.key_values().visit(&mut collector).unwrap();
+        self.0.lock().unwrap().push(collector.0);
+      }
+    }
+    fn flush(&self) {}
+  }
+  static LOGGER: KvLogger = KvLogger(Mutex::new(vec![]));
+  log::set_logger(&LOGGER).unwrap();
+  log::set_max_level(log::LevelFilter::Error);
+
+  let genesis = new_genesis();
+  let key = Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng));
+  let validator = (<Ristretto as Ciphersuite>::generator() * *key).to_bytes();
+  let mut network = network(genesis, &[key]);
+
+  network.slash(validator, SlashEvent::Id(SlashReason::InvalidBlock, 5, 0)).await;
+
+  let expected = vec![
+    ("genesis".to_string(), hex::encode(genesis)),
+    ("block_number".to_string(), "5".to_string()),
+    ("validator".to_string(), hex::encode(validator)),
+  ];
+  assert!(LOGGER.0.lock().unwrap().contains(&expected));
+}
+
+#[tokio::test]
+async fn verify_commit() {
+  let genesis = new_genesis();
+  let keys = (0 .. 5)
+    .map(|_| Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng)))
+    .collect::<Vec<_>>();
+  // Only the first four keys are validators, with a threshold of 3
+  let network = network(genesis, &keys[.. 4]);
+  let validators = network.signature_scheme();
+
+  let block = [0xff; 32];
+  let end_time = 1;
+  let mut signers = vec![];
+  let mut sigs = vec![];
+  for key in &keys {
+    let signer = Signer::new(genesis, key.clone());
+    signers.push(signer.validator_id().await.unwrap());
+    sigs.push(signer.sign(&commit_msg(end_time, &block)).await);
+  }
+  let commit = |range: core::ops::Range<usize>| Commit::<Arc<Validators>> {
+    end_time,
+    validators: signers[range.clone()].to_vec(),
+    signature: validators.aggregate(
+      &signers[range.clone()],
+      &commit_msg(end_time, &block),
+      &sigs[range],
+    ),
+  };
+
+  assert!(network.verify_commit(block, &commit(0 .. 3)));
+  assert!(network.verify_commit(block, &commit(0 .. 4)));
+  // Not enough weight
+  assert!(!network.verify_commit(block, &commit(0 .. 2)));
+  // Signed by a non-validator
+  assert!(!network.verify_commit(block, &commit(2 .. 5)));
+  // Signed for a different block
+  assert!(!network.verify_commit([0xfe; 32], &commit(0 .. 3)));
+}
+
+#[test]
+fn commit_signers() {
+  let genesis = new_genesis();
+  let keys =
+    (0 .. 5).map(|_| <Ristretto as Ciphersuite>::G::random(&mut OsRng)).collect::<Vec<_>>();
+  // Only the first four keys are validators
+  let validators =
+    Validators::new(genesis, keys[.. 4].iter().copied().zip([1, 2, 3, 4]).collect()).unwrap();
+  let ids = keys.iter().map(GroupEncoding::to_bytes).collect::<Vec<_>>();
+
+  // The signature isn't checked, so a commit can be constructed with an empty signature
+  let commit =
+    |validators: Vec<[u8; 32]>| Commit::<Validators> { end_time: 0, validators, signature: vec![] };
+
+  assert_eq!(validators.commit_signers(&commit(vec![])), Some((vec![], 0)));
+  assert_eq!(
+    validators.commit_signers(&commit(vec![ids[0], ids[2]])),
+    Some((vec![keys[0], keys[2]], 4))
+  );
+  assert_eq!(
+    validators.commit_signers(&commit(vec![ids[3], ids[1], ids[2]])),
+    Some((vec![keys[3], keys[1], keys[2]], 9))
+  );
+  assert_eq!(
+    validators.commit_signers(&commit(ids[.. 4].to_vec())),
+    Some((keys[.. 4].to_vec(), 10))
+  );
+  // Duplicated signers should only be counted once
+  assert_eq!(
+    validators.commit_signers(&commit(vec![ids[1], ids[1], ids[3]])),
+    Some((vec![keys[1], keys[3]], 6))
+  );
+  // Signed by a non-validator
+  assert_eq!(validators.commit_signers(&commit(vec![ids[0], ids[4]])), None);
+}
+
+#[test]
+fn absent_signers() {
+  let genesis = new_genesis();
+  let keys =
+    (0 .. 5).map(|_| <Ristretto as Ciphersuite>::G::random(&mut OsRng)).collect::<Vec<_>>();
+  // Only the first four keys are validators
+  let validators =
+    Validators::new(genesis, keys[.. 4].iter().copied().zip([1, 2, 3, 4]).collect()).unwrap();
+  let ids = keys.iter().map(GroupEncoding::to_bytes).collect::<Vec<_>>();
+
+  let commit =
+    |validators: Vec<[u8; 32]>| Commit::<Validators> { end_time: 0, validators, signature: vec![] };
+  let sorted = |mut ids: Vec<[u8; 32]>| {
+    ids.sort_unstable();
+    ids
+  };
+
+  assert_eq!(validators.absent_signers(&commit(vec![])), sorted(ids[.. 4].to_vec()));
+  assert_eq!(
+    validators.absent_signers(&commit(vec![ids[2], ids[0]])),
+    sorted(vec![ids[1], ids[3]])
+  );
+  assert!(validators.absent_signers(&commit(ids[.. 4].to_vec())).is_empty());
+  // Duplicated signers and non-validators don't affect who's absent
+  assert_eq!(
+    validators.absent_signers(&commit(vec![ids[1], ids[1], ids[4]])),
+    sorted(vec![ids[0], ids[2], ids[3]])
+  );
+}
+
+#[test]
+fn canonical_robin() {
+  let genesis = new_genesis();
+  let keys =
+    (0 .. 4).map(|_| <Ristretto as Ciphersuite>::G::random(&mut OsRng)).collect::<Vec<_>>();
+  let validators =
+    Validators::new(genesis, keys[.. 3].iter().copied().zip([1, 2, 3]).collect()).unwrap();
+  assert!(validators.is_canonical());
+
+  // A validator listed multiple times would leave the robin inconsistent with the weights
+  assert!(Validators::new(genesis, vec![(keys[0], 1), (keys[1], 1), (keys[0], 2)]).is_none());
+
+  let canonical_after = |modify: &dyn Fn(&mut Vec<[u8; 32]>)| {
+    let mut validators = validators.clone();
+    modify(validators.robin_mut());
+    validators.is_canonical()
+  };
+  // Omitted entry
+  assert!(!canonical_after(&|robin| {
+    robin.pop();
+  }));
+  // Additional entry for an existing validator
+  assert!(!canonical_after(&|robin| robin.push(robin[0])));
+  // Entry for a non-validator, replacing an existing entry
+  assert!(!canonical_after(&|robin| robin[0] = keys[3].to_bytes()));
+  // Same length, yet one validator appears in place of another
+  assert!(!canonical_after(&|robin| {
+    let other = *robin.iter().find(|validator| **validator != robin[0]).unwrap();
+    robin[0] = other;
+  }));
+  // Same entries, yet not in the deterministic order
+  assert!(!canonical_after(&|robin| {
+    let other = robin.iter().position(|validator| *validator != robin[0]).unwrap();
+    robin.swap(0, other);
+  }));
+  assert!(!canonical_after(&|robin| robin.reverse()));
+}
+
+#[tokio::test]
+async fn sign_vectors() {
+  for (genesis, key, msg, expected) in [
+    (
+      [0; 32],
+      1u64,
+      b"".as_ref(),
+      "d42ec5cf22f2a8cf80522ea981b1de02714ededfee90e2cc70df268cc27cb524\
+       ca0a4c79576cf2bb03b6301678e448f6c7d9648132df07342d2176e1efca3005",
+    ),
+    (
+      [0xff; 32],
+      2,
+      b"message",
+      "eef268d8e66427702385bc39c7f9b00ac74f0090e3d1f0ec4f61aec1e4243773\
+       267306e493a8f25bcf10a2f80b69fc78e367827ce070e3a367cda84bab00dc0c",
+    ),
+    (
+      [1; 32],
+      0xdeadbeef,
+      &[0; 64],
+      "5c52087e0cc3f49d17cb1706605c9f2ef8ee29aadb7cd5f68fd0f1d1737eea15\
+       da5db6a9a423fe5bd71c8698a0c5b2e0cf8eee86a2e1d2ee46c928604ae4f507",
+    ),
+  ] {
+    let key = Zeroizing::new(<Ristretto as Ciphersuite>::F::from(key));
+    let sig = sign(genesis, &key, msg);
+    assert_eq!(hex::encode(sig), expected);
+
+    // The Signer should produce the same signature, which should verify
+    let signer = Signer::new(genesis, key);
+    assert_eq!(signer.sign(msg).await, sig);
+    let validator = signer.validator_id().await.unwrap();
+    let validators = Validators::new(
+      genesis,
+      vec![(<Ristretto as Ciphersuite>::read_G(&mut validator.as_ref()).unwrap(), 1)],
+    )
+    .unwrap();
+    assert!(validators.verify(validator, msg, &sig));
+  }
+}
+
+#[tokio::test]
+async fn signer_debug_redacts_key() {
+  let genesis = new_genesis();
+  let key = Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng));
+  let signer = Signer::new(genesis, key.clone());
+
+  let debug = format!("{signer:?}");
+  assert!(!debug.contains(&hex::encode(key.to_repr())));
+  assert!(!debug.contains(&format!("{:?}", *key)));
+  // The signer is instead identified by its validator ID
+  assert!(debug.contains(&hex::encode(signer.validator_id().await.unwrap())));
+
+  // The signer is still cloneable and comparable
+  assert_eq!(signer.clone(), signer);
+  assert!(
+    signer != Signer::new(genesis, Zeroizing::new(*key + <Ristretto as Ciphersuite>::F::ONE))
+  );
+}
+
+#[tokio::test]
+async fn verify_chain() {
+  let genesis = new_genesis();
+  let keys = (0 .. 4)
+    .map(|_| Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng)))
+    .collect::<Vec<_>>();
+  let network = network(genesis, &keys);
+  let validators = network.signature_scheme();
+
+  // Sign a commit for the specified block with the specified amount of validators
+  let commit = |block: [u8; 32], signers: usize| {
+    let keys = keys[.. signers].to_vec();
+    let validators = validators.clone();
+    async move {
+      let end_time = 1;
+      let mut ids = vec![];
+      let mut sigs = vec![];
+      for key in keys {
+        let signer = Signer::new(genesis, key);
+        ids.push(signer.validator_id().await.unwrap());
+        sigs.push(signer.sign(&commit_msg(end_time, &block)).await);
+      }
+      let signature = validators.aggregate(&ids, &commit_msg(end_time, &block), &sigs);
+      Commit::<Arc<Validators>> { end_time, validators: ids, signature }.encode()
+    }
+  };
+
+  // Build a chain of five blocks, where the specified block's commit lacks a supermajority
+  let chain = |bad: Option<u64>| {
+    let validators = validators.clone();
+    let commit = &commit;
+    async move {
+      let mut blockchain =
+        Blockchain::<MemDb, SignedTransaction>::new(MemDb::new(), genesis, &[], BLOCK_SIZE_LIMIT);
+      for number in 1 ..= 5 {
+        let block = blockchain.build_block::<N>(&validators);
+        let signers = if bad == Some(number) { 2 } else { 3 };
+        blockchain
+          .add_block::<N>(&block, commit(block.hash(), signers).await, &validators)
+          .unwrap();
+      }
+      blockchain
+    }
+  };
+
+  // An empty chain is trivially valid
+  let empty =
+    Blockchain::<MemDb, SignedTransaction>::new(MemDb::new(), genesis, &[], BLOCK_SIZE_LIMIT);
+  assert_eq!(empty.verify_chain(&network), Ok(()));
+
+  let mut valid = chain(None).await;
+  assert_eq!(valid.verify_chain(&network), Ok(()));
+  // Pruned blocks are still verified
+  valid.prune_below(4);
+  assert_eq!(valid.verify_chain(&network), Ok(()));
+
+  assert_eq!(chain(Some(3)).await.verify_chain(&network), Err(ChainError::InvalidCommit(3)));
+}
@@ -2,11 +2,14 @@ use rand::rngs::OsRng;
 
 use blake2::{Digest, Blake2s256};
 
-use ciphersuite::{group::ff::Field, Ciphersuite, Ristretto};
+use ciphersuite::{
+  group::{ff::Field, GroupEncoding},
+  Ciphersuite, Ristretto,
+};
 
 use crate::{
   ReadWrite,
-  transaction::{Signed, Transaction, verify_transaction},
+  transaction::{TransactionError, Signed, Transaction, verify_transaction},
   tests::{random_signed, random_signed_transaction},
 };
 
@@ -49,7 +52,10 @@ fn signed_transaction() {
   {
     let mut tx = tx.clone();
     tx.1.signer += Ristretto::generator();
-    assert!(verify_transaction(&tx, genesis, &mut |_, _| Some(tx.1.nonce)).is_err());
+    assert_eq!(
+      verify_transaction(&tx, genesis, &mut |_, _| Some(tx.1.nonce)),
+      Err(TransactionError::InvalidSignature(tx.1.signer.to_bytes()))
+    );
   }
 
   // Different nonce
@@ -64,12 +70,18 @@ fn signed_transaction() {
   {
     let mut tx = tx.clone();
     tx.1.signature.R += Ristretto::generator();
-    assert!(verify_transaction(&tx, genesis, &mut |_, _| Some(tx.1.nonce)).is_err());
+    assert_eq!(
+      verify_transaction(&tx, genesis, &mut |_, _| Some(tx.1.nonce)),
+      Err(TransactionError::InvalidSignature(tx.1.signer.to_bytes()))
+    );
   }
   {
     let mut tx = tx.clone();
     tx.1.signature.s += <Ristretto as Ciphersuite>::F::ONE;
-    assert!(verify_transaction(&tx, genesis, &mut |_, _| Some(tx.1.nonce)).is_err());
+    assert_eq!(
+      verify_transaction(&tx, genesis, &mut |_, _| Some(tx.1.nonce)),
+      Err(TransactionError::InvalidSignature(tx.1.signer.to_bytes()))
+    );
   }
 
   // Sanity check the original TX was never mutated and is valid
@@ -25,9 +25,9 @@ pub enum TransactionError {
   /// Transaction's nonce isn't the prior nonce plus one.
   #[error("invalid nonce")]
   InvalidNonce,
-  /// Transaction's signature is invalid.
+  /// Transaction's signature is invalid, carrying the encoding of the claimed signer.
   #[error("invalid signature")]
-  InvalidSignature,
+  InvalidSignature([u8; 32]),
   /// Transaction's content is invalid.
   #[error("transaction content is invalid")]
   InvalidContent,
@@ -209,7 +209,7 @@ pub(crate) fn verify_transaction<F: GAIN, T: Transaction>(
 
       // TODO: Use a batch verification here
       if !signature.verify(*signer, tx.sig_hash(genesis)) {
-        Err(TransactionError::InvalidSignature)?;
+        Err(TransactionError::InvalidSignature(signer.to_bytes()))?;
       }
     }
   }
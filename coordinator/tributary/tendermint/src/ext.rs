@@ -167,6 +167,11 @@ pub trait Weights: Send + Sync {
   fn fault_threshold(&self) -> u64 {
     (self.total_weight() - self.threshold()) + 1
   }
+  /// If the combined weight of the distinct signers meets the threshold for BFT consensus.
+  fn has_supermajority(&self, signers: &[Self::ValidatorId]) -> bool {
+    let signers = signers.iter().collect::<HashSet<_>>();
+    signers.into_iter().map(|signer| self.weight(*signer)).sum::<u64>() >= self.threshold()
+  }
 
   /// Weighted round robin function.
   fn proposer(&self, block: BlockNumber, round: RoundNumber) -> Self::ValidatorId;
@@ -234,6 +239,12 @@ pub trait Network: Sized + Send + Sync {
   ///
   /// BLOCK_PROCESSING_TIME + (3 * LATENCY_TIME) must be divisible by 1000.
   const LATENCY_TIME: u32;
+  /// Amount of messages from the P2P layer which may be queued for the machine.
+  ///
+  /// Each `MessageSender` may queue one message in addition to these. Once the queue is full,
+  /// further messages are dropped until the machine catches up, bounding the memory a flood of
+  /// messages can consume.
+  const MAX_IN_FLIGHT_MESSAGES: usize = 1024;
 
   /// The block time, in seconds. Defined as the processing time plus three times the latency.
   fn block_time() -> u32 {
@@ -270,8 +281,7 @@ pub trait Network: Sized + Send + Sync {
       return false;
     }
 
-    let weights = self.weights();
-    commit.validators.iter().map(|v| weights.weight(*v)).sum::<u64>() >= weights.threshold()
+    self.weights().has_supermajority(&commit.validators)
   }
 
   /// Broadcast a message to the other validators.
@@ -240,7 +240,7 @@ pub struct TendermintMachine<N: Network> {
   weights: Arc<N::Weights>,
 
   queue: VecDeque<MessageFor<N>>,
-  msg_recv: mpsc::UnboundedReceiver<SignedMessageFor<N>>,
+  msg_recv: mpsc::Receiver<SignedMessageFor<N>>,
   synced_block_recv: mpsc::UnboundedReceiver<SyncedBlock<N>>,
   synced_block_result_send: mpsc::UnboundedSender<bool>,
 
@@ -256,7 +256,39 @@ pub struct SyncedBlock<N: Network> {
 pub type SyncedBlockSender<N> = mpsc::UnboundedSender<SyncedBlock<N>>;
 pub type SyncedBlockResultReceiver = mpsc::UnboundedReceiver<bool>;
 
-pub type MessageSender<N> = mpsc::UnboundedSender<SignedMessageFor<N>>;
+/// Channel to send messages received from the P2P layer to the machine.
+///
+/// This channel buffers `Network::MAX_IN_FLIGHT_MESSAGES` messages, plus one message for each
+/// clone of this sender. If the machine falls behind, messages sent in excess of that bound are
+/// dropped.
+pub struct MessageSender<N: Network>(mpsc::Sender<SignedMessageFor<N>>);
+
+impl<N: Network> Clone for MessageSender<N> {
+  fn clone(&self) -> Self {
+    MessageSender(self.0.clone())
+  }
+}
+
+impl<N: Network> MessageSender<N> {
+  /// Queue a message for the machine.
+  ///
+  /// Returns Ok(false) if the message was dropped due to too many messages being in-flight.
+  /// Errors if the machine has been dropped.
+  pub fn send(&mut self, msg: SignedMessageFor<N>) -> Result<bool, mpsc::SendError> {
+    match self.0.try_send(msg) {
+      Ok(()) => Ok(true),
+      Err(e) if e.is_full() => {
+        log::warn!(
+          target: "tendermint",
+          "dropping message as at least {} messages are already in-flight",
+          N::MAX_IN_FLIGHT_MESSAGES,
+        );
+        Ok(false)
+      }
+      Err(e) => Err(e.into_send_error()),
+    }
+  }
+}
 
 /// A Tendermint machine and its channel to receive messages from the gossip layer over.
 pub struct TendermintHandle<N: Network> {
@@ -382,13 +414,13 @@ impl<N: Network + 'static> TendermintMachine<N> {
     last_time: u64,
     proposal: N::Block,
   ) -> TendermintHandle<N> {
-    let (msg_send, msg_recv) = mpsc::unbounded();
+    let (msg_send, msg_recv) = mpsc::channel(N::MAX_IN_FLIGHT_MESSAGES);
     let (synced_block_send, synced_block_recv) = mpsc::unbounded();
     let (synced_block_result_send, synced_block_result_recv) = mpsc::unbounded();
     TendermintHandle {
       synced_block: synced_block_send,
       synced_block_result: synced_block_result_recv,
-      messages: msg_send,
+      messages: MessageSender(msg_send),
       machine: {
         let now = SystemTime::now();
         let sys_time = sys_time(last_time);
@@ -1,5 +1,8 @@
 use std::{
-  sync::Arc,
+  sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+  },
   time::{UNIX_EPOCH, SystemTime, Duration},
 };
 
@@ -7,14 +10,16 @@ use async_trait::async_trait;
 
 use parity_scale_codec::{Encode, Decode};
 
-use futures_util::sink::SinkExt;
-use tokio::{sync::RwLock, time::sleep};
+use tokio::{
+  sync::RwLock,
+  time::{sleep, timeout},
+};
 
 use serai_db::MemDb;
 
 use tendermint_machine::{
-  ext::*, SignedMessageFor, SyncedBlockSender, SyncedBlockResultReceiver, MessageSender,
-  SlashEvent, TendermintMachine, TendermintHandle,
+  ext::*, Data, Message, SignedMessageFor, SyncedBlockSender, SyncedBlockResultReceiver,
+  MessageSender, SlashEvent, TendermintMachine, TendermintHandle,
 };
 
 type TestValidatorId = u16;
@@ -105,11 +110,14 @@ impl Block for TestBlock {
   }
 }
 
-#[allow(clippy::type_complexity)]
-struct TestNetwork(
-  u16,
-  Arc<RwLock<Vec<(MessageSender<Self>, SyncedBlockSender<Self>, SyncedBlockResultReceiver)>>>,
-);
+type TestHandles = Arc<
+  RwLock<
+    Vec<(MessageSender<TestNetwork>, SyncedBlockSender<TestNetwork>, SyncedBlockResultReceiver)>,
+  >,
+>;
+
+// The third field is the highest block number added to the chain, used to check for progress
+struct TestNetwork(u16, TestHandles, Arc<AtomicU32>);
 
 #[async_trait]
 impl Network for TestNetwork {
@@ -137,7 +145,7 @@ impl Network for TestNetwork {
 
   async fn broadcast(&mut self, msg: SignedMessageFor<Self>) {
     for (messages, _, _) in self.1.write().await.iter_mut() {
-      messages.send(msg.clone()).await.unwrap();
+      messages.send(msg.clone()).unwrap();
     }
   }
 
@@ -157,17 +165,15 @@ impl Network for TestNetwork {
     println!("Adding {:?}", &block);
     assert!(block.valid.is_ok());
     assert!(self.verify_commit(block.id(), &commit));
+    self.2.fetch_max(u32::from_le_bytes(block.id), Ordering::SeqCst);
     Some(TestBlock { id: (u32::from_le_bytes(block.id) + 1).to_le_bytes(), valid: Ok(()) })
   }
 }
 
 impl TestNetwork {
-  async fn new(
-    validators: usize,
-    start_time: u64,
-  ) -> Arc<RwLock<Vec<(MessageSender<Self>, SyncedBlockSender<Self>, SyncedBlockResultReceiver)>>>
-  {
+  async fn new(validators: usize, start_time: u64) -> (TestHandles, Arc<AtomicU32>) {
     let arc = Arc::new(RwLock::new(vec![]));
+    let highest = Arc::new(AtomicU32::new(0));
     {
       let mut write = arc.write().await;
       for i in 0 .. validators {
@@ -175,7 +181,7 @@ impl TestNetwork {
         let TendermintHandle { messages, synced_block, synced_block_result, machine } =
           TendermintMachine::new(
             MemDb::new(),
-            TestNetwork(i, arc.clone()),
+            TestNetwork(i, arc.clone(), highest.clone()),
             [0; 32],
             BlockNumber(1),
             start_time,
@@ -186,18 +192,65 @@ impl TestNetwork {
         write.push((messages, synced_block, synced_block_result));
       }
     }
-    arc
+    (arc, highest)
   }
 }
 
+// Wait for a block after the specified block to be added, panicking if that takes too long
+async fn wait_for_block_after(highest: &AtomicU32, block: u32) {
+  timeout(Duration::from_secs(60), async {
+    while highest.load(Ordering::SeqCst) <= block {
+      sleep(Duration::from_millis(100)).await;
+    }
+  })
+  .await
+  .expect("network didn't make progress");
+}
+
 #[tokio::test]
 async fn test_machine() {
-  TestNetwork::new(4, SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()).await;
-  sleep(Duration::from_secs(30)).await;
+  let (_, highest) =
+    TestNetwork::new(4, SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()).await;
+  wait_for_block_after(&highest, 2).await;
 }
 
 #[tokio::test]
 async fn test_machine_with_historic_start_time() {
-  TestNetwork::new(4, SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - 60).await;
-  sleep(Duration::from_secs(30)).await;
+  let (_, highest) =
+    TestNetwork::new(4, SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - 60).await;
+  wait_for_block_after(&highest, 2).await;
+}
+
+#[tokio::test]
+async fn test_machine_with_message_flood() {
+  let (handles, highest) =
+    TestNetwork::new(4, SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()).await;
+
+  // Flood the first validator with messages faster than it can handle them
+  // These have invalid signatures, so if they were handled, they'd simply be discarded
+  let junk = SignedMessageFor::<TestNetwork> {
+    msg: Message {
+      sender: 1,
+      block: BlockNumber(1),
+      round: RoundNumber(0),
+      data: Data::Prevote(None),
+    },
+    sig: [0; 32],
+  };
+  let flood = TestNetwork::MAX_IN_FLIGHT_MESSAGES * 4;
+  let mut queued = 0;
+  {
+    let messages = &mut handles.write().await[0].0;
+    for _ in 0 .. flood {
+      if messages.send(junk.clone()).unwrap() {
+        queued += 1;
+      }
+    }
+  }
+  // The amount of queued messages should be bounded, with the excess dropped
+  // The channel additionally guarantees a slot to each sender, of which there's only one
+  assert!(queued <= (TestNetwork::MAX_IN_FLIGHT_MESSAGES + 1));
+
+  // The network should still make progress
+  wait_for_block_after(&highest, 2).await;
 }
@@ -3,6 +3,10 @@ use std::collections::HashMap;
 
 use rand_core::OsRng;
 
+use thiserror::Error;
+
+use scale::Encode;
+
 use frost::{
   curve::Ristretto,
   ThresholdKeys, FrostError,
@@ -18,7 +22,7 @@ use log::{info, debug, warn};
 
 use serai_client::{
   primitives::{NetworkId, BlockHash},
-  in_instructions::primitives::{Batch, SignedBatch, batch_message},
+  in_instructions::primitives::{Batch, SignedBatch, MAX_BATCH_SIZE, batch_message},
   validator_sets::primitives::Session,
 };
 
@@ -33,6 +37,16 @@ create_db!(
   }
 );
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Error)]
+pub enum BatchSignerError {
+  #[error("batch was for {batch:?} yet our keys are for {keys:?}")]
+  NetworkMismatch { keys: NetworkId, batch: NetworkId },
+  #[error("instruction {0} of the batch was invalid")]
+  InvalidInstruction(usize),
+  #[error("batch was {0} bytes, exceeding MAX_BATCH_SIZE")]
+  BatchTooLarge(usize),
+}
+
 type Preprocess = <AlgorithmMachine<Ristretto, Schnorrkel> as PreprocessMachine>::Preprocess;
 type SignatureShare = <AlgorithmSignMachine<Ristretto, Schnorrkel> as SignMachine<
   <Schnorrkel as Algorithm<Ristretto>>::Signature,
@@ -110,6 +124,25 @@ impl<D: Db> BatchSigner<D> {
     Ok((*session, *id, *attempt))
   }
 
+  fn discard_attempt(&mut self, id: u32) {
+    let preprocessing = self.preprocessing.remove(&id).is_some();
+    let signing = self.signing.remove(&id).is_some();
+    if preprocessing || signing {
+      debug!("discarded signing state for batch {id}");
+    }
+  }
+
+  /// The attempt in progress for a batch, if any, and how many attempts' machines are retained for
+  /// it.
+  #[cfg(test)]
+  pub(crate) fn attempt_state(&self, id: u32) -> (Option<u32>, usize) {
+    (
+      self.attempt.get(&id).copied(),
+      usize::from(self.preprocessing.contains_key(&id)) +
+        usize::from(self.signing.contains_key(&id)),
+    )
+  }
+
   #[must_use]
   fn attempt(
     &mut self,
@@ -138,9 +171,8 @@ impl<D: Db> BatchSigner<D> {
       return None;
     };
 
-    // Delete any existing machines
-    self.preprocessing.remove(&id);
-    self.signing.remove(&id);
+    // Discard the prior attempt's machines, which this attempt supersedes
+    self.discard_attempt(id);
 
     // Update the attempt number
     self.attempt.insert(id, attempt);
@@ -188,18 +220,49 @@ impl<D: Db> BatchSigner<D> {
     Some(ProcessorMessage::BatchPreprocess { id, block, preprocesses: serialized_preprocesses })
   }
 
-  #[must_use]
-  pub fn sign(&mut self, txn: &mut D::Transaction<'_>, batch: Batch) -> Option<ProcessorMessage> {
-    debug_assert_eq!(self.network, batch.network);
+  // Check the batch fits within MAX_BATCH_SIZE and every instruction is for a coin native to the
+  // batch's network, as Serai would reject the batch otherwise
+  //
+  // Instructions which fail when executed, such as swaps Serai can't perform, don't cause the
+  // batch to be rejected, solely that instruction to fail, so they aren't checked here
+  fn verify_instructions(batch: &Batch) -> Result<(), BatchSignerError> {
+    let size = batch.encode().len();
+    if size > MAX_BATCH_SIZE {
+      Err(BatchSignerError::BatchTooLarge(size))?;
+    }
+
+    for (i, instruction) in batch.instructions.iter().enumerate() {
+      if instruction.balance.coin.network() != batch.network {
+        Err(BatchSignerError::InvalidInstruction(i))?;
+      }
+    }
+    Ok(())
+  }
+
+  /// Start signing a batch, returning our preprocesses for it.
+  ///
+  /// Batches for a network other than the one our keys are for are rejected, as signing them
+  /// would let a batch be attributed to the wrong network. Batches exceeding MAX_BATCH_SIZE, or
+  /// with an instruction for a coin from another network, are also rejected.
+  pub fn sign(
+    &mut self,
+    txn: &mut D::Transaction<'_>,
+    batch: Batch,
+  ) -> Result<Option<ProcessorMessage>, BatchSignerError> {
+    if batch.network != self.network {
+      Err(BatchSignerError::NetworkMismatch { keys: self.network, batch: batch.network })?;
+    }
+    Self::verify_instructions(&batch)?;
+
     let id = batch.id;
     if CompletedDb::get(txn, id).is_some() {
       debug!("Sign batch order for ID we've already completed signing");
       // See batch_signed for commentary on why this simply returns
-      return None;
+      return Ok(None);
     }
 
     self.signable.insert(id, batch);
-    self.attempt(txn, id, 0)
+    Ok(self.attempt(txn, id, 0))
   }
 
   #[must_use]
@@ -405,8 +468,7 @@ impl<D: Db> BatchSigner<D> {
 
     self.signable.remove(&id);
     self.attempt.remove(&id);
-    self.preprocessing.remove(&id);
-    self.signing.remove(&id);
+    self.discard_attempt(id);
 
     // This doesn't emit SignedBatch because it doesn't have access to the SignedBatch
     // This function is expected to only be called once Substrate acknowledges this block,
@@ -0,0 +1,9 @@
+/// If a network's first key pair, confirmed by Serai at `serai_time`, is active as of a block
+/// whose network time is `block_time`.
+///
+/// The first key pair is activated by the first block whose time is greater than or equal to the
+/// Serai time it was confirmed at. As network time is monotonic, the key pair is also active for
+/// every block after it. This rule is the same for every network.
+pub fn key_active_at(block_time: u64, serai_time: u64) -> bool {
+  block_time >= serai_time
+}
@@ -5,3 +5,6 @@ pub mod networks;
 
 mod additional_key;
 pub use additional_key::additional_key;
+
+mod key_activation;
+pub use key_activation::key_active_at;
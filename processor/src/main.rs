@@ -37,6 +37,9 @@ use networks::Monero;
 mod additional_key;
 pub use additional_key::additional_key;
 
+mod key_activation;
+use key_activation::key_active_at;
+
 mod db;
 pub use db::*;
 
@@ -331,7 +334,7 @@ async fn handle_coordinator_msg<D: Db, N: Network, Co: Coordinator>(
             // We can't check this as existing is no longer pub
             // assert!(substrate_mutable.existing.as_ref().is_none());
 
-            // Wait until a network's block's time exceeds Serai's time
+            // Wait until a network's block has the key pair active, per its time and Serai's time
             // These time calls are extremely expensive for what they do, yet they only run when
             // confirming the first key pair, before any network activity has occurred, so they
             // should be fine
@@ -342,7 +345,10 @@ async fn handle_coordinator_msg<D: Db, N: Network, Co: Coordinator>(
             while {
               block_i = (network.get_latest_block_number_with_retries().await + 1)
                 .saturating_sub(N::CONFIRMATIONS);
-              network.get_block_with_retries(block_i).await.time(network).await < context.serai_time
+              !key_active_at(
+                network.get_block_with_retries(block_i).await.time(network).await,
+                context.serai_time,
+              )
             } {
               info!(
                 "serai confirmed the first key pair for a set. {} {}",
@@ -358,8 +364,10 @@ async fn handle_coordinator_msg<D: Db, N: Network, Co: Coordinator>(
             // which... should be impossible
             // Yet a prevented panic is a prevented panic
             while (earliest > 0) &&
-              (network.get_block_with_retries(earliest - 1).await.time(network).await >=
-                context.serai_time)
+              key_active_at(
+                network.get_block_with_retries(earliest - 1).await.time(network).await,
+                context.serai_time,
+              )
             {
               earliest -= 1;
             }
@@ -656,8 +664,10 @@ async fn run<N: Network, D: Db, Co: Coordinator>(mut raw_db: D, network: N, mut
               ).await;
 
               if let Some(batch_signer) = tributary_mutable.batch_signer.as_mut() {
-                if let Some(msg) = batch_signer.sign(&mut txn, batch) {
-                  coordinator.send(msg).await;
+                match batch_signer.sign(&mut txn, batch) {
+                  Ok(Some(msg)) => coordinator.send(msg).await,
+                  Ok(None) => {}
+                  Err(e) => log::error!("refusing to sign batch: {e}"),
                 }
               }
             }
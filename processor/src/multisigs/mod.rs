@@ -3,13 +3,13 @@ use std::collections::HashSet;
 
 use ciphersuite::{group::GroupEncoding, Ciphersuite};
 
-use scale::{Encode, Decode};
+use scale::Encode;
 use messages::SubstrateContext;
 
 use serai_client::{
-  primitives::{MAX_DATA_LEN, NetworkId, Coin, ExternalAddress, BlockHash, Data},
+  primitives::{MAX_DATA_LEN, Coin, ExternalAddress, BlockHash, Data},
   in_instructions::primitives::{
-    InInstructionWithBalance, Batch, RefundableInInstruction, Shorthand, MAX_BATCH_SIZE,
+    InInstructionWithBalance, Batch, RefundableInInstruction, MAX_BATCH_SIZE,
   },
   coins::primitives::{OutInstruction, OutInstructionWithBalance},
 };
@@ -23,7 +23,7 @@ mod scanner;
 #[cfg(test)]
 pub mod scanner;
 
-use scanner::{ScannerEvent, ScannerHandle, Scanner};
+use scanner::{ScannerEvent, ScannerHandle, Scanner, outputs_grouped_by_transaction};
 
 mod db;
 use db::*;
@@ -55,7 +55,7 @@ fn instruction_from_output<N: Network>(
     .expect("presumed origin exceeded address limits")
   });
 
-  let mut data = output.data();
+  let data = output.data();
   let max_data_len = usize::try_from(MAX_DATA_LEN).unwrap();
   if data.len() > max_data_len {
     error!(
@@ -66,8 +66,7 @@ fn instruction_from_output<N: Network>(
     return (presumed_origin, None);
   }
 
-  let Ok(shorthand) = Shorthand::decode(&mut data) else { return (presumed_origin, None) };
-  let Ok(instruction) = RefundableInInstruction::try_from(shorthand) else {
+  let Some(instruction) = RefundableInInstruction::decode_from_deposit(data) else {
     return (presumed_origin, None);
   };
 
@@ -158,18 +157,8 @@ impl<D: Db, N: Network> MultisigManager<D, N> {
     let mut actively_signing = vec![];
     for (_, key) in &current_keys {
       schedulers.push(
-        Scheduler::from_db(
-          raw_db,
-          *key,
-          match N::NETWORK {
-            NetworkId::Serai => panic!("adding a key for Serai"),
-            NetworkId::Bitcoin => Coin::Bitcoin,
-            // TODO: This is incomplete to DAI
-            NetworkId::Ethereum => Coin::Ether,
-            NetworkId::Monero => Coin::Monero,
-          },
-        )
-        .unwrap(),
+        // TODO: This is incomplete to DAI
+        Scheduler::from_db(raw_db, *key, Coin::native_for(N::NETWORK)).unwrap(),
       );
 
       // Load any TXs being actively signed
@@ -245,17 +234,8 @@ impl<D: Db, N: Network> MultisigManager<D, N> {
     let viewer = Some(MultisigViewer {
       activation_block,
       key: external_key,
-      scheduler: Scheduler::<N>::new::<D>(
-        txn,
-        external_key,
-        match N::NETWORK {
-          NetworkId::Serai => panic!("adding a key for Serai"),
-          NetworkId::Bitcoin => Coin::Bitcoin,
-          // TODO: This is incomplete to DAI
-          NetworkId::Ethereum => Coin::Ether,
-          NetworkId::Monero => Coin::Monero,
-        },
-      ),
+      // TODO: This is incomplete to DAI
+      scheduler: Scheduler::<N>::new::<D>(txn, external_key, Coin::native_for(N::NETWORK)),
     });
 
     if self.existing.is_none() {
@@ -810,6 +790,12 @@ impl<D: Db, N: Network> MultisigManager<D, N> {
         // Accordingly, the following calls regarding new keys and step should be safe
         let block_number = ScannerHandle::<N, D>::block_number(txn, &block)
           .expect("didn't have the block number for a block we just scanned");
+        // The instructions created from these outputs must be in the same order for every
+        // processor, which is why the scanner canonicalized them
+        debug_assert!(
+          outputs_grouped_by_transaction::<N>(&outputs),
+          "scanner yielded outputs which weren't in their canonical order"
+        );
         let step = self.current_rotation_step(block_number);
 
         // Instructions created from this block
@@ -20,6 +20,52 @@ use crate::{
   networks::{Output, Transaction, EventualitiesTracker, Block, Network},
 };
 
+/// Sort the outputs scanned from a block into their canonical order, the order they appear in
+/// within the block (by their transaction's position, then their index within it).
+///
+/// Every processor must build identical batches in order to sign them, so the order of a batch's
+/// instructions can't depend on the order their outputs happened to be discovered in (such as
+/// which of the multisigs' keys was scanned for first).
+pub(crate) fn canonicalize_outputs<N: Network>(block: &N::Block, outputs: &mut [N::Output]) {
+  let positions = block
+    .txs()
+    .into_iter()
+    .enumerate()
+    .map(|(i, tx)| (tx.as_ref().to_vec(), i))
+    .collect::<HashMap<_, _>>();
+  outputs.sort_by_cached_key(|output| {
+    let tx = *positions
+      .get(output.tx_id().as_ref())
+      .expect("scanned an output whose transaction wasn't in the block");
+    (tx, output.index())
+  });
+}
+
+/// If outputs are in their canonical order, to the extent checkable without their block.
+///
+/// This checks each transaction's outputs are contiguous and in ascending order by index.
+pub(crate) fn outputs_grouped_by_transaction<N: Network>(outputs: &[N::Output]) -> bool {
+  let mut prior_txs = HashSet::new();
+  let mut last: Option<&N::Output> = None;
+  for output in outputs {
+    if let Some(last) = last {
+      if last.tx_id() == output.tx_id() {
+        if last.index() >= output.index() {
+          return false;
+        }
+      } else {
+        // Outputs from a transaction must not follow outputs from a later transaction
+        prior_txs.insert(last.tx_id().as_ref().to_vec());
+        if prior_txs.contains(output.tx_id().as_ref()) {
+          return false;
+        }
+      }
+    }
+    last = Some(output);
+  }
+  true
+}
+
 #[derive(Clone, Debug)]
 pub enum ScannerEvent<N: Network> {
   // Block scanned
@@ -573,6 +619,10 @@ impl<N: Network, D: Db> Scanner<N, D> {
           }
         }
 
+        // Sort the outputs, as they were found key-by-key, so every processor yields them in the
+        // same order
+        canonicalize_outputs::<N>(&block, &mut outputs);
+
         // Panic if we've already seen these outputs
         for output in &outputs {
           let id = output.id();
@@ -44,7 +44,7 @@ use bitcoin_serai::bitcoin::{
 };
 
 use serai_client::{
-  primitives::{MAX_DATA_LEN, Coin, NetworkId, Amount, Balance},
+  primitives::{MAX_DATA_LEN, Coin, NetworkId, Amount, Balance, confirmations},
   networks::bitcoin::Address,
 };
 
@@ -110,6 +110,10 @@ impl OutputTrait<Bitcoin> for Output {
     hash
   }
 
+  fn index(&self) -> u32 {
+    self.output.outpoint().vout
+  }
+
   fn key(&self) -> ProjectivePoint {
     let script = &self.output.output().script_pubkey;
     assert!(script.is_p2tr());
@@ -258,6 +262,10 @@ impl BlockTrait<Bitcoin> for Block {
     hash
   }
 
+  fn txs(&self) -> Vec<[u8; 32]> {
+    self.txdata.iter().map(<Transaction as TransactionTrait<Bitcoin>>::id).collect()
+  }
+
   async fn time(&self, rpc: &Bitcoin) -> u64 {
     // Use the network median time defined in BIP-0113 since the in-block time isn't guaranteed to
     // be monotonic
@@ -554,7 +562,8 @@ impl Network for Bitcoin {
   const NETWORK: NetworkId = NetworkId::Bitcoin;
   const ID: &'static str = "Bitcoin";
   const ESTIMATED_BLOCK_TIME_IN_SECONDS: usize = 600;
-  const CONFIRMATIONS: usize = 6;
+  #[allow(clippy::cast_possible_truncation)]
+  const CONFIRMATIONS: usize = confirmations(Self::NETWORK) as usize;
 
   /*
     A Taproot input is:
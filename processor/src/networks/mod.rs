@@ -106,6 +106,8 @@ pub trait Output<N: Network>: Send + Sync + Sized + Clone + PartialEq + Eq + Deb
 
   fn id(&self) -> Self::Id;
   fn tx_id(&self) -> <N::Transaction as Transaction<N>>::Id;
+  /// The index of this output within its transaction.
+  fn index(&self) -> u32;
   fn key(&self) -> <N::Curve as Ciphersuite>::G;
 
   fn presumed_origin(&self) -> Option<N::Address>;
@@ -192,6 +194,8 @@ pub trait Block<N: Network>: Send + Sync + Sized + Clone + Debug {
   type Id: 'static + Id;
   fn id(&self) -> Self::Id;
   fn parent(&self) -> Self::Id;
+  /// The IDs of the transactions within this block, in the order they were included.
+  fn txs(&self) -> Vec<<N::Transaction as Transaction<N>>::Id>;
   /// The monotonic network time at this block.
   ///
   /// This call is presumed to be expensive and should only be called sparingly.
@@ -268,6 +272,8 @@ pub trait Network: 'static + Send + Sync + Clone + PartialEq + Eq + Debug {
   /// The estimated amount of time a block will take.
   const ESTIMATED_BLOCK_TIME_IN_SECONDS: usize;
   /// The amount of confirmations required to consider a block 'final'.
+  ///
+  /// This should be the amount Serai defines for this network, as `confirmations` returns.
   const CONFIRMATIONS: usize;
   /// The maximum amount of inputs which will fit in a TX.
   /// This should be equal to MAX_OUTPUTS unless one is specifically limited.
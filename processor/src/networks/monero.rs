@@ -26,13 +26,17 @@ use monero_serai::{
     SignableTransaction as MSignableTransaction, Eventuality, TransactionMachine,
   },
 };
+#[cfg(test)]
+use monero_serai::wallet::ReceivedOutput;
 
 use tokio::time::sleep;
 
 pub use serai_client::{
-  primitives::{MAX_DATA_LEN, Coin, NetworkId, Amount, Balance},
+  primitives::{MAX_DATA_LEN, Coin, NetworkId, Amount, Balance, confirmations},
   networks::monero::Address,
 };
+#[cfg(test)]
+use serai_client::in_instructions::primitives::RefundableInInstruction;
 
 use crate::{
   Payment, additional_key,
@@ -46,6 +50,20 @@ use crate::{
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Output(SpendableOutput, Vec<u8>);
 
+// The data for an output, from the first arbitrary data segment in its transaction's extra
+fn output_data(arbitrary_data: &[Vec<u8>]) -> Vec<u8> {
+  let mut data = arbitrary_data.first().cloned().unwrap_or(vec![]);
+
+  // The Output serialization code below uses u16 to represent length
+  data.truncate(u16::MAX.into());
+  // Monero data segments should be <= 255 already, and MAX_DATA_LEN is currently 512
+  // This just allows either Monero to change, or MAX_DATA_LEN to change, without introducing
+  // complicationso
+  data.truncate(MAX_DATA_LEN.try_into().unwrap());
+
+  data
+}
+
 const EXTERNAL_SUBADDRESS: Option<SubaddressIndex> = SubaddressIndex::new(0, 0);
 const BRANCH_SUBADDRESS: Option<SubaddressIndex> = SubaddressIndex::new(1, 0);
 const CHANGE_SUBADDRESS: Option<SubaddressIndex> = SubaddressIndex::new(2, 0);
@@ -75,6 +93,10 @@ impl OutputTrait<Monero> for Output {
     self.0.output.absolute.tx
   }
 
+  fn index(&self) -> u32 {
+    u32::from(self.0.output.absolute.o)
+  }
+
   fn key(&self) -> EdwardsPoint {
     EdwardsPoint(self.0.output.data.key - (EdwardsPoint::generator().0 * self.0.key_offset()))
   }
@@ -169,6 +191,10 @@ impl BlockTrait<Monero> for Block {
     self.header.previous
   }
 
+  fn txs(&self) -> Vec<[u8; 32]> {
+    core::iter::once(self.miner_tx.hash()).chain(self.txs.iter().copied()).collect()
+  }
+
   async fn time(&self, rpc: &Monero) -> u64 {
     // Constant from Monero
     const BLOCKCHAIN_TIMESTAMP_CHECK_WINDOW: u64 = 60;
@@ -440,6 +466,87 @@ impl Monero {
     Address::new(Self::test_view_pair().address(MoneroNetwork::Mainnet, AddressSpec::Standard))
       .unwrap()
   }
+
+  /// Scan a transaction for deposits to the specified key, decoding the InInstruction within each
+  /// deposit's data, if it has a valid one, as the data of the outputs from `get_outputs` is
+  /// decoded when creating batches.
+  ///
+  /// Solely unlocked outputs received to the external address are returned.
+  #[cfg(test)]
+  pub(crate) fn scan_deposits(
+    key: EdwardsPoint,
+    tx: &Transaction,
+  ) -> Vec<(ReceivedOutput, Option<RefundableInInstruction>)> {
+    Self::scanner(key)
+      .scan_transaction(tx)
+      .not_locked()
+      .into_iter()
+      .filter(|output| output.metadata.subaddress == EXTERNAL_SUBADDRESS)
+      .map(|output| {
+        let instruction =
+          RefundableInInstruction::decode_from_deposit(&output_data(output.arbitrary_data()));
+        (output, instruction)
+      })
+      .collect()
+  }
+
+  /// Send to the specified address with the specified arbitrary data, as `test_send` does.
+  #[cfg(test)]
+  pub(crate) async fn test_send_with_data(&self, address: Address, data: Vec<Vec<u8>>) -> Block {
+    use zeroize::Zeroizing;
+    use rand_core::OsRng;
+    use monero_serai::wallet::FeePriority;
+
+    let new_block = self.get_latest_block_number().await.unwrap() + 1;
+    for _ in 0 .. 80 {
+      self.mine_block().await;
+    }
+
+    let outputs = Self::test_scanner()
+      .scan(&self.rpc, &self.rpc.get_block_by_number(new_block).await.unwrap())
+      .await
+      .unwrap()
+      .swap_remove(0)
+      .ignore_timelock();
+
+    let amount = outputs[0].commitment().amount;
+    // The dust should always be sufficient for the fee
+    let fee = Monero::DUST;
+
+    let protocol = self.rpc.get_protocol().await.unwrap();
+
+    let decoys = Decoys::fingerprintable_canonical_select(
+      &mut OsRng,
+      &self.rpc,
+      protocol.ring_len(),
+      self.rpc.get_height().await.unwrap(),
+      &outputs,
+    )
+    .await
+    .unwrap();
+
+    let inputs = outputs.into_iter().zip(decoys).collect::<Vec<_>>();
+
+    let tx = MSignableTransaction::new(
+      protocol,
+      None,
+      inputs,
+      vec![(address.into(), amount - fee)],
+      &Change::fingerprintable(Some(Self::test_address().into())),
+      data,
+      self.rpc.get_fee(protocol, FeePriority::Unimportant).await.unwrap(),
+    )
+    .unwrap()
+    .sign(&mut OsRng, &Zeroizing::new(Scalar::ONE.0))
+    .unwrap();
+
+    let block = self.get_latest_block_number().await.unwrap() + 1;
+    self.rpc.publish_transaction(&tx).await.unwrap();
+    for _ in 0 .. 10 {
+      self.mine_block().await;
+    }
+    self.get_block(block).await.unwrap()
+  }
 }
 
 #[async_trait]
@@ -459,7 +566,8 @@ impl Network for Monero {
   const NETWORK: NetworkId = NetworkId::Monero;
   const ID: &'static str = "Monero";
   const ESTIMATED_BLOCK_TIME_IN_SECONDS: usize = 120;
-  const CONFIRMATIONS: usize = 10;
+  #[allow(clippy::cast_possible_truncation)]
+  const CONFIRMATIONS: usize = confirmations(Self::NETWORK) as usize;
 
   // wallet2 will not create a transaction larger than 100kb, and Monero won't relay a transaction
   // larger than 150kb. This fits within the 100kb mark
@@ -538,15 +646,7 @@ impl Network for Monero {
     let mut outputs = Vec::with_capacity(txs.len());
     for mut tx_outputs in txs.drain(..) {
       for output in tx_outputs.drain(..) {
-        let mut data = output.arbitrary_data().first().cloned().unwrap_or(vec![]);
-
-        // The Output serialization code above uses u16 to represent length
-        data.truncate(u16::MAX.into());
-        // Monero data segments should be <= 255 already, and MAX_DATA_LEN is currently 512
-        // This just allows either Monero to change, or MAX_DATA_LEN to change, without introducing
-        // complicationso
-        data.truncate(MAX_DATA_LEN.try_into().unwrap());
-
+        let data = output_data(output.arbitrary_data());
         outputs.push(Output(output, data));
       }
     }
@@ -720,58 +820,6 @@ impl Network for Monero {
 
   #[cfg(test)]
   async fn test_send(&self, address: Address) -> Block {
-    use zeroize::Zeroizing;
-    use rand_core::OsRng;
-    use monero_serai::wallet::FeePriority;
-
-    let new_block = self.get_latest_block_number().await.unwrap() + 1;
-    for _ in 0 .. 80 {
-      self.mine_block().await;
-    }
-
-    let outputs = Self::test_scanner()
-      .scan(&self.rpc, &self.rpc.get_block_by_number(new_block).await.unwrap())
-      .await
-      .unwrap()
-      .swap_remove(0)
-      .ignore_timelock();
-
-    let amount = outputs[0].commitment().amount;
-    // The dust should always be sufficient for the fee
-    let fee = Monero::DUST;
-
-    let protocol = self.rpc.get_protocol().await.unwrap();
-
-    let decoys = Decoys::fingerprintable_canonical_select(
-      &mut OsRng,
-      &self.rpc,
-      protocol.ring_len(),
-      self.rpc.get_height().await.unwrap(),
-      &outputs,
-    )
-    .await
-    .unwrap();
-
-    let inputs = outputs.into_iter().zip(decoys).collect::<Vec<_>>();
-
-    let tx = MSignableTransaction::new(
-      protocol,
-      None,
-      inputs,
-      vec![(address.into(), amount - fee)],
-      &Change::fingerprintable(Some(Self::test_address().into())),
-      vec![],
-      self.rpc.get_fee(protocol, FeePriority::Unimportant).await.unwrap(),
-    )
-    .unwrap()
-    .sign(&mut OsRng, &Zeroizing::new(Scalar::ONE.0))
-    .unwrap();
-
-    let block = self.get_latest_block_number().await.unwrap() + 1;
-    self.rpc.publish_transaction(&tx).await.unwrap();
-    for _ in 0 .. 10 {
-      self.mine_block().await;
-    }
-    self.get_block(block).await.unwrap()
+    self.test_send_with_data(address, vec![]).await
   }
 }
@@ -9,6 +9,8 @@ use frost::{
   dkg::tests::{key_gen, clone_without},
 };
 
+use scale::Encode;
+
 use sp_application_crypto::{RuntimePublic, sr25519::Public};
 
 use serai_db::{DbTxn, Db, MemDb};
@@ -21,7 +23,7 @@ use messages::{
   coordinator::{self, SubstrateSignableId, SubstrateSignId, CoordinatorMessage},
   ProcessorMessage,
 };
-use crate::batch_signer::BatchSigner;
+use crate::batch_signer::{BatchSignerError, BatchSigner};
 
 #[test]
 fn test_batch_signer() {
@@ -39,7 +41,7 @@ fn test_batch_signer() {
     instructions: vec![
       InInstructionWithBalance {
         instruction: InInstruction::Transfer(SeraiAddress([0xbb; 32])),
-        balance: Balance { coin: Coin::Bitcoin, amount: Amount(1000) },
+        balance: Balance { coin: Coin::Monero, amount: Amount(1000) },
       },
       InInstructionWithBalance {
         instruction: InInstruction::Dex(DexCall::SwapAndAddLiquidity(SeraiAddress([0xbb; 32]))),
@@ -74,7 +76,7 @@ fn test_batch_signer() {
     let mut db = MemDb::new();
 
     let mut txn = db.txn();
-    match signer.sign(&mut txn, batch.clone()).unwrap() {
+    match signer.sign(&mut txn, batch.clone()).unwrap().unwrap() {
       // All participants should emit a preprocess
       coordinator::ProcessorMessage::BatchPreprocess {
         id,
@@ -150,3 +152,205 @@ fn test_batch_signer() {
     txn.commit();
   }
 }
+
+#[test]
+fn test_batch_signer_reattempts() {
+  let keys = key_gen::<_, Ristretto>(&mut OsRng);
+
+  let id: u32 = 5;
+  let batch =
+    Batch { network: NetworkId::Monero, id, block: BlockHash([0xaa; 32]), instructions: vec![] };
+  let sign_id =
+    |attempt| SubstrateSignId { session: Session(0), id: SubstrateSignableId::Batch(id), attempt };
+
+  let mut signers = HashMap::new();
+  let mut dbs = HashMap::new();
+  for (i, keys) in &keys {
+    let mut signer = BatchSigner::<MemDb>::new(NetworkId::Monero, Session(0), vec![keys.clone()]);
+    let mut db = MemDb::new();
+    let mut txn = db.txn();
+    assert!(signer.sign(&mut txn, batch.clone()).unwrap().is_some());
+    txn.commit();
+    assert_eq!(signer.attempt_state(id), (Some(0), 1));
+    signers.insert(*i, signer);
+    dbs.insert(*i, db);
+  }
+
+  for attempt in 1 ..= 3 {
+    let mut preprocesses = HashMap::new();
+    for (i, signer) in &mut signers {
+      let mut txn = dbs.get_mut(i).unwrap().txn();
+      match signer
+        .handle(&mut txn, CoordinatorMessage::BatchReattempt { id: sign_id(attempt) })
+        .unwrap()
+      {
+        ProcessorMessage::Coordinator(coordinator::ProcessorMessage::BatchPreprocess {
+          id: preprocess_id,
+          preprocesses: mut these_preprocesses,
+          ..
+        }) => {
+          assert_eq!(preprocess_id, sign_id(attempt));
+          preprocesses.insert(*i, these_preprocesses.swap_remove(0));
+        }
+        _ => panic!("didn't get preprocess back"),
+      }
+      txn.commit();
+
+      // Only the new attempt's state should be retained
+      assert_eq!(signer.attempt_state(id), (Some(attempt), 1));
+    }
+
+    // Advance the second attempt to signing so the next reattempt has to discard signing state
+    if attempt == 2 {
+      for (i, signer) in &mut signers {
+        let mut txn = dbs.get_mut(i).unwrap().txn();
+        assert!(signer
+          .handle(
+            &mut txn,
+            CoordinatorMessage::SubstratePreprocesses {
+              id: sign_id(attempt),
+              preprocesses: clone_without(&preprocesses, i),
+            },
+          )
+          .is_some());
+        txn.commit();
+        assert_eq!(signer.attempt_state(id), (Some(attempt), 1));
+      }
+    }
+  }
+
+  // A stale reattempt is ignored, leaving the latest attempt's state in place
+  for (i, signer) in &mut signers {
+    let mut txn = dbs.get_mut(i).unwrap().txn();
+    assert!(signer
+      .handle(&mut txn, CoordinatorMessage::BatchReattempt { id: sign_id(1) })
+      .is_none());
+    txn.commit();
+    assert_eq!(signer.attempt_state(id), (Some(3), 1));
+  }
+
+  // Once the batch is signed, no state is retained
+  for (i, signer) in &mut signers {
+    let mut txn = dbs.get_mut(i).unwrap().txn();
+    signer.batch_signed(&mut txn, id);
+    txn.commit();
+    assert_eq!(signer.attempt_state(id), (None, 0));
+  }
+}
+
+#[test]
+fn test_batch_signer_network_mismatch() {
+  let keys = key_gen::<_, Ristretto>(&mut OsRng);
+  let keys = keys.values().next().unwrap().clone();
+
+  let id: u32 = 5;
+  let batch =
+    Batch { network: NetworkId::Bitcoin, id, block: BlockHash([0xaa; 32]), instructions: vec![] };
+
+  let mut signer = BatchSigner::<MemDb>::new(NetworkId::Monero, Session(0), vec![keys]);
+  let mut db = MemDb::new();
+  let mut txn = db.txn();
+  assert_eq!(
+    signer.sign(&mut txn, batch),
+    Err(BatchSignerError::NetworkMismatch { keys: NetworkId::Monero, batch: NetworkId::Bitcoin })
+  );
+  txn.commit();
+
+  // No attempt was started, so there's nothing to reattempt
+  assert_eq!(signer.attempt_state(id), (None, 0));
+  let mut txn = db.txn();
+  assert!(signer
+    .handle(
+      &mut txn,
+      CoordinatorMessage::BatchReattempt {
+        id: SubstrateSignId { session: Session(0), id: SubstrateSignableId::Batch(id), attempt: 1 },
+      },
+    )
+    .is_none());
+  txn.commit();
+}
+
+#[test]
+fn test_batch_signer_invalid_instruction() {
+  let keys = key_gen::<_, Ristretto>(&mut OsRng);
+  let keys = keys.values().next().unwrap().clone();
+
+  let valid = InInstructionWithBalance {
+    instruction: InInstruction::Transfer(SeraiAddress([0xbb; 32])),
+    balance: Balance { coin: Coin::Monero, amount: Amount(1000) },
+  };
+  // A coin from another network
+  let invalid = InInstructionWithBalance {
+    instruction: InInstruction::Transfer(SeraiAddress([0xbb; 32])),
+    balance: Balance { coin: Coin::Bitcoin, amount: Amount(1000) },
+  };
+  let id: u32 = 5;
+  let batch = Batch {
+    network: NetworkId::Monero,
+    id,
+    block: BlockHash([0xaa; 32]),
+    instructions: vec![valid.clone(), invalid, valid.clone()],
+  };
+
+  let mut db = MemDb::new();
+  let mut signer = BatchSigner::<MemDb>::new(NetworkId::Monero, Session(0), vec![keys.clone()]);
+  let mut txn = db.txn();
+  assert_eq!(signer.sign(&mut txn, batch), Err(BatchSignerError::InvalidInstruction(1)));
+  txn.commit();
+
+  // No attempt was started for the rejected batch
+  assert_eq!(signer.attempt_state(id), (None, 0));
+
+  // Instructions which will fail when executed don't stop Serai from executing the batch, so the
+  // batch is still signed
+  let failing = InInstructionWithBalance {
+    instruction: InInstruction::Dex(DexCall::Swap(
+      Balance { coin: Coin::Monero, amount: Amount(1) },
+      OutAddress::External(ExternalAddress::new(vec![]).unwrap()),
+    )),
+    balance: Balance { coin: Coin::Monero, amount: Amount(0) },
+  };
+  let batch = Batch {
+    network: NetworkId::Monero,
+    id,
+    block: BlockHash([0xaa; 32]),
+    instructions: vec![valid.clone(), failing, valid],
+  };
+  let mut txn = db.txn();
+  assert!(signer.sign(&mut txn, batch).unwrap().is_some());
+  txn.commit();
+  assert_eq!(signer.attempt_state(id), (Some(0), 1));
+}
+
+#[test]
+fn test_batch_signer_batch_too_large() {
+  let keys = key_gen::<_, Ristretto>(&mut OsRng);
+  let keys = keys.values().next().unwrap().clone();
+
+  // Swaps to the largest external addresses, valid individually yet not when this many are batched
+  let instruction = InInstructionWithBalance {
+    instruction: InInstruction::Dex(DexCall::Swap(
+      Balance { coin: Coin::Bitcoin, amount: Amount(1) },
+      OutAddress::External(
+        ExternalAddress::new(vec![0xbb; usize::try_from(MAX_ADDRESS_LEN).unwrap()]).unwrap(),
+      ),
+    )),
+    balance: Balance { coin: Coin::Monero, amount: Amount(1000) },
+  };
+  let id: u32 = 5;
+  let batch = Batch {
+    network: NetworkId::Monero,
+    id,
+    block: BlockHash([0xaa; 32]),
+    instructions: vec![instruction; MAX_BATCH_SIZE / usize::try_from(MAX_ADDRESS_LEN).unwrap()],
+  };
+  let size = batch.encode().len();
+  assert!(size > MAX_BATCH_SIZE);
+
+  let mut db = MemDb::new();
+  let mut signer = BatchSigner::<MemDb>::new(NetworkId::Monero, Session(0), vec![keys]);
+  let mut txn = db.txn();
+  assert_eq!(signer.sign(&mut txn, batch), Err(BatchSignerError::BatchTooLarge(size)));
+  txn.commit();
+  assert_eq!(signer.attempt_state(id), (None, 0));
+}
@@ -0,0 +1,102 @@
+#[cfg(feature = "monero")]
+mod monero {
+  use rand_core::OsRng;
+
+  use ciphersuite::group::{Group, GroupEncoding};
+  use dalek_ff_group::EdwardsPoint;
+
+  use monero_serai::{
+    transaction::{Timelock, Input, TransactionPrefix, Transaction},
+    ringct::{RctBase, RctPrunable, RctSignatures},
+    block::{BlockHeader, Block},
+  };
+
+  use crate::{
+    networks::{
+      Output as OutputTrait,
+      monero::{Monero, Output},
+    },
+    multisigs::scanner::{canonicalize_outputs, outputs_grouped_by_transaction},
+  };
+
+  // A block with a miner transaction, followed by transactions whose hashes aren't in order
+  fn block() -> Block {
+    Block {
+      header: BlockHeader {
+        major_version: 16,
+        minor_version: 16,
+        timestamp: 0,
+        previous: [0; 32],
+        nonce: 0,
+      },
+      miner_tx: Transaction {
+        prefix: TransactionPrefix {
+          version: 2,
+          timelock: Timelock::None,
+          inputs: vec![Input::Gen(1)],
+          outputs: vec![],
+          extra: vec![],
+        },
+        signatures: vec![],
+        rct_signatures: RctSignatures {
+          base: RctBase {
+            fee: 0,
+            pseudo_outs: vec![],
+            encrypted_amounts: vec![],
+            commitments: vec![],
+          },
+          prunable: RctPrunable::Null,
+        },
+      },
+      txs: vec![[0xff; 32], [0x01; 32]],
+    }
+  }
+
+  // An output, as scanned, at the specified index within the specified transaction
+  fn output(tx: [u8; 32], o: u8) -> Output {
+    let mut serialized = tx.to_vec();
+    serialized.push(o);
+    // The output's key, with a random key so output IDs are in no particular order, then its key
+    // offset and commitment
+    serialized.extend(EdwardsPoint::random(&mut OsRng).to_bytes());
+    serialized.extend([0; 32]);
+    serialized.extend([0; 32]);
+    serialized.extend(1u64.to_le_bytes());
+    // Its metadata: the unique derivation, no subaddress, no payment ID, and no arbitrary data
+    serialized.extend([0xff, 1, 0, 0]);
+    serialized.extend(0u32.to_le_bytes());
+    // Its global index, then the data extracted from it
+    serialized.extend(0u64.to_le_bytes());
+    serialized.extend(0u16.to_le_bytes());
+    Output::read(&mut serialized.as_slice()).unwrap()
+  }
+
+  #[test]
+  fn test_canonical_output_order() {
+    let block = block();
+    let canonical = vec![
+      output(block.miner_tx.hash(), 0),
+      output([0xff; 32], 0),
+      output([0xff; 32], 1),
+      output([0x01; 32], 0),
+      output([0x01; 32], 2),
+    ];
+    assert!(outputs_grouped_by_transaction::<Monero>(&canonical));
+    let mut sorted = canonical.clone();
+    canonicalize_outputs::<Monero>(&block, &mut sorted);
+    assert_eq!(sorted, canonical);
+
+    // Two processors discovering the outputs in different orders, such as when scanning for
+    // multiple keys, should still yield them in the same order
+    let mut first = canonical.clone();
+    first.reverse();
+    let mut second = canonical.clone();
+    second.rotate_left(2);
+    for mut discovered in [first, second] {
+      assert!(!outputs_grouped_by_transaction::<Monero>(&discovered));
+      canonicalize_outputs::<Monero>(&block, &mut discovered);
+      assert!(outputs_grouped_by_transaction::<Monero>(&discovered));
+      assert_eq!(discovered, canonical);
+    }
+  }
+}
@@ -0,0 +1,12 @@
+use crate::key_activation::key_active_at;
+
+#[test]
+fn test_key_active_at() {
+  let serai_time = 1_700_000_000;
+  // Blocks prior to the Serai time don't have the key pair active
+  assert!(!key_active_at(0, serai_time));
+  assert!(!key_active_at(serai_time - 1, serai_time));
+  // The key pair is active as of the first block whose time equals the Serai time
+  assert!(key_active_at(serai_time, serai_time));
+  assert!(key_active_at(serai_time + 1, serai_time));
+}
@@ -230,6 +230,17 @@ mod bitcoin {
 
 #[cfg(feature = "monero")]
 mod monero {
+  use rand_core::OsRng;
+
+  use frost::Participant;
+
+  use scale::Encode;
+  use sp_application_crypto::Pair;
+  use serai_client::{
+    in_instructions::primitives::{RefundableInInstruction, Shorthand},
+    primitives::insecure_pair_from_name,
+  };
+
   use super::*;
   use crate::networks::{Network, Monero};
 
@@ -261,6 +272,37 @@ mod monero {
     monero
   }
 
+  #[test]
+  fn test_scan_deposits() {
+    let docker = spawn_monero();
+    docker.run(|ops| async move {
+      let monero = monero(&ops).await;
+
+      let key = frost::tests::key_gen::<_, <Monero as Network>::Curve>(&mut OsRng)
+        .remove(&Participant::new(1).unwrap())
+        .unwrap()
+        .group_key();
+
+      // Deposit with a transfer instruction
+      let serai_address = insecure_pair_from_name("alice").public();
+      let shorthand = Shorthand::transfer(None, serai_address.into());
+      let block =
+        monero.test_send_with_data(Monero::external_address(key), vec![shorthand.encode()]).await;
+
+      let mut deposits = vec![];
+      for hash in &block.txs {
+        let tx = monero.get_transaction(hash).await.unwrap();
+        deposits.extend(Monero::scan_deposits(key, &tx));
+      }
+
+      // Both the output and its instruction should've been found
+      assert_eq!(deposits.len(), 1);
+      let (output, instruction) = deposits.swap_remove(0);
+      assert!(output.commitment().amount > 0);
+      assert_eq!(instruction, Some(RefundableInInstruction::try_from(shorthand).unwrap()));
+    });
+  }
+
   test_network!(
     Monero,
     spawn_monero,
@@ -11,6 +11,8 @@ pub(crate) use signer::{sign, test_signer};
 
 mod cosigner;
 mod batch_signer;
+mod key_activation;
+mod canonical;
 
 mod wallet;
 pub(crate) use wallet::test_wallet;
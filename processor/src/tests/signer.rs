@@ -10,7 +10,7 @@ use frost::{
 use serai_db::{DbTxn, Db, MemDb};
 
 use serai_client::{
-  primitives::{NetworkId, Coin, Amount, Balance},
+  primitives::{Coin, Amount, Balance},
   validator_sets::primitives::Session,
 };
 
@@ -168,15 +168,7 @@ pub async fn test_signer<N: Network>(network: N) {
           payments: vec![Payment {
             address: N::external_address(key),
             data: None,
-            balance: Balance {
-              coin: match N::NETWORK {
-                NetworkId::Serai => panic!("test_signer called with Serai"),
-                NetworkId::Bitcoin => Coin::Bitcoin,
-                NetworkId::Ethereum => Coin::Ether,
-                NetworkId::Monero => Coin::Monero,
-              },
-              amount: Amount(amount),
-            },
+            balance: Balance { coin: Coin::native_for(N::NETWORK), amount: Amount(amount) },
           }],
           change: Some(N::change_address(key)),
         },
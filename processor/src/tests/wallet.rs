@@ -9,7 +9,7 @@ use tokio::time::timeout;
 use serai_db::{DbTxn, Db, MemDb};
 
 use serai_client::{
-  primitives::{NetworkId, Coin, Amount, Balance},
+  primitives::{Coin, Amount, Balance},
   validator_sets::primitives::Session,
 };
 
@@ -69,16 +69,7 @@ pub async fn test_wallet<N: Network>(network: N) {
   txn.commit();
 
   let mut txn = db.txn();
-  let mut scheduler = Scheduler::new::<MemDb>(
-    &mut txn,
-    key,
-    match N::NETWORK {
-      NetworkId::Serai => panic!("test_wallet called with Serai"),
-      NetworkId::Bitcoin => Coin::Bitcoin,
-      NetworkId::Ethereum => Coin::Ether,
-      NetworkId::Monero => Coin::Monero,
-    },
-  );
+  let mut scheduler = Scheduler::new::<MemDb>(&mut txn, key, Coin::native_for(N::NETWORK));
   let amount = 2 * N::DUST;
   let plans = scheduler.schedule::<MemDb>(
     &mut txn,
@@ -86,15 +77,7 @@ pub async fn test_wallet<N: Network>(network: N) {
     vec![Payment {
       address: N::external_address(key),
       data: None,
-      balance: Balance {
-        coin: match N::NETWORK {
-          NetworkId::Serai => panic!("test_wallet called with Serai"),
-          NetworkId::Bitcoin => Coin::Bitcoin,
-          NetworkId::Ethereum => Coin::Ether,
-          NetworkId::Monero => Coin::Monero,
-        },
-        amount: Amount(amount),
-      },
+      balance: Balance { coin: Coin::native_for(N::NETWORK), amount: Amount(amount) },
     }],
     key,
     false,
@@ -108,15 +91,7 @@ pub async fn test_wallet<N: Network>(network: N) {
       payments: vec![Payment {
         address: N::external_address(key),
         data: None,
-        balance: Balance {
-          coin: match N::NETWORK {
-            NetworkId::Serai => panic!("test_wallet called with Serai"),
-            NetworkId::Bitcoin => Coin::Bitcoin,
-            NetworkId::Ethereum => Coin::Ether,
-            NetworkId::Monero => Coin::Monero,
-          },
-          amount: Amount(amount),
-        }
+        balance: Balance { coin: Coin::native_for(N::NETWORK), amount: Amount(amount) }
       }],
       change: Some(N::change_address(key)),
     }]
@@ -1,9 +1,10 @@
 pub use serai_abi::in_instructions::primitives;
-use primitives::SignedBatch;
+use primitives::{SignedBatch, batch_message};
 
 use crate::{
   primitives::{BlockHash, NetworkId},
-  Transaction, SeraiError, Serai, TemporalSerai,
+  validator_sets::primitives::{Session, ValidatorSet},
+  Transaction, SeraiError, Serai, TemporalSerai, Pair, PairTrait,
 };
 
 pub type InInstructionsEvent = serai_abi::in_instructions::Event;
@@ -27,6 +28,83 @@ impl<'a> SeraiInInstructions<'a> {
     self.0.storage(PALLET, "LastBatch", network).await
   }
 
+  // If the specified batch was executed as of this block
+  pub(crate) async fn batch_executed(
+    &self,
+    network: NetworkId,
+    id: u32,
+  ) -> Result<bool, SeraiError> {
+    Ok(self.last_batch_for_network(network).await?.is_some_and(|last| last >= id))
+  }
+
+  // The hash of the first block which executed the specified batch, given a block which had
+  // executed it
+  //
+  // Batches are executed in order, so this binary searches for the first block whose last batch is
+  // at least this batch
+  pub(crate) async fn batch_execution_block(
+    &self,
+    network: NetworkId,
+    id: u32,
+    mut high: u64,
+    mut high_hash: [u8; 32],
+  ) -> Result<[u8; 32], SeraiError> {
+    // The batch isn't executed as of low, yet is as of high
+    // The genesis block doesn't include any batches, so it's a valid lower bound
+    let mut low = 0;
+    while (high - low) > 1 {
+      let mid = low + ((high - low) / 2);
+      let Some(hash) = self.0.serai.block_hash(mid).await? else {
+        Err(SeraiError::InvalidNode(
+          "couldn't get block hash for a block number below a block which executed a batch"
+            .to_string(),
+        ))?
+      };
+      if self.0.serai.as_of(hash).in_instructions().batch_executed(network, id).await? {
+        high = mid;
+        high_hash = hash;
+      } else {
+        low = mid;
+      }
+    }
+    Ok(high_hash)
+  }
+
+  /// The external network block the specified batch was for.
+  ///
+  /// Batches are executed in order, so this binary searches the Serai blocks up to the last block
+  /// to include a batch for this network, for the block which included this batch. This costs a
+  /// logarithmic amount of requests in the length of the chain. Returns None if the batch has yet
+  /// to be published as of this block.
+  pub async fn batch_origin_block(
+    &self,
+    network: NetworkId,
+    id: u32,
+  ) -> Result<Option<BlockHash>, SeraiError> {
+    let Some(last_batch) = self.last_batch_for_network(network).await? else { return Ok(None) };
+    if id > last_batch {
+      return Ok(None);
+    }
+
+    let Some(high) = self.0.storage::<_, u64>(PALLET, "LastBatchBlock", network).await? else {
+      Err(SeraiError::InvalidNode("network had a last batch yet no last batch block".to_string()))?
+    };
+    let Some(high_hash) = self.0.serai.block_hash(high).await? else {
+      Err(SeraiError::InvalidNode("couldn't get block hash for the last batch block".to_string()))?
+    };
+
+    let high_hash = self.batch_execution_block(network, id, high, high_hash).await?;
+    for event in self.0.serai.as_of(high_hash).in_instructions().batch_events().await? {
+      if let InInstructionsEvent::Batch { network: event_network, id: event_id, block, .. } = event
+      {
+        if (event_network == network) && (event_id == id) {
+          return Ok(Some(block));
+        }
+      }
+    }
+    Err(SeraiError::InvalidNode("batch wasn't present in the block which executed it".to_string()))
+  }
+
   pub async fn batch_events(&self) -> Result<Vec<InInstructionsEvent>, SeraiError> {
     self
       .0
@@ -44,9 +122,90 @@ impl<'a> SeraiInInstructions<'a> {
       .await
   }
 
+  /// All InInstructions events in this block, in the order they were emitted.
+  pub async fn all_events(&self) -> Result<Vec<InInstructionsEvent>, SeraiError> {
+    self
+      .0
+      .events(|event| {
+        if let serai_abi::Event::InInstructions(event) = event {
+          Some(event.clone())
+        } else {
+          None
+        }
+      })
+      .await
+  }
+
   pub fn execute_batch(batch: SignedBatch) -> Transaction {
     Serai::unsigned(serai_abi::Call::InInstructions(
       serai_abi::in_instructions::Call::execute_batch { batch },
     ))
   }
+
+  /// Build a transaction executing this batch, after verifying its signature.
+  ///
+  /// As the runtime does, the signature is accepted if it's valid for either the key of the
+  /// network's current session or the key of its prior session, as of this block. Returns
+  /// `SeraiError::InvalidBatchSignature` instead of a doomed transaction if it isn't.
+  pub async fn execute_batch_checked(&self, batch: SignedBatch) -> Result<Transaction, SeraiError> {
+    let network = batch.batch.network;
+    // Serai doesn't publish batches
+    if network == NetworkId::Serai {
+      Err(SeraiError::InvalidBatchSignature)?;
+    }
+
+    let validator_sets = self.0.validator_sets();
+    let Some(session) = validator_sets.session(network).await? else {
+      Err(SeraiError::InvalidBatchSignature)?
+    };
+    let mut keys = vec![validator_sets.keys(ValidatorSet { network, session }).await?];
+    if let Some(prior) = session.0.checked_sub(1) {
+      keys.push(validator_sets.keys(ValidatorSet { network, session: Session(prior) }).await?);
+    }
+
+    let message = batch_message(&batch.batch);
+    if !keys.into_iter().flatten().any(|keys| Pair::verify(&batch.signature, &message, &keys.0)) {
+      Err(SeraiError::InvalidBatchSignature)?;
+    }
+    Ok(Self::execute_batch(batch))
+  }
+}
+
+/// A cursor over the batch events of finalized blocks.
+///
+/// This remembers the next block to process, so no finalized block's batches are skipped, even if
+/// the consumer falls behind finalization or an advance fails midway.
+#[derive(Clone)]
+pub struct BatchCursor<'a> {
+  serai: &'a Serai,
+  next_block: u64,
+}
+impl<'a> BatchCursor<'a> {
+  /// Create a cursor which will start processing from the specified block number.
+  pub fn new(serai: &'a Serai, next_block: u64) -> Self {
+    BatchCursor { serai, next_block }
+  }
+
+  /// The number of the next block this cursor will process.
+  pub fn next_block(&self) -> u64 {
+    self.next_block
+  }
+
+  /// Advance to the latest finalized block, returning the batch events of every block processed
+  /// in the order they were emitted.
+  pub async fn advance(&mut self) -> Result<Vec<InInstructionsEvent>, SeraiError> {
+    let latest = self.serai.latest_finalized_block().await?.number();
+
+    let mut events = vec![];
+    while self.next_block <= latest {
+      let Some(hash) = self.serai.block_hash(self.next_block).await? else {
+        Err(SeraiError::InvalidNode(
+          "couldn't get block hash for a block number below the finalized block".to_string(),
+        ))?
+      };
+      events.extend(self.serai.as_of(hash).in_instructions().batch_events().await?);
+      self.next_block += 1;
+    }
+    Ok(events)
+  }
 }
@@ -1,3 +1,9 @@
+use core::time::Duration;
+use std::sync::{
+  Arc,
+  atomic::{Ordering, AtomicU64},
+};
+
 use thiserror::Error;
 
 use async_lock::RwLock;
@@ -16,14 +22,14 @@ pub use abi::{primitives, Transaction};
 use abi::*;
 
 pub use primitives::{SeraiAddress, Signature, Amount};
-use primitives::{Header, NetworkId};
+use primitives::{Header, NetworkId, Coin};
 
 pub mod coins;
 pub use coins::SeraiCoins;
 pub mod dex;
 pub use dex::SeraiDex;
 pub mod in_instructions;
-pub use in_instructions::SeraiInInstructions;
+pub use in_instructions::{SeraiInInstructions, BatchCursor};
 pub mod validator_sets;
 pub use validator_sets::SeraiValidatorSets;
 
@@ -51,23 +57,62 @@ impl Block {
   }
 }
 
+/// An error from the Serai client.
+///
+/// Errors from communicating with the node include the ID of the request which failed, allowing
+/// concurrent failures to be told apart. Request IDs are unique per client, shared with its clones.
 #[derive(Error, Debug)]
 pub enum SeraiError {
-  #[error("failed to communicate with serai")]
-  ConnectionError,
+  #[error("failed to communicate with serai (request {0})")]
+  ConnectionError(u64),
   #[error("node is faulty: {0}")]
   InvalidNode(String),
-  #[error("error in response: {0}")]
-  ErrorInResponse(String),
+  #[error("error in response to request {0}: {1}")]
+  ErrorInResponse(u64, String),
   #[error("serai-client library was intended for a different runtime version: {0}")]
   InvalidRuntime(String),
+  #[error("timed out")]
+  Timeout,
+  #[error("batch wasn't signed by the network's keys")]
+  InvalidBatchSignature,
 }
 
+impl SeraiError {
+  /// The ID of the request this error occurred for, if it occurred while communicating with the
+  /// node.
+  pub fn request_id(&self) -> Option<u64> {
+    match self {
+      SeraiError::ConnectionError(id) | SeraiError::ErrorInResponse(id, _) => Some(*id),
+      _ => None,
+    }
+  }
+}
+
+/// The health of a Serai node, as reported by the node itself.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NodeHealth {
+  /// The number of the latest finalized block.
+  pub finalized_block: u64,
+  /// The number of the best block, which may not yet be finalized.
+  pub best_block: u64,
+  /// If the node reports itself as syncing.
+  pub is_syncing: bool,
+  /// The amount of peers the node is connected to.
+  pub peers: usize,
+}
+
+/// The default interval to poll the node at, when waiting on it.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Clone)]
 pub struct Serai {
   url: String,
   client: Client,
   genesis: [u8; 32],
+  // The poll interval in milliseconds, shared across clones so it may be changed at runtime
+  poll_interval: Arc<AtomicU64>,
+  // The ID of the next request, shared across clones so IDs are unique per client
+  next_request_id: Arc<AtomicU64>,
 }
 
 type EventsInBlock = Vec<frame_system::EventRecord<Event, [u8; 32]>>;
@@ -88,12 +133,13 @@ impl Serai {
     method: &str,
     params: Req,
   ) -> Result<Res, SeraiError> {
+    let id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
     let request = Request::from(
       hyper::Request::post(&self.url)
         .header("Content-Type", "application/json")
         .body(
           serde_json::to_vec(
-            &serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }),
+            &serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }),
           )
           .unwrap()
           .into(),
@@ -117,20 +163,20 @@ impl Serai {
       .client
       .request(request)
       .await
-      .map_err(|_| SeraiError::ConnectionError)?
+      .map_err(|_| SeraiError::ConnectionError(id))?
       .body()
       .await
-      .map_err(|_| SeraiError::ConnectionError)?;
+      .map_err(|_| SeraiError::ConnectionError(id))?;
 
     let res: RpcResponse<Res> = serde_json::from_reader(&mut res).map_err(|e| {
       SeraiError::InvalidRuntime(format!(
-        "response was a different type than expected: {:?}",
+        "response to request {id} was a different type than expected: {:?}",
         e.classify()
       ))
     })?;
     match res {
       RpcResponse::Ok { result } => Ok(result),
-      RpcResponse::Err { error } => Err(SeraiError::ErrorInResponse(error.message)),
+      RpcResponse::Err { error } => Err(SeraiError::ErrorInResponse(id, error.message)),
     }
   }
 
@@ -154,13 +200,36 @@ impl Serai {
 
   pub async fn new(url: String) -> Result<Self, SeraiError> {
     let client = Client::with_connection_pool();
-    let mut res = Serai { url, client, genesis: [0xfe; 32] };
+    let mut res = Serai {
+      url,
+      client,
+      genesis: [0xfe; 32],
+      poll_interval: Arc::new(AtomicU64::new(
+        DEFAULT_POLL_INTERVAL.as_millis().try_into().unwrap(),
+      )),
+      next_request_id: Arc::new(AtomicU64::new(0)),
+    };
     res.genesis = res.block_hash(0).await?.ok_or_else(|| {
       SeraiError::InvalidNode("node didn't have the first block's hash".to_string())
     })?;
     Ok(res)
   }
 
+  /// The interval to poll the node at, when waiting on it.
+  pub fn poll_interval(&self) -> Duration {
+    Duration::from_millis(self.poll_interval.load(Ordering::Relaxed))
+  }
+
+  /// Set the interval to poll the node at, when waiting on it.
+  ///
+  /// This may be changed at any time, including while polling, and is shared with every clone of
+  /// this client. The interval is truncated to milliseconds.
+  pub fn set_poll_interval(&self, interval: Duration) {
+    self
+      .poll_interval
+      .store(interval.as_millis().try_into().unwrap_or(u64::MAX), Ordering::Relaxed);
+  }
+
   fn unsigned(call: Call) -> Transaction {
     Transaction { call, signature: None }
   }
@@ -201,7 +270,7 @@ impl Serai {
       .await?;
     let bytes = Self::hex_decode(hash)?;
     let r = Vec::<Public>::decode(&mut bytes.as_slice())
-      .map_err(|e| SeraiError::ErrorInResponse(e.to_string()))?;
+      .map_err(|e| SeraiError::InvalidRuntime(e.to_string()))?;
     Ok(r)
   }
 
@@ -236,6 +305,32 @@ impl Serai {
     Ok(block)
   }
 
+  /// Fetch the health of the node, such as for liveness probes.
+  pub async fn health(&self) -> Result<NodeHealth, SeraiError> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct SystemHealth {
+      peers: usize,
+      is_syncing: bool,
+    }
+    let health: SystemHealth = self.call("system_health", ()).await?;
+
+    let finalized = self.latest_finalized_block_hash().await?;
+    let Some(finalized) = self.header(finalized).await? else {
+      Err(SeraiError::InvalidNode("couldn't get finalized header".to_string()))?
+    };
+
+    // Without a hash specified, this returns the header of the best block
+    let best: Header = self.call("chain_getHeader", ()).await?;
+
+    Ok(NodeHealth {
+      finalized_block: finalized.number,
+      best_block: best.number,
+      is_syncing: health.is_syncing,
+      peers: health.peers,
+    })
+  }
+
   // There is no provided method for this
   // TODO: Add one to Serai
   pub async fn is_finalized(&self, header: &Header) -> Result<bool, SeraiError> {
@@ -279,6 +374,41 @@ impl Serai {
     Ok(Some(block))
   }
 
+  /// Wait for the specified network's batch to be executed, returning the hash of the finalized
+  /// block it was executed in.
+  ///
+  /// This polls the latest finalized block at the poll interval (or the timeout, if shorter),
+  /// returning `SeraiError::Timeout` if the batch wasn't executed within the specified timeout. If
+  /// the batch was already executed, this returns immediately.
+  pub async fn wait_for_batch(
+    &self,
+    network: NetworkId,
+    id: u32,
+    timeout: Duration,
+  ) -> Result<[u8; 32], SeraiError> {
+    let start = std::time::Instant::now();
+    let latest = loop {
+      let latest = self.latest_finalized_block_hash().await?;
+      if self.as_of(latest).in_instructions().batch_executed(network, id).await? {
+        break latest;
+      }
+      if start.elapsed() >= timeout {
+        Err(SeraiError::Timeout)?;
+      }
+      tokio::time::sleep(self.poll_interval().min(timeout)).await;
+    };
+
+    let Some(latest_header) = self.header(latest).await? else {
+      Err(SeraiError::InvalidNode("couldn't get finalized header".to_string()))?
+    };
+
+    self
+      .as_of(latest)
+      .in_instructions()
+      .batch_execution_block(network, id, latest_header.number, latest)
+      .await
+  }
+
   /*
   /// A stream which yields whenever new block(s) have been finalized.
   pub async fn newly_finalized_block(
@@ -303,6 +433,19 @@ impl Serai {
   }
   */
 
+  /// Fetch an address's balance of the specified coin as of the latest finalized block.
+  ///
+  /// This is a shorthand for `SeraiCoins::coin_balance`, such as to confirm a deposit was
+  /// credited once its batch was executed.
+  pub async fn balance(&self, address: SeraiAddress, coin: Coin) -> Result<Amount, SeraiError> {
+    self.as_of_latest_finalized_block().await?.coins().coin_balance(coin, address).await
+  }
+
+  /// Poll for newly finalized blocks, starting from the specified block number.
+  pub fn poll_finalized(&self, next_block: u64) -> FinalizedBlocks {
+    FinalizedBlocks { serai: self, next_block, finalized: None }
+  }
+
   /// Create a TemporalSerai bound to whatever is currently the latest finalized block.
   ///
   /// The binding occurs at time of call. This does not track the latest finalized block and update
@@ -326,6 +469,50 @@ impl Serai {
   }
 }
 
+/// A poller which yields every finalized block, in order, once it's finalized.
+#[derive(Clone)]
+pub struct FinalizedBlocks<'a> {
+  serai: &'a Serai,
+  next_block: u64,
+  // The number of the latest block known to be finalized
+  finalized: Option<u64>,
+}
+impl<'a> FinalizedBlocks<'a> {
+  /// The number of the next block this poller will yield.
+  pub fn next_block(&self) -> u64 {
+    self.next_block
+  }
+
+  /// Wait for the next block to be finalized, returning it.
+  ///
+  /// The node is polled at the client's poll interval until the next block is finalized. If an
+  /// error is returned, or this future is dropped before completion, the next call will resume
+  /// from the same block.
+  pub async fn next(&mut self) -> Result<Block, SeraiError> {
+    while self.finalized.map_or(true, |finalized| finalized < self.next_block) {
+      let finalized = self.serai.latest_finalized_block().await?.number();
+      self.finalized = Some(finalized);
+      if finalized < self.next_block {
+        tokio::time::sleep(self.serai.poll_interval()).await;
+      }
+    }
+
+    let Some(hash) = self.serai.block_hash(self.next_block).await? else {
+      Err(SeraiError::InvalidNode(
+        "couldn't get block hash for a block number below the finalized block".to_string(),
+      ))?
+    };
+    let Some(block) = self.serai.block(hash).await? else {
+      Err(SeraiError::InvalidNode("couldn't get a finalized block".to_string()))?
+    };
+    if block.number() != self.next_block {
+      Err(SeraiError::InvalidNode("returned a block with a different number".to_string()))?;
+    }
+    self.next_block += 1;
+    Ok(block)
+  }
+}
+
 impl<'a> TemporalSerai<'a> {
   async fn events<E>(
     &self,
@@ -351,17 +538,14 @@ impl<'a> TemporalSerai<'a> {
     Ok(res)
   }
 
-  async fn storage<K: Encode, R: Decode>(
-    &self,
-    pallet: &'static str,
-    name: &'static str,
-    key: K,
-  ) -> Result<Option<R>, SeraiError> {
+  fn storage_prefix(pallet: &'static str, name: &'static str) -> Vec<u8> {
     // TODO: Make this const?
-    let mut full_key = sp_core::hashing::twox_128(pallet.as_bytes()).to_vec();
-    full_key.extend(sp_core::hashing::twox_128(name.as_bytes()));
-    full_key.extend(key.encode());
+    let mut prefix = sp_core::hashing::twox_128(pallet.as_bytes()).to_vec();
+    prefix.extend(sp_core::hashing::twox_128(name.as_bytes()));
+    prefix
+  }
 
+  async fn raw_storage<R: Decode>(&self, full_key: &[u8]) -> Result<Option<R>, SeraiError> {
     let res: Option<String> =
       self.serai.call("state_getStorage", [hex::encode(full_key), hex::encode(self.block)]).await?;
     let Some(res) = res else { return Ok(None) };
@@ -371,6 +555,64 @@ impl<'a> TemporalSerai<'a> {
     })?))
   }
 
+  async fn storage<K: Encode, R: Decode>(
+    &self,
+    pallet: &'static str,
+    name: &'static str,
+    key: K,
+  ) -> Result<Option<R>, SeraiError> {
+    let mut full_key = Self::storage_prefix(pallet, name);
+    full_key.extend(key.encode());
+    self.raw_storage(&full_key).await
+  }
+
+  /// Every entry within a storage map, as of this block.
+  ///
+  /// The keys are decoded from the storage keys as-is, so maps with hashed keys will need to
+  /// include the hash within `K` (such as `(hash, key)` for a `Twox64Concat` map).
+  pub async fn storage_keys<K: Decode, V: Decode>(
+    &self,
+    pallet: &'static str,
+    name: &'static str,
+  ) -> Result<Vec<(K, V)>, SeraiError> {
+    const PAGE_SIZE: u32 = 512;
+
+    let prefix = Self::storage_prefix(pallet, name);
+    let mut res = vec![];
+    let mut start: Option<String> = None;
+    loop {
+      let keys: Vec<String> = self
+        .serai
+        .call(
+          "state_getKeysPaged",
+          (hex::encode(&prefix), PAGE_SIZE, start.clone(), hex::encode(self.block)),
+        )
+        .await?;
+      let last_page = keys.len() < usize::try_from(PAGE_SIZE).unwrap();
+
+      for key in keys {
+        let full_key = Serai::hex_decode(key.clone())?;
+        let Some(mut map_key) = full_key.strip_prefix(prefix.as_slice()) else {
+          Err(SeraiError::InvalidNode("node returned a key outside the requested map".to_string()))?
+        };
+        let map_key = K::decode(&mut map_key).map_err(|_| {
+          SeraiError::InvalidRuntime("different type present at storage key".to_string())
+        })?;
+        // As these keys are as of this block, every key should have a value
+        let Some(value) = self.raw_storage(&full_key).await? else {
+          Err(SeraiError::InvalidNode("node returned a key without a value".to_string()))?
+        };
+        res.push((map_key, value));
+        start = Some(key);
+      }
+
+      if last_page {
+        break;
+      }
+    }
+    Ok(res)
+  }
+
   pub fn coins(&'a self) -> SeraiCoins<'a> {
     SeraiCoins(self)
   }
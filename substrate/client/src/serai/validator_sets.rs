@@ -103,6 +103,9 @@ impl<'a> SeraiValidatorSets<'a> {
       .await
   }
 
+  /// The current session for the specified network, as of this block.
+  ///
+  /// Returns None if the network has yet to have a session.
   pub async fn session(&self, network: NetworkId) -> Result<Option<Session>, SeraiError> {
     self.0.storage(PALLET, "CurrentSession", network).await
   }
@@ -171,6 +174,19 @@ impl<'a> SeraiValidatorSets<'a> {
     self.0.storage(PALLET, "Keys", (sp_core::hashing::twox_64(&set.encode()), set)).await
   }
 
+  /// The most recently set key pair for the specified network, as of this block.
+  ///
+  /// This is the current session's key pair or, if the current session has yet to set its keys,
+  /// the prior session's key pair. Returns None if the network has yet to set a key pair.
+  pub async fn active_keys(&self, network: NetworkId) -> Result<Option<KeyPair>, SeraiError> {
+    let Some(session) = self.session(network).await? else { return Ok(None) };
+    if let Some(keys) = self.keys(ValidatorSet { network, session }).await? {
+      return Ok(Some(keys));
+    }
+    let Some(prior) = session.0.checked_sub(1) else { return Ok(None) };
+    self.keys(ValidatorSet { network, session: Session(prior) }).await
+  }
+
   pub async fn key_pending_slash_report(
     &self,
     network: NetworkId,
@@ -0,0 +1,50 @@
+use scale::Encode;
+
+use sp_core::sr25519::Public;
+
+use serai_client::{
+  primitives::NetworkId,
+  validator_sets::primitives::{Session, ValidatorSet, KeyPair},
+  Serai,
+};
+
+mod common;
+use common::mock::{storage_key, mock_storage_node};
+
+fn keys_key(set: ValidatorSet) -> String {
+  storage_key("ValidatorSets", "Keys", (sp_core::hashing::twox_64(&set.encode()), set))
+}
+
+#[tokio::test]
+async fn active_keys() {
+  let monero_keys = KeyPair(Public([0xaa; 32]), vec![0xbb; 32].try_into().unwrap());
+  let bitcoin_keys = KeyPair(Public([0xcc; 32]), vec![0xdd; 33].try_into().unwrap());
+
+  let storage = vec![
+    // Monero's current session has set its keys
+    (storage_key("ValidatorSets", "CurrentSession", NetworkId::Monero), Session(3).encode()),
+    (
+      keys_key(ValidatorSet { network: NetworkId::Monero, session: Session(3) }),
+      monero_keys.encode(),
+    ),
+    // Bitcoin's current session has yet to set its keys, so the prior session's are active
+    (storage_key("ValidatorSets", "CurrentSession", NetworkId::Bitcoin), Session(1).encode()),
+    (
+      keys_key(ValidatorSet { network: NetworkId::Bitcoin, session: Session(0) }),
+      bitcoin_keys.encode(),
+    ),
+    // Ethereum's first session has yet to set its keys
+    (storage_key("ValidatorSets", "CurrentSession", NetworkId::Ethereum), Session(0).encode()),
+  ];
+
+  let url = mock_storage_node(storage);
+
+  let serai = Serai::new(url).await.unwrap();
+  let serai = serai.as_of_latest_finalized_block().await.unwrap();
+  let serai = serai.validator_sets();
+  assert_eq!(serai.active_keys(NetworkId::Monero).await.unwrap(), Some(monero_keys));
+  assert_eq!(serai.active_keys(NetworkId::Bitcoin).await.unwrap(), Some(bitcoin_keys));
+  assert_eq!(serai.active_keys(NetworkId::Ethereum).await.unwrap(), None);
+  // Serai has no session, and accordingly no keys
+  assert_eq!(serai.active_keys(NetworkId::Serai).await.unwrap(), None);
+}
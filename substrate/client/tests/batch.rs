@@ -1,3 +1,5 @@
+use core::time::Duration;
+
 use rand_core::{RngCore, OsRng};
 
 use blake2::{
@@ -8,13 +10,13 @@ use blake2::{
 use scale::Encode;
 
 use serai_client::{
-  primitives::{Amount, NetworkId, Coin, Balance, BlockHash, SeraiAddress},
+  primitives::{Amount, NetworkId, Coin, Balance, BlockHash, SeraiAddress, ExternalAddress},
   in_instructions::{
-    primitives::{InInstruction, InInstructionWithBalance, Batch},
+    primitives::{InInstruction, InInstructionWithBalance, Batch, DexCall, OutAddress},
     InInstructionsEvent,
   },
   coins::CoinsEvent,
-  Serai,
+  Serai, SeraiError, BatchCursor,
 };
 
 mod common;
@@ -72,4 +74,149 @@ serai_test!(
     assert_eq!(serai.coin_supply(coin).await.unwrap(), amount);
     assert_eq!(serai.coin_balance(coin, address).await.unwrap(), amount);
   })
+
+  all_in_instructions_events: (|serai: Serai| async move {
+    let network = NetworkId::Bitcoin;
+    let id = 0;
+
+    let mut block_hash = BlockHash([0; 32]);
+    OsRng.fill_bytes(&mut block_hash.0);
+
+    let mut address = SeraiAddress::new([0; 32]);
+    OsRng.fill_bytes(&mut address.0);
+
+    let balance =
+      Balance { coin: Coin::Bitcoin, amount: Amount(OsRng.next_u64().saturating_add(1)) };
+
+    let batch = Batch {
+      network,
+      id,
+      block: block_hash,
+      instructions: vec![
+        InInstructionWithBalance { instruction: InInstruction::Transfer(address), balance },
+        // This will fail as SRI can't be sent to an external address
+        InInstructionWithBalance {
+          instruction: InInstruction::Dex(DexCall::Swap(
+            Balance { coin: Coin::Serai, amount: Amount(1) },
+            OutAddress::External(ExternalAddress::new(vec![0; 20]).unwrap()),
+          )),
+          balance,
+        },
+      ],
+    };
+
+    let block = provide_batch(&serai, batch.clone()).await;
+
+    let serai = serai.as_of(block);
+    let serai = serai.in_instructions();
+    assert_eq!(
+      serai.all_events().await.unwrap(),
+      vec![
+        InInstructionsEvent::Batch {
+          network,
+          id,
+          block: block_hash,
+          instructions_hash: Blake2b::<U32>::digest(batch.instructions.encode()).into(),
+        },
+        InInstructionsEvent::InstructionFailure { network, id, index: 1 },
+      ]
+    );
+    // batch_events should still only return the Batch event
+    assert_eq!(serai.batch_events().await.unwrap().len(), 1);
+  })
+
+  wait_for_batch: (|serai: Serai| async move {
+    let network = NetworkId::Bitcoin;
+    let id = 0;
+
+    let mut block_hash = BlockHash([0; 32]);
+    OsRng.fill_bytes(&mut block_hash.0);
+
+    let mut address = SeraiAddress::new([0; 32]);
+    OsRng.fill_bytes(&mut address.0);
+
+    let balance =
+      Balance { coin: Coin::Bitcoin, amount: Amount(OsRng.next_u64().saturating_add(1)) };
+
+    let batch = Batch {
+      network,
+      id,
+      block: block_hash,
+      instructions: vec![InInstructionWithBalance {
+        instruction: InInstruction::Transfer(address),
+        balance,
+      }],
+    };
+
+    // The batch hasn't been executed, so this should time out
+    assert!(matches!(
+      serai.wait_for_batch(network, id, Duration::from_secs(5)).await,
+      Err(SeraiError::Timeout)
+    ));
+
+    assert_eq!(serai.balance(address, Coin::Bitcoin).await.unwrap(), Amount(0));
+
+    let waiting = tokio::spawn({
+      let serai = serai.clone();
+      async move { serai.wait_for_batch(network, id, Duration::from_secs(120)).await }
+    });
+    // Let a few blocks pass before executing the batch
+    tokio::time::sleep(Duration::from_secs(15)).await;
+    let block = provide_batch(&serai, batch).await;
+    assert_eq!(waiting.await.unwrap().unwrap(), block);
+
+    // Once executed, this should immediately return the same block, even as further blocks are
+    // finalized
+    tokio::time::sleep(Duration::from_secs(15)).await;
+    assert_eq!(serai.wait_for_batch(network, id, Duration::ZERO).await.unwrap(), block);
+
+    // The deposit should have been credited
+    assert_eq!(serai.balance(address, Coin::Bitcoin).await.unwrap(), balance.amount);
+    assert_eq!(serai.balance(address, Coin::Monero).await.unwrap(), Amount(0));
+  })
+
+  batch_cursor: (|serai: Serai| async move {
+    let network = NetworkId::Bitcoin;
+
+    let mut cursor =
+      BatchCursor::new(&serai, serai.latest_finalized_block().await.unwrap().number() + 1);
+
+    let mut batches = vec![];
+    for id in 0 .. 4 {
+      let mut block_hash = BlockHash([0; 32]);
+      OsRng.fill_bytes(&mut block_hash.0);
+
+      let mut address = SeraiAddress::new([0; 32]);
+      OsRng.fill_bytes(&mut address.0);
+
+      batches.push(Batch {
+        network,
+        id,
+        block: block_hash,
+        instructions: vec![InInstructionWithBalance {
+          instruction: InInstruction::Transfer(address),
+          balance: Balance { coin: Coin::Bitcoin, amount: Amount(1) },
+        }],
+      });
+    }
+    let event = |batch: &Batch| InInstructionsEvent::Batch {
+      network,
+      id: batch.id,
+      block: batch.block,
+      instructions_hash: Blake2b::<U32>::digest(batch.instructions.encode()).into(),
+    };
+
+    provide_batch(&serai, batches[0].clone()).await;
+    assert_eq!(cursor.advance().await.unwrap(), vec![event(&batches[0])]);
+
+    // Fall several blocks behind, executing the rest of the batches before advancing
+    for batch in &batches[1 ..] {
+      provide_batch(&serai, batch.clone()).await;
+    }
+    let events = cursor.advance().await.unwrap();
+    assert_eq!(events, batches[1 ..].iter().map(event).collect::<Vec<_>>());
+
+    // Once caught up, no batches are returned again
+    assert!(cursor.advance().await.unwrap().is_empty());
+  })
 );
@@ -0,0 +1,101 @@
+use scale::Encode;
+
+use serai_client::{
+  primitives::{BlockHash, NetworkId},
+  in_instructions::InInstructionsEvent,
+  abi::Event,
+  Serai,
+};
+
+mod common;
+use common::mock::{mock_node, storage_key, storage_response};
+
+// Encode a list of events as System's Events, with each event as a frame_system::EventRecord
+fn events(events: Vec<InInstructionsEvent>) -> Vec<u8> {
+  events
+    .into_iter()
+    .map(|event| {
+      // Phase::ApplyExtrinsic(0), the event, and no topics
+      (0u8, 0u32, Event::InInstructions(event), Vec::<[u8; 32]>::new())
+    })
+    .collect::<Vec<_>>()
+    .encode()
+}
+
+fn batch(network: NetworkId, id: u32, block: BlockHash) -> InInstructionsEvent {
+  InInstructionsEvent::Batch { network, id, block, instructions_hash: [0; 32] }
+}
+
+#[tokio::test]
+async fn batch_origin_block() {
+  let latest = hex::encode([0xff; 32]);
+  let events_key = storage_key("System", "Events", ());
+
+  // Monero published batch 4 in block 9 and batch 5 in block 10
+  let last_batch_key = storage_key("InInstructions", "LastBatch", NetworkId::Monero);
+  let storage = [
+    (
+      hex::encode([9; 32]),
+      vec![
+        (last_batch_key.clone(), 4u32.encode()),
+        (
+          events_key.clone(),
+          events(vec![
+            batch(NetworkId::Bitcoin, 2, BlockHash([0xbb; 32])),
+            batch(NetworkId::Monero, 4, BlockHash([0x44; 32])),
+          ]),
+        ),
+      ],
+    ),
+    (
+      hex::encode([10; 32]),
+      vec![
+        (last_batch_key.clone(), 5u32.encode()),
+        (events_key, events(vec![batch(NetworkId::Monero, 5, BlockHash([0x55; 32]))])),
+      ],
+    ),
+    (
+      latest.clone(),
+      vec![
+        (last_batch_key, 5u32.encode()),
+        (storage_key("InInstructions", "LastBatchBlock", NetworkId::Monero), 10u64.encode()),
+      ],
+    ),
+  ];
+
+  let url = mock_node(move |body| {
+    if body.contains("chain_getFinalizedHead") {
+      format!(r#""0x{latest}""#)
+    } else if body.contains("chain_getBlockHash") {
+      // The genesis block, and every block binary searched over
+      for number in 0u8 ..= 10 {
+        if body.contains(&format!("[{number}]")) {
+          return format!(r#""0x{}""#, hex::encode([number; 32]));
+        }
+      }
+      "null".to_string()
+    } else if body.contains("state_getStorage") {
+      storage
+        .iter()
+        .find(|(block, _)| body.contains(block.as_str()))
+        .map_or("null".to_string(), |(_, storage)| storage_response(body, storage))
+    } else {
+      panic!("unexpected request: {body}")
+    }
+  });
+
+  let serai = Serai::new(url).await.unwrap();
+  let serai = serai.as_of_latest_finalized_block().await.unwrap();
+  let serai = serai.in_instructions();
+  assert_eq!(
+    serai.batch_origin_block(NetworkId::Monero, 5).await.unwrap(),
+    Some(BlockHash([0x55; 32]))
+  );
+  assert_eq!(
+    serai.batch_origin_block(NetworkId::Monero, 4).await.unwrap(),
+    Some(BlockHash([0x44; 32]))
+  );
+  // Batches which have yet to be published, or from networks without batches, have no block
+  assert_eq!(serai.batch_origin_block(NetworkId::Monero, 6).await.unwrap(), None);
+  assert_eq!(serai.batch_origin_block(NetworkId::Bitcoin, 0).await.unwrap(), None);
+}
@@ -0,0 +1,91 @@
+use scale::Encode;
+
+use std::{
+  sync::Arc,
+  io::{Read, Write, BufRead, BufReader},
+  net::TcpListener,
+  thread,
+};
+
+// Spawn a mock node, responding to every JSON-RPC request with the result returned for its body
+#[allow(dead_code)]
+pub fn mock_node(respond: impl 'static + Send + Sync + Fn(&str) -> String) -> String {
+  let respond = Arc::new(respond);
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let url = format!("http://{}", listener.local_addr().unwrap());
+  thread::spawn(move || {
+    for stream in listener.incoming() {
+      let Ok(mut stream) = stream else { continue };
+      let respond = respond.clone();
+      thread::spawn(move || {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        loop {
+          // Read the headers, keeping the length of the body
+          let mut len = 0;
+          loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+              return;
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+              break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+              if name.eq_ignore_ascii_case("content-length") {
+                len = value.trim().parse().unwrap();
+              }
+            }
+          }
+
+          let mut body = vec![0; len];
+          reader.read_exact(&mut body).unwrap();
+          let result = respond(&String::from_utf8(body).unwrap());
+          let response = format!(r#"{{"jsonrpc":"2.0","id":1,"result":{result}}}"#);
+          write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            response.len(),
+            response
+          )
+          .unwrap();
+        }
+      });
+    }
+  });
+  url
+}
+
+// The hex-encoded key for the specified pallet's storage, under the specified key
+#[allow(dead_code)]
+pub fn storage_key(pallet: &str, name: &str, key: impl Encode) -> String {
+  let mut full_key = sp_core::hashing::twox_128(pallet.as_bytes()).to_vec();
+  full_key.extend(sp_core::hashing::twox_128(name.as_bytes()));
+  full_key.extend(key.encode());
+  hex::encode(full_key)
+}
+
+// Respond to a state_getStorage request with the value for the requested key, or null if the key
+// has no value
+#[allow(dead_code)]
+pub fn storage_response(body: &str, storage: &[(String, Vec<u8>)]) -> String {
+  storage
+    .iter()
+    .find(|(key, _)| body.contains(key.as_str()))
+    .map_or("null".to_string(), |(_, value)| format!(r#""0x{}""#, hex::encode(value)))
+}
+
+// Spawn a mock node whose every block, including its finalized block, has the hash [0xaa; 32],
+// serving the specified storage
+#[allow(dead_code)]
+pub fn mock_storage_node(storage: Vec<(String, Vec<u8>)>) -> String {
+  mock_node(move |body| {
+    if body.contains("chain_getBlockHash") || body.contains("chain_getFinalizedHead") {
+      format!(r#""0x{}""#, hex::encode([0xaa; 32]))
+    } else if body.contains("state_getStorage") {
+      storage_response(body, &storage)
+    } else {
+      panic!("unexpected request: {body}")
+    }
+  })
+}
@@ -0,0 +1,68 @@
+use scale::Encode;
+
+use sp_core::Pair;
+
+use serai_client::{
+  primitives::{insecure_pair_from_name, BlockHash, NetworkId},
+  validator_sets::primitives::{Session, ValidatorSet, KeyPair},
+  in_instructions::primitives::{Batch, SignedBatch, batch_message},
+  Serai, SeraiError, SeraiInInstructions,
+};
+
+mod common;
+use common::mock::{storage_key, mock_storage_node};
+
+#[tokio::test]
+async fn execute_batch_checked() {
+  let pair = insecure_pair_from_name("Monero");
+  let set = ValidatorSet { network: NetworkId::Monero, session: Session(0) };
+  let keys = KeyPair(pair.public(), vec![].try_into().unwrap());
+
+  let storage = vec![
+    (storage_key("ValidatorSets", "CurrentSession", NetworkId::Monero), Session(0).encode()),
+    (
+      storage_key("ValidatorSets", "Keys", (sp_core::hashing::twox_64(&set.encode()), set)),
+      keys.encode(),
+    ),
+  ];
+
+  let url = mock_storage_node(storage);
+
+  let serai = Serai::new(url).await.unwrap();
+  let serai = serai.as_of_latest_finalized_block().await.unwrap();
+  let serai = serai.in_instructions();
+
+  let batch =
+    Batch { network: NetworkId::Monero, id: 0, block: BlockHash([0xbb; 32]), instructions: vec![] };
+  let signed = SignedBatch { batch: batch.clone(), signature: pair.sign(&batch_message(&batch)) };
+  assert_eq!(
+    serai.execute_batch_checked(signed.clone()).await.unwrap(),
+    SeraiInInstructions::execute_batch(signed.clone())
+  );
+
+  // A batch tampered with after being signed is refused
+  let mut tampered = signed.clone();
+  tampered.batch.id = 1;
+  assert!(matches!(
+    serai.execute_batch_checked(tampered).await,
+    Err(SeraiError::InvalidBatchSignature)
+  ));
+
+  // As is a batch signed by a key other than the network's
+  let other = insecure_pair_from_name("Bitcoin");
+  let mis_signed =
+    SignedBatch { batch: batch.clone(), signature: other.sign(&batch_message(&batch)) };
+  assert!(matches!(
+    serai.execute_batch_checked(mis_signed).await,
+    Err(SeraiError::InvalidBatchSignature)
+  ));
+
+  // And a batch for a network without keys
+  let bitcoin = Batch { network: NetworkId::Bitcoin, ..batch };
+  let bitcoin =
+    SignedBatch { batch: bitcoin.clone(), signature: pair.sign(&batch_message(&bitcoin)) };
+  assert!(matches!(
+    serai.execute_batch_checked(bitcoin).await,
+    Err(SeraiError::InvalidBatchSignature)
+  ));
+}
@@ -0,0 +1,98 @@
+use core::time::Duration;
+use std::{
+  sync::{
+    Arc,
+    atomic::{Ordering, AtomicU64},
+  },
+  time::Instant,
+};
+
+use scale::Encode;
+
+use serai_client::{primitives::Header, Block, Serai, DEFAULT_POLL_INTERVAL};
+
+mod common;
+use common::mock::mock_node;
+
+// The hash the mock node uses for the block with the specified number
+fn hash(number: u64) -> [u8; 32] {
+  [u8::try_from(number).unwrap(); 32]
+}
+
+// Read the first parameter of a request, which is either a number or a hex string
+fn param(body: &str) -> &str {
+  let params = &body[(body.find(r#""params":["#).unwrap() + 10) ..];
+  params[.. params.find(']').unwrap()].trim_matches('"')
+}
+
+#[tokio::test]
+async fn poll_finalized() {
+  let finalized = Arc::new(AtomicU64::new(2));
+  let polls = Arc::new(AtomicU64::new(0));
+  let url = mock_node({
+    let finalized = finalized.clone();
+    let polls = polls.clone();
+    move |body| {
+      if body.contains("chain_getBlockHash") {
+        format!(r#""0x{}""#, hex::encode(hash(param(body).parse().unwrap())))
+      } else if body.contains("chain_getFinalizedHead") {
+        polls.fetch_add(1, Ordering::Relaxed);
+        format!(r#""0x{}""#, hex::encode(hash(finalized.load(Ordering::Relaxed))))
+      } else if body.contains("chain_getBlockBin") {
+        let number = hex::decode(param(body)).unwrap()[0].into();
+        let header = Header {
+          parent_hash: Default::default(),
+          number,
+          state_root: Default::default(),
+          extrinsics_root: Default::default(),
+          digest: Default::default(),
+        };
+        format!(r#""0x{}""#, hex::encode(Block { header, transactions: vec![] }.encode()))
+      } else {
+        panic!("unexpected request: {body}")
+      }
+    }
+  });
+
+  let serai = Serai::new(url).await.unwrap();
+  assert_eq!(serai.poll_interval(), DEFAULT_POLL_INTERVAL);
+  serai.set_poll_interval(Duration::from_millis(50));
+  assert_eq!(serai.poll_interval(), Duration::from_millis(50));
+
+  // Blocks which are already finalized are yielded immediately, in order
+  let mut blocks = serai.poll_finalized(1);
+  assert_eq!(blocks.next().await.unwrap().number(), 1);
+  assert_eq!(blocks.next().await.unwrap().number(), 2);
+  assert_eq!(blocks.next_block(), 3);
+
+  // The next block is yielded once it's finalized
+  tokio::spawn({
+    let finalized = finalized.clone();
+    async move {
+      tokio::time::sleep(Duration::from_millis(200)).await;
+      finalized.store(3, Ordering::Relaxed);
+    }
+  });
+  let start = Instant::now();
+  assert_eq!(blocks.next().await.unwrap().number(), 3);
+  assert!(start.elapsed() >= Duration::from_millis(200));
+
+  // Increasing the interval at runtime delays noticing finalization until the next poll
+  serai.clone().set_poll_interval(Duration::from_secs(60));
+  tokio::spawn({
+    let finalized = finalized.clone();
+    async move {
+      tokio::time::sleep(Duration::from_millis(100)).await;
+      finalized.store(4, Ordering::Relaxed);
+    }
+  });
+  let polls_before = polls.load(Ordering::Relaxed);
+  assert!(tokio::time::timeout(Duration::from_millis(500), blocks.next()).await.is_err());
+  assert_eq!(polls.load(Ordering::Relaxed), polls_before + 1);
+  assert_eq!(blocks.next_block(), 4);
+
+  // Once the interval is decreased, the block is yielded, and no block is yielded twice
+  serai.set_poll_interval(Duration::from_millis(50));
+  assert_eq!(blocks.next().await.unwrap().number(), 4);
+  assert_eq!(blocks.next_block(), 5);
+}
@@ -0,0 +1,45 @@
+use serai_client::{NodeHealth, Serai};
+
+mod common;
+use common::mock::mock_node;
+
+// A header with the specified number, as returned by chain_getHeader
+fn header(number: u64) -> String {
+  let hash = format!("0x{}", hex::encode([0; 32]));
+  serde_json::json!({
+    "parentHash": hash,
+    "number": format!("0x{number:x}"),
+    "stateRoot": hash,
+    "extrinsicsRoot": hash,
+    "digest": { "logs": [] },
+  })
+  .to_string()
+}
+
+#[tokio::test]
+async fn health() {
+  let url = mock_node(|body| {
+    let request: serde_json::Value = serde_json::from_str(body).unwrap();
+    let params = &request["params"];
+    match request["method"].as_str().unwrap() {
+      "chain_getBlockHash" | "chain_getFinalizedHead" => {
+        format!(r#""0x{}""#, hex::encode([0xaa; 32]))
+      }
+      "system_health" => r#"{"peers":3,"isSyncing":true,"shouldHavePeers":true}"#.to_string(),
+      // The finalized block, requested by hash
+      "chain_getHeader" if params[0].is_string() => {
+        assert_eq!(params[0].as_str().unwrap(), hex::encode([0xaa; 32]));
+        header(5)
+      }
+      // The best block, requested without a hash
+      "chain_getHeader" => header(7),
+      _ => panic!("unexpected request: {body}"),
+    }
+  });
+
+  let serai = Serai::new(url).await.unwrap();
+  assert_eq!(
+    serai.health().await.unwrap(),
+    NodeHealth { finalized_block: 5, best_block: 7, is_syncing: true, peers: 3 }
+  );
+}
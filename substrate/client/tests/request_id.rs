@@ -0,0 +1,29 @@
+use serai_client::{Serai, SeraiError};
+
+mod common;
+use common::mock::mock_node;
+
+#[tokio::test]
+async fn request_id() {
+  let url = mock_node(|body| {
+    if body.contains("chain_getBlockHash") {
+      format!(r#""0x{}""#, hex::encode([0xaa; 32]))
+    } else {
+      // Drop the connection without responding, failing the request
+      panic!("dropping connection for request: {body}")
+    }
+  });
+
+  let serai = Serai::new(url).await.unwrap();
+  let first = serai.latest_finalized_block_hash().await.unwrap_err();
+  let second = serai.clone().latest_finalized_block_hash().await.unwrap_err();
+  assert!(matches!(first, SeraiError::ConnectionError(_)));
+  assert!(matches!(second, SeraiError::ConnectionError(_)));
+
+  // Both failures should be attributable to their own requests
+  let (first, second) = (first.request_id().unwrap(), second.request_id().unwrap());
+  assert!(first != second);
+
+  // Errors which didn't occur while communicating with the node don't have a request ID
+  assert_eq!(SeraiError::InvalidNode("faulty".to_string()).request_id(), None);
+}
@@ -0,0 +1,21 @@
+use scale::Encode;
+
+use serai_client::{primitives::NetworkId, validator_sets::primitives::Session, Serai};
+
+mod common;
+use common::mock::{storage_key, mock_storage_node};
+
+#[tokio::test]
+async fn session() {
+  // Only Monero has a session
+  let url = mock_storage_node(vec![(
+    storage_key("ValidatorSets", "CurrentSession", NetworkId::Monero),
+    Session(3).encode(),
+  )]);
+
+  let serai = Serai::new(url).await.unwrap();
+  let serai = serai.as_of_latest_finalized_block().await.unwrap();
+  let serai = serai.validator_sets();
+  assert_eq!(serai.session(NetworkId::Monero).await.unwrap(), Some(Session(3)));
+  assert_eq!(serai.session(NetworkId::Bitcoin).await.unwrap(), None);
+}
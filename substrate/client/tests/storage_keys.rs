@@ -0,0 +1,58 @@
+use scale::Encode;
+
+use serai_client::{
+  primitives::{BlockHash, NetworkId},
+  Serai,
+};
+
+mod common;
+use common::mock::{mock_node, storage_key, storage_response};
+
+#[tokio::test]
+async fn storage_keys() {
+  let prefix = storage_key("InInstructions", "LatestNetworkBlock", ());
+
+  let entries = [
+    (NetworkId::Bitcoin, BlockHash([0xaa; 32])),
+    (NetworkId::Ethereum, BlockHash([0xbb; 32])),
+    (NetworkId::Monero, BlockHash([0xcc; 32])),
+  ];
+  let storage = entries
+    .iter()
+    .map(|(network, block)| {
+      (storage_key("InInstructions", "LatestNetworkBlock", network), block.encode())
+    })
+    .collect::<Vec<_>>();
+
+  let url = mock_node(move |body| {
+    let request: serde_json::Value = serde_json::from_str(body).unwrap();
+    let params = &request["params"];
+    match request["method"].as_str().unwrap() {
+      "chain_getBlockHash" | "chain_getFinalizedHead" => {
+        format!(r#""0x{}""#, hex::encode([0xaa; 32]))
+      }
+      "state_getKeysPaged" => {
+        assert_eq!(params[0].as_str().unwrap(), prefix);
+        let count = usize::try_from(params[1].as_u64().unwrap()).unwrap();
+        // Return the keys after the start key, if one was specified
+        let keys = storage
+          .iter()
+          .map(|(key, _)| format!("0x{key}"))
+          .filter(|key| params[2].as_str().map_or(true, |start| key.as_str() > start))
+          .take(count)
+          .collect::<Vec<_>>();
+        serde_json::to_string(&keys).unwrap()
+      }
+      "state_getStorage" => storage_response(body, &storage),
+      _ => panic!("unexpected request: {body}"),
+    }
+  });
+
+  let serai = Serai::new(url).await.unwrap();
+  let serai = serai.as_of_latest_finalized_block().await.unwrap();
+  let res = serai
+    .storage_keys::<NetworkId, BlockHash>("InInstructions", "LatestNetworkBlock")
+    .await
+    .unwrap();
+  assert_eq!(res, entries);
+}
@@ -472,7 +472,7 @@ pub mod pallet {
             // If we used atomic units, any coin whose atomic unit is worth less than SRI's atomic
             // unit would cause a 'price' of 0
             // If the decimals aren't large enough to provide sufficient buffer, use 10,000
-            let coin_decimals = coin.decimals().max(5);
+            let coin_decimals = coin.decimals().max(5).into();
             let accuracy_increase =
               HigherPrecisionBalance::from(SubstrateAmount::pow(10, coin_decimals));
             u64::try_from(
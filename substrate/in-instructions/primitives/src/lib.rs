@@ -21,6 +21,8 @@ use sp_runtime::RuntimeDebug;
 
 #[rustfmt::skip]
 use serai_primitives::{BlockHash, Balance, NetworkId, SeraiAddress, ExternalAddress, system_address};
+#[cfg(test)]
+use serai_primitives::{Coin, Amount};
 
 mod shorthand;
 pub use shorthand::*;
@@ -80,6 +82,23 @@ pub enum InInstruction {
   Dex(DexCall),
 }
 
+impl InInstruction {
+  /// Encode this InInstruction as the data to include with a deposit to an external network.
+  ///
+  /// The deposit's origin is left unspecified, leaving any refund to the deposit's presumed
+  /// origin.
+  pub fn encode_for_deposit(&self) -> Vec<u8> {
+    Shorthand::Raw(RefundableInInstruction { origin: None, instruction: self.clone() }).encode()
+  }
+
+  /// Decode an InInstruction from the data included with a deposit.
+  ///
+  /// This is `RefundableInInstruction::decode_from_deposit`, without the deposit's origin.
+  pub fn decode_from_deposit(data: &[u8]) -> Option<InInstruction> {
+    RefundableInInstruction::decode_from_deposit(data).map(|instruction| instruction.instruction)
+  }
+}
+
 #[derive(Clone, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(Zeroize))]
 #[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
@@ -89,6 +108,16 @@ pub struct RefundableInInstruction {
   pub instruction: InInstruction,
 }
 
+impl RefundableInInstruction {
+  /// Decode a RefundableInInstruction from the data included with a deposit.
+  ///
+  /// This is how the processors decode deposits, so it accepts any Shorthand and ignores any data
+  /// after it. Returns None if the data doesn't start with a Shorthand.
+  pub fn decode_from_deposit(mut data: &[u8]) -> Option<RefundableInInstruction> {
+    RefundableInInstruction::try_from(Shorthand::decode(&mut data).ok()?).ok()
+  }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, MaxEncodedLen, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Zeroize))]
 #[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
@@ -124,6 +153,15 @@ pub struct SignedBatch {
   pub signature: Signature,
 }
 
+impl SignedBatch {
+  /// Create a SignedBatch from a Batch and its signature.
+  ///
+  /// The signature is the network's sr25519 signature of `batch_message(&batch)`.
+  pub fn new(batch: Batch, signature: [u8; 64]) -> SignedBatch {
+    SignedBatch { batch, signature: Signature(signature) }
+  }
+}
+
 #[cfg(feature = "std")]
 impl Zeroize for SignedBatch {
   fn zeroize(&mut self) {
@@ -132,8 +170,98 @@ impl Zeroize for SignedBatch {
   }
 }
 
-// TODO: Make this an associated method?
 /// The message for the batch signature.
 pub fn batch_message(batch: &Batch) -> Vec<u8> {
   [b"InInstructions-batch".as_ref(), &batch.encode()].concat()
 }
+
+impl Batch {
+  /// The message to sign for this batch, including its domain separation.
+  ///
+  /// This is the same as `batch_message(self)`, letting signers without the rest of the client,
+  /// such as hardware wallets, produce the signature for a `SignedBatch`.
+  pub fn signing_message(&self) -> Vec<u8> {
+    batch_message(self)
+  }
+}
+
+#[test]
+fn deposit_encoding() {
+  let instructions = [
+    InInstruction::Transfer(SeraiAddress::new([1; 32])),
+    InInstruction::Dex(DexCall::SwapAndAddLiquidity(SeraiAddress::new([2; 32]))),
+    InInstruction::Dex(DexCall::Swap(
+      Balance { coin: Coin::Bitcoin, amount: Amount(3) },
+      OutAddress::Serai(SeraiAddress::new([4; 32])),
+    )),
+    InInstruction::Dex(DexCall::Swap(
+      Balance { coin: Coin::Monero, amount: Amount(5) },
+      OutAddress::External(ExternalAddress::new(vec![6; 64]).unwrap()),
+    )),
+  ];
+
+  for instruction in instructions {
+    let encoded = instruction.encode_for_deposit();
+    assert_eq!(InInstruction::decode_from_deposit(&encoded), Some(instruction.clone()));
+    assert_eq!(
+      RefundableInInstruction::decode_from_deposit(&encoded),
+      Some(RefundableInInstruction { origin: None, instruction: instruction.clone() })
+    );
+    // Trailing data is ignored
+    assert_eq!(
+      InInstruction::decode_from_deposit(&[encoded.as_slice(), &[0]].concat()),
+      Some(instruction)
+    );
+  }
+  assert_eq!(InInstruction::decode_from_deposit(&[]), None);
+}
+
+#[test]
+fn signed_batch() {
+  let batch = Batch {
+    network: NetworkId::Monero,
+    id: 5,
+    block: BlockHash([0xaa; 32]),
+    instructions: vec![InInstructionWithBalance {
+      instruction: InInstruction::Transfer(SeraiAddress::new([1; 32])),
+      balance: Balance { coin: Coin::Monero, amount: Amount(1) },
+    }],
+  };
+  let signed = SignedBatch::new(batch.clone(), [0xbb; 64]);
+  assert_eq!(signed.batch, batch);
+  assert_eq!(signed.signature.0, [0xbb; 64]);
+
+  // The encoding, as used by execute_batch, is the batch followed by the signature
+  let encoded = signed.encode();
+  assert_eq!(encoded, [batch.encode().as_slice(), &[0xbb; 64]].concat());
+  assert_eq!(SignedBatch::decode_all(&mut encoded.as_slice()).unwrap(), signed);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn batch_signing_message() {
+  use sp_application_crypto::{Pair as PairTrait, sr25519::Pair};
+
+  let batch = Batch {
+    network: NetworkId::Bitcoin,
+    id: 3,
+    block: BlockHash([0xcc; 32]),
+    instructions: vec![InInstructionWithBalance {
+      instruction: InInstruction::Transfer(SeraiAddress::new([2; 32])),
+      balance: Balance { coin: Coin::Bitcoin, amount: Amount(7) },
+    }],
+  };
+  let message = batch.signing_message();
+  assert_eq!(message, batch_message(&batch));
+  assert!(message.starts_with(b"InInstructions-batch"));
+
+  // A signature over the message should verify for the batch
+  let pair = serai_primitives::insecure_pair_from_name("Alice");
+  let signed = SignedBatch::new(batch.clone(), pair.sign(&message).0);
+  assert!(Pair::verify(&signed.signature, signed.batch.signing_message(), &pair.public()));
+
+  // Yet not for a different batch
+  let mut other = batch;
+  other.id += 1;
+  assert!(!Pair::verify(&signed.signature, other.signing_message(), &pair.public()));
+}
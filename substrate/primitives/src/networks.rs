@@ -39,6 +39,21 @@ impl NetworkId {
 pub const NETWORKS: [NetworkId; 4] =
   [NetworkId::Serai, NetworkId::Bitcoin, NetworkId::Ethereum, NetworkId::Monero];
 
+/// The amount of confirmations a block on the specified network needs before it's considered
+/// final.
+///
+/// Bitcoin and Monero blocks are considered final after 6 and 10 confirmations respectively.
+/// Ethereum blocks are considered final after 64 confirmations (two epochs), and Serai blocks
+/// are final upon inclusion due to its finality gadget.
+pub const fn confirmations(network: NetworkId) -> u64 {
+  match network {
+    NetworkId::Serai => 1,
+    NetworkId::Bitcoin => 6,
+    NetworkId::Ethereum => 64,
+    NetworkId::Monero => 10,
+  }
+}
+
 pub const COINS: [Coin; 5] = [Coin::Serai, Coin::Bitcoin, Coin::Ether, Coin::Dai, Coin::Monero];
 
 /// The type used to identify coins.
@@ -61,6 +76,16 @@ impl Coin {
     Coin::Serai
   }
 
+  /// The native coin of the specified network.
+  pub fn native_for(network: NetworkId) -> Coin {
+    match network {
+      NetworkId::Serai => Coin::Serai,
+      NetworkId::Bitcoin => Coin::Bitcoin,
+      NetworkId::Ethereum => Coin::Ether,
+      NetworkId::Monero => Coin::Monero,
+    }
+  }
+
   pub fn network(&self) -> NetworkId {
     match self {
       Coin::Serai => NetworkId::Serai,
@@ -90,7 +115,7 @@ impl Coin {
     }
   }
 
-  pub fn decimals(&self) -> u32 {
+  pub fn decimals(&self) -> u8 {
     match self {
       // Ether and DAI have 18 decimals, yet we only track 8 in order to fit them within u64s
       Coin::Serai | Coin::Bitcoin | Coin::Ether | Coin::Dai => 8,
@@ -160,3 +185,36 @@ impl Network {
     &self.coins
   }
 }
+
+#[test]
+fn test_confirmations() {
+  assert_eq!(confirmations(NetworkId::Serai), 1);
+  assert_eq!(confirmations(NetworkId::Bitcoin), 6);
+  assert_eq!(confirmations(NetworkId::Ethereum), 64);
+  assert_eq!(confirmations(NetworkId::Monero), 10);
+  for network in NETWORKS {
+    assert!(confirmations(network) >= 1);
+  }
+}
+
+#[test]
+fn test_native_for() {
+  assert_eq!(Coin::native_for(NetworkId::Serai), Coin::Serai);
+  assert_eq!(Coin::native_for(NetworkId::Bitcoin), Coin::Bitcoin);
+  assert_eq!(Coin::native_for(NetworkId::Ethereum), Coin::Ether);
+  assert_eq!(Coin::native_for(NetworkId::Monero), Coin::Monero);
+  for network in NETWORKS {
+    let coin = Coin::native_for(network);
+    assert_eq!(coin.network(), network);
+    assert!(network.coins().contains(&coin));
+  }
+}
+
+#[test]
+fn test_decimals() {
+  assert_eq!(Coin::Serai.decimals(), 8);
+  assert_eq!(Coin::Bitcoin.decimals(), 8);
+  assert_eq!(Coin::Ether.decimals(), 8);
+  assert_eq!(Coin::Dai.decimals(), 8);
+  assert_eq!(Coin::Monero.decimals(), 12);
+}
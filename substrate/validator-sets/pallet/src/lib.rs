@@ -771,7 +771,7 @@ pub mod pallet {
       let sri_per_coin = Dex::<T>::security_oracle_value(balance.coin).unwrap_or(Amount(0));
 
       // See dex-pallet for the reasoning on these
-      let coin_decimals = balance.coin.decimals().max(5);
+      let coin_decimals = balance.coin.decimals().max(5).into();
       let accuracy_increase = HigherPrecisionBalance::from(SubstrateAmount::pow(10, coin_decimals));
 
       let total_coin_value = u64::try_from(
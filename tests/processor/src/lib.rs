@@ -59,9 +59,16 @@ pub fn processor_instance(
 }
 
 pub type Handles = (String, String, String);
+/// Create a processor stack, returning the handles, the coordinator's message-queue key, the
+/// processor's message-queue key, and the compositions.
 pub fn processor_stack(
   network: NetworkId,
-) -> (Handles, <Ristretto as Ciphersuite>::F, Vec<TestBodySpecification>) {
+) -> (
+  Handles,
+  <Ristretto as Ciphersuite>::F,
+  <Ristretto as Ciphersuite>::F,
+  Vec<TestBodySpecification>,
+) {
   let (network_composition, network_rpc_port) = network_instance(network);
 
   let (coord_key, message_queue_keys, message_queue_composition) =
@@ -116,7 +123,12 @@ pub fn processor_stack(
   processor_composition.inject_container_name(handles[0].clone(), "NETWORK_RPC_HOSTNAME");
   processor_composition.inject_container_name(handles[1].clone(), "MESSAGE_QUEUE_RPC");
 
-  ((handles[0].clone(), handles[1].clone(), handles[2].clone()), coord_key, compositions)
+  (
+    (handles[0].clone(), handles[1].clone(), handles[2].clone()),
+    coord_key,
+    message_queue_keys[&network],
+    compositions,
+  )
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -245,6 +257,17 @@ impl Coordinator {
     borsh::from_slice(&msg.msg).unwrap()
   }
 
+  /// Assert the processor doesn't send a message within the specified timeout.
+  ///
+  /// The message-queue is polled every five seconds, so the timeout should exceed that.
+  pub async fn assert_no_message(&mut self, timeout: core::time::Duration) {
+    if let Ok(msg) =
+      tokio::time::timeout(timeout, self.queue.next(Service::Processor(self.network))).await
+    {
+      panic!("processor sent an unexpected message: {msg:?}");
+    }
+  }
+
   pub async fn add_block(&self, ops: &DockerOperations) -> ([u8; 32], Vec<u8>) {
     let rpc_url = network_rpc(self.network, ops, &self.network_handle);
     match self.network {
@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 
 use zeroize::Zeroizing;
-use rand_core::{RngCore, OsRng};
+use rand_core::{RngCore, CryptoRng, OsRng};
 
 use scale::Encode;
 
@@ -66,16 +66,6 @@ pub fn network_rpc(network: NetworkId, ops: &DockerOperations, handle: &str) ->
   format!("http://{RPC_USER}:{RPC_PASS}@{ip}:{port}")
 }
 
-pub fn confirmations(network: NetworkId) -> usize {
-  use processor::networks::*;
-  match network {
-    NetworkId::Bitcoin => Bitcoin::CONFIRMATIONS,
-    NetworkId::Ethereum => todo!(),
-    NetworkId::Monero => Monero::CONFIRMATIONS,
-    NetworkId::Serai => panic!("getting confirmations required for Serai"),
-  }
-}
-
 #[derive(Clone)]
 pub enum Wallet {
   Bitcoin {
@@ -199,6 +189,7 @@ impl Wallet {
 
   pub async fn send_to_address(
     &mut self,
+    rng: &mut (impl RngCore + CryptoRng),
     ops: &DockerOperations,
     to: &ExternalKey,
     instruction: Option<InInstruction>,
@@ -309,7 +300,7 @@ impl Wallet {
           );
         }
         let mut decoys = Decoys::fingerprintable_canonical_select(
-          &mut OsRng,
+          rng,
           &rpc,
           Protocol::v16.ring_len(),
           rpc.get_height().await.unwrap(),
@@ -345,7 +336,7 @@ impl Wallet {
           rpc.get_fee(Protocol::v16, FeePriority::Unimportant).await.unwrap(),
         )
         .unwrap()
-        .sign(&mut OsRng, spend_key)
+        .sign(rng, spend_key)
         .unwrap();
 
         // Push the change output
@@ -1,8 +1,13 @@
+use core::future::Future;
 use std::{
+  sync::Mutex,
   collections::HashMap,
   time::{SystemTime, Duration},
 };
 
+use rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
 use dkg::{Participant, tests::clone_without};
 
 use messages::{coordinator::*, SubstrateContext};
@@ -10,6 +15,7 @@ use messages::{coordinator::*, SubstrateContext};
 use serai_client::{
   primitives::{
     BlockHash, Amount, Balance, crypto::RuntimePublic, PublicKey, SeraiAddress, NetworkId,
+    confirmations,
   },
   in_instructions::primitives::{
     InInstruction, InInstructionWithBalance, Batch, SignedBatch, batch_message,
@@ -79,11 +85,56 @@ pub(crate) async fn recv_batch_preprocesses(
   (id, preprocesses)
 }
 
+// The overall deadline for signing a batch, after which the test is failed
+const SIGN_BATCH_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A step of signing a batch, tracked to report how far signing got before timing out.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum SignBatchStep {
+  Started,
+  PreprocessesSent,
+  SharesReceived,
+  SharesSent,
+  BatchReceived,
+}
+
+// Run a flow with an overall deadline, panicking with the last step it completed on timeout
+async fn timeout_with_step<S: Copy + core::fmt::Debug, T>(
+  duration: Duration,
+  step: &Mutex<S>,
+  flow: impl Future<Output = T>,
+) -> T {
+  match tokio::time::timeout(duration, flow).await {
+    Ok(res) => res,
+    Err(_) => panic!(
+      "timed out after {}s, with the last completed step being {:?}",
+      duration.as_secs(),
+      *step.lock().unwrap()
+    ),
+  }
+}
+
 pub(crate) async fn sign_batch(
   coordinators: &mut [Coordinator],
   key: [u8; 32],
   id: SubstrateSignId,
   preprocesses: HashMap<Participant, [u8; 64]>,
+) -> SignedBatch {
+  let step = Mutex::new(SignBatchStep::Started);
+  timeout_with_step(
+    SIGN_BATCH_TIMEOUT,
+    &step,
+    sign_batch_steps(coordinators, key, id, preprocesses, &step),
+  )
+  .await
+}
+
+async fn sign_batch_steps(
+  coordinators: &mut [Coordinator],
+  key: [u8; 32],
+  id: SubstrateSignId,
+  preprocesses: HashMap<Participant, [u8; 64]>,
+  step: &Mutex<SignBatchStep>,
 ) -> SignedBatch {
   assert_eq!(preprocesses.len(), THRESHOLD);
 
@@ -99,6 +150,7 @@ pub(crate) async fn sign_batch(
         .await;
     }
   }
+  *step.lock().unwrap() = SignBatchStep::PreprocessesSent;
 
   let mut shares = HashMap::new();
   for (i, coordinator) in coordinators.iter_mut().enumerate() {
@@ -120,6 +172,7 @@ pub(crate) async fn sign_batch(
       }
     }
   }
+  *step.lock().unwrap() = SignBatchStep::SharesReceived;
 
   for (i, coordinator) in coordinators.iter_mut().enumerate() {
     let i = Participant::new(u16::try_from(i).unwrap() + 1).unwrap();
@@ -133,6 +186,7 @@ pub(crate) async fn sign_batch(
         .await;
     }
   }
+  *step.lock().unwrap() = SignBatchStep::SharesSent;
 
   // The selected processors should yield the batch
   let mut batch = None;
@@ -157,6 +211,7 @@ pub(crate) async fn sign_batch(
       }
     }
   }
+  *step.lock().unwrap() = SignBatchStep::BatchReceived;
   batch.unwrap()
 }
 
@@ -186,12 +241,93 @@ pub(crate) async fn substrate_block(
   }
 }
 
+/// Mine the specified amount of blocks, syncing every coordinator's node, and return the hash of
+/// the first block mined.
+///
+/// Mining `confirmations(network)` blocks after publishing a transaction puts it at the
+/// confirmation depth. Tests may mine a different amount to check the behavior around it.
+pub(crate) async fn mine_confirmations(
+  ops: &DockerOperations,
+  coordinators: &[Coordinator],
+  confirmations: u64,
+) -> [u8; 32] {
+  assert!(confirmations > 0, "mining zero confirmations");
+  let mut first = None;
+  for _ in 0 .. confirmations {
+    let (hash, _) = coordinators[0].add_block(ops).await;
+    first.get_or_insert(hash);
+  }
+  coordinators[0].sync(ops, &coordinators[1 ..]).await;
+  first.unwrap()
+}
+
+#[test]
+#[should_panic(expected = "with the last completed step being SharesReceived")]
+fn sign_batch_timeout_reports_step() {
+  tokio::runtime::Runtime::new().unwrap().block_on(async {
+    let step = Mutex::new(SignBatchStep::Started);
+    timeout_with_step(Duration::from_millis(100), &step, async {
+      *step.lock().unwrap() = SignBatchStep::PreprocessesSent;
+      *step.lock().unwrap() = SignBatchStep::SharesReceived;
+      // Stall, as a processor which never sends the signed batch would
+      core::future::pending::<()>().await;
+    })
+    .await;
+  });
+}
+
+/// The seed for the batch test's RNG, as hex, which may be specified to reproduce a prior run.
+const BATCH_TEST_SEED: &str = "BATCH_TEST_SEED";
+
+/// The seed for the batch test's RNG.
+///
+/// This is read from the environment if specified, and otherwise randomly generated. Either way,
+/// it's printed so a failing run can be reproduced.
+fn batch_test_seed() -> [u8; 32] {
+  let seed = if let Ok(seed) = std::env::var(BATCH_TEST_SEED) {
+    hex::decode(seed)
+      .ok()
+      .and_then(|seed| <[u8; 32]>::try_from(seed).ok())
+      .unwrap_or_else(|| panic!("{BATCH_TEST_SEED} wasn't 32 hex-encoded bytes"))
+  } else {
+    let mut seed = [0; 32];
+    OsRng.fill_bytes(&mut seed);
+    seed
+  };
+  println!("{BATCH_TEST_SEED}={}", hex::encode(seed));
+  seed
+}
+
+/// An instruction to transfer to a Serai address sampled from the RNG.
+pub(crate) fn transfer_instruction(rng: &mut impl RngCore) -> InInstruction {
+  let mut serai_address = [0; 32];
+  rng.fill_bytes(&mut serai_address);
+  InInstruction::Transfer(SeraiAddress(serai_address))
+}
+
+#[test]
+fn transfer_instruction_is_deterministic() {
+  use scale::Encode;
+
+  let mut seed = [0; 32];
+  OsRng.fill_bytes(&mut seed);
+  let instruction = |seed| transfer_instruction(&mut ChaCha20Rng::from_seed(seed)).encode();
+  assert_eq!(instruction(seed), instruction(seed));
+
+  let mut other_seed = seed;
+  other_seed[0] ^= 1;
+  assert!(instruction(seed) != instruction(other_seed));
+}
+
 #[test]
 fn batch_test() {
+  let seed = batch_test_seed();
   for network in [NetworkId::Bitcoin, NetworkId::Monero] {
     let (coordinators, test) = new_test(network);
 
     test.run(|ops| async move {
+      let mut rng = ChaCha20Rng::from_seed(seed);
+
       tokio::time::sleep(Duration::from_secs(1)).await;
 
       let mut coordinators = coordinators
@@ -219,27 +355,17 @@ fn batch_test() {
       // Run twice, once with an instruction and once without
       let substrate_block_num = (OsRng.next_u64() % 4_000_000_000u64) + 1;
       for i in 0 .. 2 {
-        let mut serai_address = [0; 32];
-        OsRng.fill_bytes(&mut serai_address);
-        let instruction =
-          if i == 0 { Some(InInstruction::Transfer(SeraiAddress(serai_address))) } else { None };
+        let instruction = if i == 0 { Some(transfer_instruction(&mut rng)) } else { None };
 
         // Send into the processor's wallet
         let (tx, balance_sent) =
-          wallet.send_to_address(&ops, &key_pair.1, instruction.clone()).await;
+          wallet.send_to_address(&mut rng, &ops, &key_pair.1, instruction.clone()).await;
         for coordinator in &mut coordinators {
           coordinator.publish_transacton(&ops, &tx).await;
         }
 
         // Put the TX past the confirmation depth
-        let mut block_with_tx = None;
-        for _ in 0 .. confirmations(network) {
-          let (hash, _) = coordinators[0].add_block(&ops).await;
-          if block_with_tx.is_none() {
-            block_with_tx = Some(hash);
-          }
-        }
-        coordinators[0].sync(&ops, &coordinators[1 ..]).await;
+        let block_with_tx = mine_confirmations(&ops, &coordinators, confirmations(network)).await;
 
         // Sleep for 10s
         // The scanner works on a 5s interval, so this leaves a few s for any processing/latency
@@ -248,7 +374,7 @@ fn batch_test() {
         let expected_batch = Batch {
           network,
           id: i,
-          block: BlockHash(block_with_tx.unwrap()),
+          block: BlockHash(block_with_tx),
           instructions: if let Some(instruction) = &instruction {
             vec![InInstructionWithBalance {
               instruction: instruction.clone(),
@@ -347,3 +473,50 @@ fn batch_test() {
     });
   }
 }
+
+#[test]
+fn batch_awaits_confirmations() {
+  for network in [NetworkId::Bitcoin, NetworkId::Monero] {
+    let (coordinators, test) = new_test(network);
+
+    test.run(|ops| async move {
+      tokio::time::sleep(Duration::from_secs(1)).await;
+
+      let mut coordinators = coordinators
+        .into_iter()
+        .map(|(handles, key)| Coordinator::new(network, &ops, handles, key))
+        .collect::<Vec<_>>();
+
+      let mut wallet = Wallet::new(network, &ops, coordinators[0].network_handle.clone()).await;
+      coordinators[0].sync(&ops, &coordinators[1 ..]).await;
+
+      // Generate keys and activate them
+      let key_pair = key_gen(&mut coordinators).await;
+      for _ in 0 .. (10 * confirmations(network)) {
+        coordinators[0].add_block(&ops).await;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+      }
+      coordinators[0].sync(&ops, &coordinators[1 ..]).await;
+
+      let (tx, _) = wallet.send_to_address(&mut OsRng, &ops, &key_pair.1, None).await;
+      for coordinator in &mut coordinators {
+        coordinator.publish_transacton(&ops, &tx).await;
+      }
+
+      // Mine one block short of the confirmation depth
+      let block_with_tx = mine_confirmations(&ops, &coordinators, confirmations(network) - 1).await;
+
+      // No batch should be produced yet
+      for coordinator in &mut coordinators {
+        coordinator.assert_no_message(Duration::from_secs(10)).await;
+      }
+
+      // Once the final confirmation is mined, the batch should be produced
+      mine_confirmations(&ops, &coordinators, 1).await;
+      tokio::time::sleep(Duration::from_secs(10)).await;
+      let expected_batch =
+        Batch { network, id: 0, block: BlockHash(block_with_tx), instructions: vec![] };
+      recv_batch_preprocesses(&mut coordinators, Session(0), &expected_batch, 0).await;
+    });
+  }
+}
@@ -159,6 +159,11 @@ fn key_gen_test() {
         .collect::<Vec<_>>();
 
       key_gen(&mut coordinators).await;
+
+      // Confirming the key pair shouldn't cause the processors to send anything further
+      for coordinator in &mut coordinators {
+        coordinator.assert_no_message(core::time::Duration::from_secs(10)).await;
+      }
     });
   }
 }
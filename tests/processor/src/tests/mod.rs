@@ -1,7 +1,12 @@
+use zeroize::Zeroizing;
+use rand_core::{RngCore, OsRng};
+
 use ciphersuite::{Ciphersuite, Ristretto};
 
 use serai_client::primitives::NetworkId;
 
+use serai_message_queue::{Service, Metadata, client::MessageQueue};
+
 use dockertest::DockerTest;
 
 use crate::*;
@@ -10,7 +15,7 @@ mod key_gen;
 pub(crate) use key_gen::key_gen;
 
 mod batch;
-pub(crate) use batch::{recv_batch_preprocesses, sign_batch, substrate_block};
+pub(crate) use batch::{recv_batch_preprocesses, sign_batch, substrate_block, mine_confirmations};
 
 mod send;
 
@@ -21,7 +26,7 @@ fn new_test(network: NetworkId) -> (Vec<(Handles, <Ristretto as Ciphersuite>::F)
   let mut coordinators = vec![];
   let mut test = DockerTest::new().with_network(dockertest::Network::Isolated);
   for _ in 0 .. COORDINATORS {
-    let (handles, coord_key, compositions) = processor_stack(network);
+    let (handles, coord_key, _, compositions) = processor_stack(network);
     coordinators.push((handles, coord_key));
     for composition in compositions {
       test.provide_container(composition);
@@ -29,3 +34,36 @@ fn new_test(network: NetworkId) -> (Vec<(Handles, <Ristretto as Ciphersuite>::F)
   }
   (coordinators, test)
 }
+
+#[test]
+#[should_panic(expected = "processor sent an unexpected message")]
+fn assert_no_message_detects_stray_message() {
+  let network = NetworkId::Bitcoin;
+  let (handles, coord_key, processor_key, compositions) = processor_stack(network);
+  let mut test = DockerTest::new().with_network(dockertest::Network::Isolated);
+  for composition in compositions {
+    test.provide_container(composition);
+  }
+
+  test.run(|ops| async move {
+    tokio::time::sleep(core::time::Duration::from_secs(1)).await;
+
+    let rpc = ops.handle(&handles.1).host_port(2287).unwrap();
+    let rpc = rpc.0.to_string() + ":" + &rpc.1.to_string();
+    let mut coordinator = Coordinator::new(network, &ops, handles, coord_key);
+
+    // Act as a misbehaving processor, sending a message the coordinator never solicited
+    let processor =
+      MessageQueue::new(Service::Processor(network), rpc, Zeroizing::new(processor_key));
+    let mut intent = vec![0; 32];
+    OsRng.fill_bytes(&mut intent);
+    processor
+      .queue(
+        Metadata { from: Service::Processor(network), to: Service::Coordinator, intent },
+        vec![],
+      )
+      .await;
+
+    coordinator.assert_no_message(core::time::Duration::from_secs(20)).await;
+  });
+}
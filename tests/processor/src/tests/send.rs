@@ -8,7 +8,7 @@ use dkg::{Participant, tests::clone_without};
 use messages::{sign::SignId, SubstrateContext};
 
 use serai_client::{
-  primitives::{BlockHash, NetworkId},
+  primitives::{BlockHash, NetworkId, confirmations},
   coins::primitives::{OutInstruction, OutInstructionWithBalance},
   in_instructions::primitives::Batch,
   validator_sets::primitives::Session,
@@ -173,27 +173,20 @@ fn send_test() {
       coordinators[0].sync(&ops, &coordinators[1 ..]).await;
 
       // Send into the processor's wallet
-      let (tx, balance_sent) = wallet.send_to_address(&ops, &key_pair.1, None).await;
+      let (tx, balance_sent) = wallet.send_to_address(&mut OsRng, &ops, &key_pair.1, None).await;
       for coordinator in &mut coordinators {
         coordinator.publish_transacton(&ops, &tx).await;
       }
 
       // Put the TX past the confirmation depth
-      let mut block_with_tx = None;
-      for _ in 0 .. confirmations(network) {
-        let (hash, _) = coordinators[0].add_block(&ops).await;
-        if block_with_tx.is_none() {
-          block_with_tx = Some(hash);
-        }
-      }
-      coordinators[0].sync(&ops, &coordinators[1 ..]).await;
+      let block_with_tx = mine_confirmations(&ops, &coordinators, confirmations(network)).await;
 
       // Sleep for 10s
       // The scanner works on a 5s interval, so this leaves a few s for any processing/latency
       tokio::time::sleep(Duration::from_secs(10)).await;
 
       let expected_batch =
-        Batch { network, id: 0, block: BlockHash(block_with_tx.unwrap()), instructions: vec![] };
+        Batch { network, id: 0, block: BlockHash(block_with_tx), instructions: vec![] };
 
       // Make sure the proceessors picked it up by checking they're trying to sign a batch for it
       let (id, preprocesses) =